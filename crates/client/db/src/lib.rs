@@ -40,7 +40,7 @@ pub(crate) mod columns {
 
     pub const META: u32 = 0;
     pub const BLOCK_MAPPING: u32 = 1;
-    // pub const TRANSACTION_MAPPING: u32 = 2;
+    pub const TRANSACTION_MAPPING: u32 = 2;
     pub const SYNCED_MAPPING: u32 = 3;
 }
 
@@ -51,7 +51,7 @@ pub mod static_keys {
 /// The Madara client database backend
 ///
 /// Contains two distinct databases: `meta` and `mapping`.
-/// `mapping` is used to map Starknet blocks to Substrate ones.
+/// `mapping` is used to map Starknet blocks and transactions to Substrate blocks.
 /// `meta` is used to store data about the current state of the chain
 pub struct Backend<B: BlockT> {
     meta: Arc<MetaDb<B>>,
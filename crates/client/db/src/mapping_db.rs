@@ -14,6 +14,7 @@ use crate::DbHash;
 pub struct MappingCommitment<B: BlockT> {
     pub block_hash: B::Hash,
     pub starknet_block_hash: H256,
+    pub starknet_transaction_hashes: Vec<H256>,
 }
 
 /// Allow interaction with the mapping db
@@ -43,6 +44,17 @@ impl<B: BlockT> MappingDb<B> {
         }
     }
 
+    /// Return the hashes of the Substrate blocks wrapping the Starknet transaction with given hash
+    ///
+    /// As for `block_hash`, multiple hashes can be returned, the canonical one has to be found by
+    /// the caller.
+    pub fn transaction_block_hash(&self, starknet_transaction_hash: &H256) -> Result<Option<Vec<B::Hash>>, String> {
+        match self.db.get(crate::columns::TRANSACTION_MAPPING, &starknet_transaction_hash.encode()) {
+            Some(raw) => Ok(Some(Vec::<B::Hash>::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?)),
+            None => Ok(None),
+        }
+    }
+
     /// Register that a Substrate block has been seen, without it containing a Starknet one
     pub fn write_none(&self, block_hash: B::Hash) -> Result<(), String> {
         let _lock = self.write_lock.lock();
@@ -82,6 +94,21 @@ impl<B: BlockT> MappingDb<B> {
             &substrate_hashes.encode(),
         );
 
+        for starknet_transaction_hash in commitment.starknet_transaction_hashes.iter() {
+            let substrate_hashes = match self.transaction_block_hash(starknet_transaction_hash) {
+                Ok(Some(mut data)) => {
+                    data.push(commitment.block_hash);
+                    data
+                }
+                _ => vec![commitment.block_hash],
+            };
+            transaction.set(
+                crate::columns::TRANSACTION_MAPPING,
+                &starknet_transaction_hash.encode(),
+                &substrate_hashes.encode(),
+            );
+        }
+
         transaction.set(crate::columns::SYNCED_MAPPING, &commitment.block_hash.encode(), &true.encode());
 
         self.db.commit(transaction).map_err(|e| format!("{:?}", e))?;
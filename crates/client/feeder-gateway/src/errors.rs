@@ -0,0 +1,42 @@
+use hyper::StatusCode;
+
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeederGatewayError {
+    #[error("Block not found")]
+    BlockNotFound,
+    #[error("Class hash not declared")]
+    UndeclaredClass,
+    #[error("Malformed request")]
+    MalformedRequest,
+    #[error("Transaction rejected by the transaction pool")]
+    TransactionRejected,
+    #[error("Endpoint not found")]
+    NotFound,
+    #[error("Internal server error")]
+    InternalServerError,
+}
+
+impl FeederGatewayError {
+    /// The error code returned by the Starknet sequencer for this error
+    pub fn code(&self) -> &'static str {
+        match self {
+            FeederGatewayError::BlockNotFound => "StarknetErrorCode.BLOCK_NOT_FOUND",
+            FeederGatewayError::UndeclaredClass => "StarknetErrorCode.UNDECLARED_CLASS",
+            FeederGatewayError::MalformedRequest | FeederGatewayError::NotFound => "StarkErrorCode.MALFORMED_REQUEST",
+            FeederGatewayError::TransactionRejected => "StarknetErrorCode.TRANSACTION_FAILED",
+            FeederGatewayError::InternalServerError => "StarkErrorCode.INTERNAL_ERROR",
+        }
+    }
+
+    /// The HTTP status of the response
+    pub fn status(&self) -> StatusCode {
+        match self {
+            FeederGatewayError::BlockNotFound
+            | FeederGatewayError::UndeclaredClass
+            | FeederGatewayError::MalformedRequest
+            | FeederGatewayError::TransactionRejected => StatusCode::BAD_REQUEST,
+            FeederGatewayError::NotFound => StatusCode::NOT_FOUND,
+            FeederGatewayError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
@@ -0,0 +1,583 @@
+//! Starknet feeder gateway compatible HTTP server
+//!
+//! Serves the subset of the Starknet sequencer HTTP API used by existing tooling to sync from a
+//! node and to submit transactions:
+//! - `GET /feeder_gateway/get_block`
+//! - `GET /feeder_gateway/get_state_update`
+//! - `GET /feeder_gateway/get_class_by_hash`
+//! - `POST /gateway/add_transaction`
+//!
+//! Data is read from the same sources as the JSON-RPC server. Blocks are selected with the
+//! `blockNumber` or `blockHash` query parameters, and default to the latest block. As the node has
+//! no pending block, `blockNumber=pending` is also served the latest block.
+
+mod errors;
+pub mod types;
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+pub use errors::FeederGatewayError;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use log::{error, info};
+use mc_rpc::madara_backend_client;
+use mc_rpc_core::{BlockId as StarknetBlockId, BlockTag, FieldElement};
+use mc_storage::OverrideHandle;
+use mp_starknet::block::{Block as StarknetBlock, BlockTransactions};
+use mp_starknet::execution::{ClassHashWrapper, ContractAddressWrapper};
+use mp_starknet::transaction::types::{
+    EventWrapper, MessageToL1Wrapper, Transaction, TransactionExecutionStatus, TransactionReceiptWrapper,
+};
+use pallet_starknet::runtime_api::StarknetRuntimeApi;
+use sc_client_api::backend::{Backend, StorageProvider};
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_arithmetic::traits::UniqueSaturatedInto;
+use sp_blockchain::HeaderBackend;
+use sp_core::{H256, U256};
+use sp_runtime::generic::BlockId as SubstrateBlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use types::{
+    AddTransaction, AddTransactionResponse, Block, BlockStatus, ContractClass, DeclaredClass, DeployedContract,
+    ErrorResponse, Event, ExecutionStatus, L2ToL1Message, StateDiff, StateUpdate, StorageDiff, TransactionReceipt,
+};
+
+/// A Starknet feeder gateway server for Madara
+pub struct FeederGateway<B: BlockT, BE, C, P> {
+    client: Arc<C>,
+    backend: Arc<mc_db::Backend<B>>,
+    overrides: Arc<OverrideHandle<B>>,
+    pool: Arc<P>,
+    _marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, BE, C, P> FeederGateway<B, BE, C, P> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<mc_db::Backend<B>>,
+        overrides: Arc<OverrideHandle<B>>,
+        pool: Arc<P>,
+    ) -> Self {
+        Self { client, backend, overrides, pool, _marker: PhantomData }
+    }
+}
+
+impl<B, BE, C, P> FeederGateway<B, BE, C, P>
+where
+    B: BlockT,
+    BE: Backend<B> + 'static,
+    C: HeaderBackend<B> + StorageProvider<B, BE> + Send + Sync + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
+{
+    /// Serves the feeder gateway on the given address, until the server fails
+    pub async fn run(self, addr: SocketAddr) {
+        let gateway = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let gateway = gateway.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let gateway = gateway.clone();
+                    async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+                }))
+            }
+        });
+
+        let server = match Server::try_bind(&addr) {
+            Ok(builder) => builder.serve(make_service),
+            Err(e) => {
+                error!("Failed to bind the feeder gateway to {addr}: {e}");
+                return;
+            }
+        };
+        info!("Feeder gateway listening on http://{addr}");
+
+        if let Err(e) = server.await {
+            error!("Feeder gateway server failed: {e}");
+        }
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let query = parse_query(request.uri().query());
+
+        let result = match (&method, path.as_str()) {
+            (&Method::GET, "/feeder_gateway/get_block") => self.get_block(&query),
+            (&Method::GET, "/feeder_gateway/get_state_update") => self.get_state_update(&query),
+            (&Method::GET, "/feeder_gateway/get_class_by_hash") => self.get_class_by_hash(&query),
+            (&Method::POST, "/gateway/add_transaction") => self.add_transaction(request).await,
+            _ => Err(FeederGatewayError::NotFound),
+        };
+
+        result.unwrap_or_else(error_response)
+    }
+
+    fn get_block(&self, query: &HashMap<String, String>) -> Result<Response<Body>, FeederGatewayError> {
+        let substrate_block_hash = self.substrate_block_hash(block_id(query)?)?;
+        let block = self
+            .overrides
+            .for_block_hash(self.client.as_ref(), substrate_block_hash)
+            .current_block(substrate_block_hash)
+            .ok_or(FeederGatewayError::BlockNotFound)?;
+
+        let receipts = self.client.runtime_api().transaction_receipts(substrate_block_hash).map_err(|e| {
+            error!("Failed to get the receipts of block {substrate_block_hash}: {:?}", e);
+            FeederGatewayError::InternalServerError
+        })?;
+
+        let header = block.header();
+        json_response(
+            StatusCode::OK,
+            &Block {
+                block_hash: header.hash().into(),
+                parent_block_hash: header.parent_block_hash.into(),
+                block_number: header.block_number.as_u64(),
+                state_root: header.global_state_root.into(),
+                status: BlockStatus::AcceptedOnL2,
+                timestamp: header.block_timestamp,
+                sequencer_address: header.sequencer_address.into(),
+                transactions: block_transactions(&block)?
+                    .into_iter()
+                    .map(|transaction| mc_rpc::to_rpc_transaction(transaction).into())
+                    .collect(),
+                transaction_receipts: receipts.into_iter().enumerate().map(to_transaction_receipt).collect(),
+            },
+        )
+    }
+
+    fn get_state_update(&self, query: &HashMap<String, String>) -> Result<Response<Body>, FeederGatewayError> {
+        let substrate_block_hash = self.substrate_block_hash(block_id(query)?)?;
+        let block = self
+            .overrides
+            .for_block_hash(self.client.as_ref(), substrate_block_hash)
+            .current_block(substrate_block_hash)
+            .ok_or(FeederGatewayError::BlockNotFound)?;
+
+        let parent_hash = *self
+            .client
+            .header(substrate_block_hash)
+            .map_err(|e| {
+                error!("Failed to get the header of block {substrate_block_hash}: {e}");
+                FeederGatewayError::InternalServerError
+            })?
+            .ok_or(FeederGatewayError::BlockNotFound)?
+            .parent_hash();
+        // The genesis block has no parent, its state update starts from an empty state
+        let old_root = self
+            .overrides
+            .for_block_hash(self.client.as_ref(), parent_hash)
+            .current_block(parent_hash)
+            .map(|parent| parent.header().global_state_root)
+            .unwrap_or_default();
+
+        let storage_diffs = self.client.runtime_api().storage_diffs(substrate_block_hash).map_err(|e| {
+            error!("Failed to get the storage diffs of block {substrate_block_hash}: {:?}", e);
+            FeederGatewayError::InternalServerError
+        })?;
+        let declared_classes = self.client.runtime_api().declared_classes(substrate_block_hash).map_err(|e| {
+            error!("Failed to get the classes declared in block {substrate_block_hash}: {:?}", e);
+            FeederGatewayError::InternalServerError
+        })?;
+
+        let header = block.header();
+        json_response(
+            StatusCode::OK,
+            &StateUpdate {
+                block_hash: header.hash().into(),
+                new_root: header.global_state_root.into(),
+                old_root: old_root.into(),
+                state_diff: to_state_diff(&block_transactions(&block)?, storage_diffs, declared_classes),
+            },
+        )
+    }
+
+    fn get_class_by_hash(&self, query: &HashMap<String, String>) -> Result<Response<Body>, FeederGatewayError> {
+        let class_hash: FieldElement = query
+            .get("classHash")
+            .ok_or(FeederGatewayError::MalformedRequest)?
+            .parse()
+            .map_err(|_| FeederGatewayError::MalformedRequest)?;
+        let substrate_block_hash = self.substrate_block_hash(block_id(query)?)?;
+
+        let contract_class = self
+            .client
+            .runtime_api()
+            .contract_class_by_class_hash(substrate_block_hash, class_hash.into())
+            .map_err(|e| {
+                error!("Failed to get the contract class {class_hash}: {:?}", e);
+                FeederGatewayError::InternalServerError
+            })?
+            .ok_or(FeederGatewayError::UndeclaredClass)?;
+
+        let decode = |bytes: &[u8]| {
+            serde_json::from_slice(bytes).map_err(|e| {
+                error!("Failed to decode the contract class {class_hash}: {e}");
+                FeederGatewayError::InternalServerError
+            })
+        };
+        json_response(
+            StatusCode::OK,
+            &ContractClass {
+                program: decode(contract_class.program.as_ref())?,
+                entry_points_by_type: decode(contract_class.entry_points_by_type.as_ref())?,
+                // TODO: return the abi once it is stored with the contract class
+                abi: Vec::new(),
+            },
+        )
+    }
+
+    async fn add_transaction(&self, request: Request<Body>) -> Result<Response<Body>, FeederGatewayError> {
+        let body = hyper::body::to_bytes(request.into_body()).await.map_err(|e| {
+            error!("Failed to read the transaction: {e}");
+            FeederGatewayError::MalformedRequest
+        })?;
+        let transaction: AddTransaction = serde_json::from_slice(&body).map_err(|e| {
+            error!("Failed to decode the transaction: {e}");
+            FeederGatewayError::MalformedRequest
+        })?;
+        let transaction = mc_rpc::to_starknet_transaction(transaction.into()).map_err(|e| {
+            error!("Failed to convert the transaction: {:?}", e);
+            FeederGatewayError::MalformedRequest
+        })?;
+
+        let transaction_hash = transaction.hash().into();
+        let address = match &transaction {
+            Transaction::DeployAccount(tx) => Some(tx.contract_address.into()),
+            _ => None,
+        };
+
+        let best_block_hash = self.client.info().best_hash;
+        let extrinsic = self.client.runtime_api().convert_transaction(best_block_hash, transaction).map_err(|e| {
+            error!("Failed to convert the transaction to an extrinsic: {:?}", e);
+            FeederGatewayError::InternalServerError
+        })?;
+        self.pool
+            .submit_one(&SubstrateBlockId::hash(best_block_hash), TransactionSource::External, extrinsic)
+            .await
+            .map_err(|e| {
+                error!("Failed to submit the transaction: {e}");
+                FeederGatewayError::TransactionRejected
+            })?;
+
+        json_response(
+            StatusCode::OK,
+            &AddTransactionResponse { code: "TRANSACTION_RECEIVED", transaction_hash, address },
+        )
+    }
+
+    /// Returns the substrate block hash of a Starknet block id
+    fn substrate_block_hash(&self, block_id: StarknetBlockId) -> Result<B::Hash, FeederGatewayError> {
+        match block_id {
+            StarknetBlockId::BlockHash(h) => {
+                madara_backend_client::load_hash(self.client.as_ref(), &self.backend, h.into()).map_err(|e| {
+                    error!("Failed to load the Starknet block hash {h}: {e}");
+                    FeederGatewayError::InternalServerError
+                })?
+            }
+            StarknetBlockId::BlockNumber(n) => {
+                self.client.hash(UniqueSaturatedInto::unique_saturated_into(n)).map_err(|e| {
+                    error!("Failed to get the hash of block {n}: {e}");
+                    FeederGatewayError::InternalServerError
+                })?
+            }
+            StarknetBlockId::BlockTag(_) => Some(self.client.info().best_hash),
+        }
+        .ok_or(FeederGatewayError::BlockNotFound)
+    }
+}
+
+/// Parses the query string of a request
+///
+/// Values are not percent-decoded, as the gateway parameters are numbers and hexadecimal strings.
+fn parse_query(query: Option<&str>) -> HashMap<String, String> {
+    query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Returns the block selected by the `blockHash` or `blockNumber` query parameters
+fn block_id(query: &HashMap<String, String>) -> Result<StarknetBlockId, FeederGatewayError> {
+    if let Some(block_hash) = query.get("blockHash") {
+        return block_hash.parse().map(StarknetBlockId::BlockHash).map_err(|_| FeederGatewayError::MalformedRequest);
+    }
+
+    match query.get("blockNumber").map(String::as_str) {
+        None | Some("latest") => Ok(StarknetBlockId::BlockTag(BlockTag::Latest)),
+        Some("pending") => Ok(StarknetBlockId::BlockTag(BlockTag::Pending)),
+        Some(block_number) => {
+            block_number.parse().map(StarknetBlockId::BlockNumber).map_err(|_| FeederGatewayError::MalformedRequest)
+        }
+    }
+}
+
+/// Returns the transactions of a Starknet block
+fn block_transactions(block: &StarknetBlock) -> Result<Vec<Transaction>, FeederGatewayError> {
+    match block.transactions() {
+        BlockTransactions::Full(transactions) => Ok(transactions.to_vec()),
+        BlockTransactions::Hashes(_) => {
+            error!("Block {} is stored without its transactions", block.header().block_number);
+            Err(FeederGatewayError::InternalServerError)
+        }
+    }
+}
+
+/// Converts the receipt of the transaction at the given index of its block
+fn to_transaction_receipt((transaction_index, receipt): (usize, TransactionReceiptWrapper)) -> TransactionReceipt {
+    TransactionReceipt {
+        transaction_hash: receipt.transaction_hash.into(),
+        transaction_index: transaction_index as u64,
+        actual_fee: receipt.actual_fee.into(),
+        events: receipt.events.iter().map(to_event).collect(),
+        l2_to_l1_messages: receipt.messages_sent.iter().map(to_l2_to_l1_message).collect(),
+        execution_status: match receipt.execution_status {
+            TransactionExecutionStatus::Succeeded => ExecutionStatus::Succeeded,
+            TransactionExecutionStatus::Reverted => ExecutionStatus::Reverted,
+        },
+        revert_error: receipt.revert_reason.map(|revert_reason| String::from_utf8_lossy(&revert_reason).into_owned()),
+    }
+}
+
+/// Builds the state diff of a block from its transactions, its storage writes and its declared
+/// classes
+///
+/// Only the last write of a storage var in the block is reported.
+fn to_state_diff(
+    transactions: &[Transaction],
+    storage_diffs: Vec<(ContractAddressWrapper, H256, U256)>,
+    declared_classes: Vec<(ClassHashWrapper, Option<ClassHashWrapper>)>,
+) -> StateDiff {
+    let mut state_diff = StateDiff::default();
+    for (address, key, value) in storage_diffs {
+        let (key, value) = (FieldElement::from(key), FieldElement::from(value));
+        let diffs = state_diff.storage_diffs.entry(address.into()).or_default();
+        match diffs.iter_mut().find(|diff| diff.key == key) {
+            Some(diff) => diff.value = value,
+            None => diffs.push(StorageDiff { key, value }),
+        }
+    }
+
+    for transaction in transactions {
+        match transaction {
+            Transaction::DeployAccount(tx) => state_diff
+                .deployed_contracts
+                .push(DeployedContract { address: tx.contract_address.into(), class_hash: tx.class_hash.into() }),
+            Transaction::Declare(_) | Transaction::Invoke(_) | Transaction::L1Handler(_) => {}
+        }
+        if transaction.uses_account_nonce() {
+            state_diff
+                .nonces
+                .insert(transaction.sender_address().into(), transaction.nonce().saturating_add(U256::one()).into());
+        }
+    }
+
+    for (class_hash, compiled_class_hash) in declared_classes {
+        match compiled_class_hash {
+            Some(compiled_class_hash) => state_diff
+                .declared_classes
+                .push(DeclaredClass { class_hash: class_hash.into(), compiled_class_hash: compiled_class_hash.into() }),
+            None => state_diff.old_declared_contracts.push(class_hash.into()),
+        }
+    }
+    state_diff
+}
+
+fn to_event(event: &EventWrapper) -> Event {
+    Event {
+        from_address: event.from_address.into(),
+        keys: event.keys.iter().map(|k| FieldElement::from(*k)).collect(),
+        data: event.data.iter().map(|d| FieldElement::from(*d)).collect(),
+    }
+}
+
+fn to_l2_to_l1_message(message: &MessageToL1Wrapper) -> L2ToL1Message {
+    L2ToL1Message {
+        from_address: message.from_address.into(),
+        to_address: H256::from(message.to_address).into(),
+        payload: message.payload.iter().map(|x| FieldElement::from(*x)).collect(),
+    }
+}
+
+/// Returns the response of a failed request, in the format of the Starknet sequencer
+fn error_response(error: FeederGatewayError) -> Response<Body> {
+    json_response(error.status(), &ErrorResponse { code: error.code(), message: error.to_string() }).unwrap_or_else(
+        |_| {
+            let mut response = Response::default();
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        },
+    )
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Result<Response<Body>, FeederGatewayError> {
+    let body = serde_json::to_vec(body).map_err(|e| {
+        error!("Failed to serialize the response: {e}");
+        FeederGatewayError::InternalServerError
+    })?;
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use mp_starknet::transaction::types::{DeployAccountTransaction, InvokeTransaction};
+
+    use super::*;
+
+    #[test]
+    fn parses_block_ids() {
+        assert_eq!(block_id(&parse_query(None)), Ok(StarknetBlockId::BlockTag(BlockTag::Latest)));
+        assert_eq!(
+            block_id(&parse_query(Some("blockNumber=pending"))),
+            Ok(StarknetBlockId::BlockTag(BlockTag::Pending))
+        );
+        assert_eq!(block_id(&parse_query(Some("blockNumber=12"))), Ok(StarknetBlockId::BlockNumber(12)));
+        assert_eq!(
+            block_id(&parse_query(Some("blockHash=0xabc&blockNumber=12"))),
+            Ok(StarknetBlockId::BlockHash(FieldElement::from(0xabc_u64)))
+        );
+        assert_eq!(block_id(&parse_query(Some("blockNumber=abc"))), Err(FeederGatewayError::MalformedRequest));
+        assert_eq!(block_id(&parse_query(Some("blockHash=abc"))), Err(FeederGatewayError::MalformedRequest));
+    }
+
+    #[test]
+    fn block_receipts_report_fees_events_messages_and_reverts() {
+        let event = EventWrapper {
+            keys: vec![H256::from_low_u64_be(2)].try_into().unwrap(),
+            data: vec![H256::from_low_u64_be(3)].try_into().unwrap(),
+            from_address: H256::from_low_u64_be(1).to_fixed_bytes(),
+        };
+        let message = MessageToL1Wrapper {
+            from_address: H256::from_low_u64_be(1).to_fixed_bytes(),
+            to_address: sp_core::H160::from_low_u64_be(4),
+            payload: vec![H256::from_low_u64_be(5)].try_into().unwrap(),
+        };
+        let receipts = vec![
+            TransactionReceiptWrapper {
+                transaction_hash: H256::from_low_u64_be(10),
+                actual_fee: U256::from(1_000),
+                events: vec![event].try_into().unwrap(),
+                messages_sent: vec![message].try_into().unwrap(),
+                ..TransactionReceiptWrapper::default()
+            },
+            TransactionReceiptWrapper {
+                transaction_hash: H256::from_low_u64_be(11),
+                actual_fee: U256::from(500),
+                execution_status: TransactionExecutionStatus::Reverted,
+                revert_reason: Some(b"out of gas".to_vec().try_into().unwrap()),
+                ..TransactionReceiptWrapper::default()
+            },
+        ];
+
+        let receipts: Vec<TransactionReceipt> = receipts.into_iter().enumerate().map(to_transaction_receipt).collect();
+        assert_eq!(
+            receipts,
+            vec![
+                TransactionReceipt {
+                    transaction_hash: FieldElement::from(10_u64),
+                    transaction_index: 0,
+                    actual_fee: FieldElement::from(1_000_u64),
+                    events: vec![Event {
+                        from_address: FieldElement::from(1_u64),
+                        keys: vec![FieldElement::from(2_u64)],
+                        data: vec![FieldElement::from(3_u64)],
+                    }],
+                    l2_to_l1_messages: vec![L2ToL1Message {
+                        from_address: FieldElement::from(1_u64),
+                        to_address: FieldElement::from(4_u64),
+                        payload: vec![FieldElement::from(5_u64)],
+                    }],
+                    execution_status: ExecutionStatus::Succeeded,
+                    revert_error: None,
+                },
+                TransactionReceipt {
+                    transaction_hash: FieldElement::from(11_u64),
+                    transaction_index: 1,
+                    actual_fee: FieldElement::from(500_u64),
+                    execution_status: ExecutionStatus::Reverted,
+                    revert_error: Some("out of gas".to_string()),
+                    ..TransactionReceipt::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn state_diffs_report_the_last_write_of_each_storage_var() {
+        let contract = H256::from_low_u64_be(1).to_fixed_bytes();
+        let storage_diffs = vec![
+            (contract, H256::from_low_u64_be(2), U256::from(3)),
+            (contract, H256::from_low_u64_be(4), U256::from(5)),
+            (contract, H256::from_low_u64_be(2), U256::from(6)),
+        ];
+
+        let state_diff = to_state_diff(&[], storage_diffs, Vec::new());
+        assert_eq!(
+            state_diff.storage_diffs,
+            HashMap::from([(
+                FieldElement::from(1_u64),
+                vec![
+                    StorageDiff { key: FieldElement::from(2_u64), value: FieldElement::from(6_u64) },
+                    StorageDiff { key: FieldElement::from(4_u64), value: FieldElement::from(5_u64) },
+                ]
+            )])
+        );
+    }
+
+    #[test]
+    fn state_diffs_report_deployed_contracts_nonces_and_declared_classes() {
+        let account = H256::from_low_u64_be(1).to_fixed_bytes();
+        let transactions = vec![
+            Transaction::from(DeployAccountTransaction {
+                contract_address: account,
+                class_hash: H256::from_low_u64_be(2).to_fixed_bytes(),
+                nonce: U256::zero(),
+                ..DeployAccountTransaction::default()
+            }),
+            Transaction::from(InvokeTransaction {
+                sender_address: account,
+                nonce: U256::one(),
+                ..InvokeTransaction::default()
+            }),
+        ];
+        let declared_classes = vec![
+            (H256::from_low_u64_be(3).to_fixed_bytes(), None),
+            (H256::from_low_u64_be(4).to_fixed_bytes(), Some(H256::from_low_u64_be(5).to_fixed_bytes())),
+        ];
+
+        let state_diff = to_state_diff(&transactions, Vec::new(), declared_classes);
+        assert_eq!(
+            state_diff.deployed_contracts,
+            vec![DeployedContract { address: FieldElement::from(1_u64), class_hash: FieldElement::from(2_u64) }]
+        );
+        assert_eq!(state_diff.nonces, HashMap::from([(FieldElement::from(1_u64), FieldElement::from(2_u64))]));
+        assert_eq!(state_diff.old_declared_contracts, vec![FieldElement::from(3_u64)]);
+        assert_eq!(
+            state_diff.declared_classes,
+            vec![DeclaredClass {
+                class_hash: FieldElement::from(4_u64),
+                compiled_class_hash: FieldElement::from(5_u64)
+            }]
+        );
+    }
+
+    #[test]
+    fn errors_are_reported_in_the_sequencer_format() {
+        let response = error_response(FeederGatewayError::BlockNotFound);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers().get(CONTENT_TYPE), Some(&HeaderValue::from_static("application/json")));
+    }
+}
@@ -0,0 +1,181 @@
+//! Feeder gateway request and response types
+//!
+//! They follow the JSON format of the Starknet sequencer, which differs from the JSON-RPC one.
+
+use std::collections::HashMap;
+
+use mc_rpc_core::{
+    BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedTransaction, DeclareTransaction,
+    DeployAccountTransaction, FieldElement, InvokeTransaction, L1HandlerTransaction, Transaction as RpcTransaction,
+};
+use serde::{Deserialize, Serialize};
+
+/// The status of a block
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum BlockStatus {
+    #[serde(rename = "ACCEPTED_ON_L2")]
+    AcceptedOnL2,
+}
+
+/// A Starknet block, along with the receipts of its transactions
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Block {
+    pub block_hash: FieldElement,
+    pub parent_block_hash: FieldElement,
+    pub block_number: u64,
+    pub state_root: FieldElement,
+    pub status: BlockStatus,
+    pub timestamp: u64,
+    pub sequencer_address: FieldElement,
+    pub transactions: Vec<Transaction>,
+    pub transaction_receipts: Vec<TransactionReceipt>,
+}
+
+/// A Starknet transaction, tagged by its type
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum Transaction {
+    #[serde(rename = "INVOKE_FUNCTION")]
+    Invoke(InvokeTransaction),
+    #[serde(rename = "DECLARE")]
+    Declare(DeclareTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(DeployAccountTransaction),
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler(L1HandlerTransaction),
+}
+
+impl From<RpcTransaction> for Transaction {
+    fn from(transaction: RpcTransaction) -> Self {
+        match transaction {
+            RpcTransaction::Invoke(tx) => Transaction::Invoke(tx),
+            RpcTransaction::Declare(tx) => Transaction::Declare(tx),
+            RpcTransaction::DeployAccount(tx) => Transaction::DeployAccount(tx),
+            RpcTransaction::L1Handler(tx) => Transaction::L1Handler(tx),
+        }
+    }
+}
+
+/// An event emitted by a transaction
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct Event {
+    pub from_address: FieldElement,
+    pub keys: Vec<FieldElement>,
+    pub data: Vec<FieldElement>,
+}
+
+/// A message sent to L1 by a transaction
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct L2ToL1Message {
+    pub from_address: FieldElement,
+    pub to_address: FieldElement,
+    pub payload: Vec<FieldElement>,
+}
+
+/// The execution status of a transaction included in a block
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ExecutionStatus {
+    #[default]
+    #[serde(rename = "SUCCEEDED")]
+    Succeeded,
+    #[serde(rename = "REVERTED")]
+    Reverted,
+}
+
+/// The receipt of a transaction included in a block
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct TransactionReceipt {
+    pub transaction_hash: FieldElement,
+    pub transaction_index: u64,
+    pub actual_fee: FieldElement,
+    pub events: Vec<Event>,
+    pub l2_to_l1_messages: Vec<L2ToL1Message>,
+    pub execution_status: ExecutionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_error: Option<String>,
+}
+
+/// The value written to a storage var in a block
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct StorageDiff {
+    pub key: FieldElement,
+    pub value: FieldElement,
+}
+
+/// A contract deployed in a block
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct DeployedContract {
+    pub address: FieldElement,
+    pub class_hash: FieldElement,
+}
+
+/// A Cairo 1 class declared in a block
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct DeclaredClass {
+    pub class_hash: FieldElement,
+    pub compiled_class_hash: FieldElement,
+}
+
+/// The changes a block applied to the state
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct StateDiff {
+    pub storage_diffs: HashMap<FieldElement, Vec<StorageDiff>>,
+    pub nonces: HashMap<FieldElement, FieldElement>,
+    pub deployed_contracts: Vec<DeployedContract>,
+    pub old_declared_contracts: Vec<FieldElement>,
+    pub declared_classes: Vec<DeclaredClass>,
+    pub replaced_classes: Vec<serde_json::Value>,
+}
+
+/// The state update of a block
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct StateUpdate {
+    pub block_hash: FieldElement,
+    pub new_root: FieldElement,
+    pub old_root: FieldElement,
+    pub state_diff: StateDiff,
+}
+
+/// A Cairo 0 contract class
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ContractClass {
+    pub program: serde_json::Value,
+    pub entry_points_by_type: serde_json::Value,
+    pub abi: Vec<serde_json::Value>,
+}
+
+/// A transaction sent to `/gateway/add_transaction`, tagged by its type
+/// TODO: support declare transactions once contract classes can be decoded from the request
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum AddTransaction {
+    #[serde(rename = "INVOKE_FUNCTION")]
+    Invoke(BroadcastedInvokeTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(BroadcastedDeployAccountTransaction),
+}
+
+impl From<AddTransaction> for BroadcastedTransaction {
+    fn from(transaction: AddTransaction) -> Self {
+        match transaction {
+            AddTransaction::Invoke(tx) => BroadcastedTransaction::Invoke(tx),
+            AddTransaction::DeployAccount(tx) => BroadcastedTransaction::DeployAccount(tx),
+        }
+    }
+}
+
+/// The response of `/gateway/add_transaction`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AddTransactionResponse {
+    pub code: &'static str,
+    pub transaction_hash: FieldElement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<FieldElement>,
+}
+
+/// The body of an error response
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ErrorResponse {
+    pub code: &'static str,
+    pub message: String,
+}
@@ -5,15 +5,19 @@
 //! `pallet-starknet` logs. Those logs should contain the data necessary to update the Madara
 //! mapping db: a starknet block header.
 //!
+//! Once a block has been synced, a `StarknetBlockNotification` is sent to every registered sink,
+//! which is what drives the RPC subscriptions.
+//!
 //! # Usage
 //! The madara node should spawn a `MappingSyncWorker` among it's services.
 
 mod sync_blocks;
 
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use futures::channel::mpsc::UnboundedSender;
 use futures::prelude::*;
 use futures::task::{Context, Poll};
 use futures_timer::Delay;
@@ -24,8 +28,21 @@ use sc_client_api::backend::{Backend, StorageProvider};
 use sc_client_api::client::ImportNotifications;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
+/// Notification sent once the Madara db has been synced with a new Substrate block
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StarknetBlockNotification<B: BlockT> {
+    /// Whether the block is the new best block of the chain
+    pub is_new_best: bool,
+    /// The hash of the Substrate block
+    pub hash: B::Hash,
+}
+
+/// The sinks notified each time a Substrate block has been synced
+pub type StarknetBlockNotificationSinks<T> = Mutex<Vec<UnboundedSender<T>>>;
+
 /// The worker in charge of syncing the Madara db when it receive a new Substrate block
 pub struct MappingSyncWorker<B: BlockT, C, BE> {
     import_notifications: ImportNotifications<B>,
@@ -40,6 +57,8 @@ pub struct MappingSyncWorker<B: BlockT, C, BE> {
     have_next: bool,
     retry_times: usize,
     sync_from: <B::Header as HeaderT>::Number,
+    sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
+    notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
 }
 
 impl<B: BlockT, C, BE> Unpin for MappingSyncWorker<B, C, BE> {}
@@ -55,6 +74,8 @@ impl<B: BlockT, C, BE> MappingSyncWorker<B, C, BE> {
         frontier_backend: Arc<mc_db::Backend<B>>,
         retry_times: usize,
         sync_from: <B::Header as HeaderT>::Number,
+        sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
+        notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
     ) -> Self {
         Self {
             import_notifications,
@@ -69,6 +90,8 @@ impl<B: BlockT, C, BE> MappingSyncWorker<B, C, BE> {
             have_next: true,
             retry_times,
             sync_from,
+            sync_oracle,
+            notification_sinks,
         }
     }
 }
@@ -119,6 +142,8 @@ where
                 self.madara_backend.as_ref(),
                 self.retry_times,
                 self.sync_from,
+                self.sync_oracle.clone(),
+                self.notification_sinks.clone(),
             ) {
                 Ok(have_next) => {
                     self.have_next = have_next;
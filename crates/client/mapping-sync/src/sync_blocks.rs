@@ -6,8 +6,11 @@ use pallet_starknet::runtime_api::StarknetRuntimeApi;
 use sc_client_api::backend::{Backend, StorageProvider};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Backend as _, HeaderBackend};
+use sp_consensus::SyncOracle;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
 
+use crate::{StarknetBlockNotification, StarknetBlockNotificationSinks};
+
 fn sync_block<B: BlockT, C, BE>(
     client: &C,
     overrides: Arc<OverrideHandle<B>>,
@@ -22,7 +25,11 @@ where
     match mp_digest_log::find_log(header.digest()) {
         Ok(log) => {
             let gen_from_hashes = |hashes: Hashes| -> mc_db::MappingCommitment<B> {
-                mc_db::MappingCommitment { block_hash: substrate_block_hash, starknet_block_hash: hashes.block_hash }
+                mc_db::MappingCommitment {
+                    block_hash: substrate_block_hash,
+                    starknet_block_hash: hashes.block_hash,
+                    starknet_transaction_hashes: hashes.transaction_hashes,
+                }
             };
             let gen_from_block = |block| -> mc_db::MappingCommitment<B> {
                 let hashes = Hashes::from_block(block);
@@ -67,8 +74,11 @@ where
 
     let block = client.runtime_api().current_block(substrate_block_hash).map_err(|e| format!("{:?}", e))?;
     let block_hash = block.header().hash();
-    let mapping_commitment =
-        mc_db::MappingCommitment::<B> { block_hash: substrate_block_hash, starknet_block_hash: block_hash };
+    let mapping_commitment = mc_db::MappingCommitment::<B> {
+        block_hash: substrate_block_hash,
+        starknet_block_hash: block_hash,
+        starknet_transaction_hashes: block.transactions_hashes(),
+    };
     backend.mapping().write_hashes(mapping_commitment)?;
 
     Ok(())
@@ -80,6 +90,8 @@ fn sync_one_block<B: BlockT, C, BE>(
     overrides: Arc<OverrideHandle<B>>,
     madara_backend: &mc_db::Backend<B>,
     sync_from: <B::Header as HeaderT>::Number,
+    sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
+    notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
 ) -> Result<bool, String>
 where
     C: ProvideRuntimeApi<B>,
@@ -124,6 +136,15 @@ where
 
         current_syncing_tips.push(*operating_header.parent_hash());
         madara_backend.meta().write_current_syncing_tips(current_syncing_tips)?;
+
+        // Notify the subscribers, unless the node is catching up with the chain, and drop the
+        // closed sinks.
+        if !sync_oracle.is_major_syncing() {
+            let hash = operating_header.hash();
+            let is_new_best = client.info().best_hash == hash;
+            let mut sinks = notification_sinks.lock().map_err(|e| format!("{:?}", e))?;
+            sinks.retain(|sink| sink.unbounded_send(StarknetBlockNotification { is_new_best, hash }).is_ok());
+        }
         Ok(true)
     }
 }
@@ -135,6 +156,8 @@ pub fn sync_blocks<B: BlockT, C, BE>(
     madara_backend: &mc_db::Backend<B>,
     limit: usize,
     sync_from: <B::Header as HeaderT>::Number,
+    sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
+    notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
 ) -> Result<bool, String>
 where
     C: ProvideRuntimeApi<B>,
@@ -145,8 +168,16 @@ where
     let mut synced_any = false;
 
     for _ in 0..limit {
-        synced_any =
-            synced_any || sync_one_block(client, substrate_backend, overrides.clone(), madara_backend, sync_from)?;
+        synced_any = synced_any
+            || sync_one_block(
+                client,
+                substrate_backend,
+                overrides.clone(),
+                madara_backend,
+                sync_from,
+                sync_oracle.clone(),
+                notification_sinks.clone(),
+            )?;
     }
 
     Ok(synced_any)
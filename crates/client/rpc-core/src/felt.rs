@@ -0,0 +1,172 @@
+//! Starknet field element, as exchanged over the RPC
+//!
+//! A field element is serialized as a `0x`-prefixed hexadecimal string without leading zeros.
+//! Deserialization enforces the prefix and the field bounds, so that malformed parameters are
+//! rejected by `jsonrpsee` with an `InvalidParams` error before reaching the handlers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sp_core::{H256, U256};
+
+/// The prime `P = 2^251 + 17 * 2^192 + 1` of the Starknet field, in big-endian bytes
+const FIELD_PRIME: [u8; 32] = [
+    0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Errors raised when parsing a field element
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldElementError {
+    #[error("field element must start with 0x")]
+    MissingPrefix,
+    #[error("field element is not a valid hexadecimal string")]
+    InvalidHex,
+    #[error("field element is longer than 64 hexadecimal digits")]
+    TooLong,
+    #[error("field element is not lower than the field prime")]
+    OutOfRange,
+}
+
+/// An element of the Starknet field
+///
+/// Conversions from node types (`H256`, `U256`, contract addresses) are not bound-checked, as the
+/// values stored by the node are already field elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct FieldElement(H256);
+
+impl FromStr for FieldElement {
+    type Err = FieldElementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").ok_or(FieldElementError::MissingPrefix)?;
+        if digits.is_empty() {
+            return Err(FieldElementError::InvalidHex);
+        }
+        if digits.len() > 64 {
+            return Err(FieldElementError::TooLong);
+        }
+
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(format!("{:0>64}", digits), &mut bytes).map_err(|_| FieldElementError::InvalidHex)?;
+        if bytes >= FIELD_PRIME {
+            return Err(FieldElementError::OutOfRange);
+        }
+
+        Ok(Self(H256(bytes)))
+    }
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", U256::from_big_endian(self.0.as_bytes()))
+    }
+}
+
+impl Serialize for FieldElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e| D::Error::custom(format!("invalid field element '{s}': {e}")))
+    }
+}
+
+impl From<H256> for FieldElement {
+    fn from(value: H256) -> Self {
+        Self(value)
+    }
+}
+
+impl From<FieldElement> for H256 {
+    fn from(value: FieldElement) -> Self {
+        value.0
+    }
+}
+
+impl From<[u8; 32]> for FieldElement {
+    fn from(value: [u8; 32]) -> Self {
+        Self(H256(value))
+    }
+}
+
+impl From<FieldElement> for [u8; 32] {
+    fn from(value: FieldElement) -> Self {
+        value.0.to_fixed_bytes()
+    }
+}
+
+impl From<U256> for FieldElement {
+    fn from(value: U256) -> Self {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        Self(H256(bytes))
+    }
+}
+
+impl From<FieldElement> for U256 {
+    fn from(value: FieldElement) -> Self {
+        U256::from_big_endian(value.0.as_bytes())
+    }
+}
+
+impl From<u64> for FieldElement {
+    fn from(value: u64) -> Self {
+        U256::from(value).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_field_elements() {
+        let felt =
+            FieldElement::from_str("0x0222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7").unwrap();
+        assert_eq!(
+            H256::from(felt),
+            H256::from_str("0x0222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7").unwrap()
+        );
+
+        // Short values are padded with zeros on the left
+        let felt = FieldElement::from_str("0x0123456789abcdef").unwrap();
+        assert_eq!(U256::from(felt), U256::from(0x0123456789abcdefu64));
+
+        // The largest field element is P - 1
+        assert!(FieldElement::from_str("0x800000000000011000000000000000000000000000000000000000000000000").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_field_elements() {
+        assert_eq!(
+            FieldElement::from_str("222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7"),
+            Err(FieldElementError::MissingPrefix)
+        );
+        assert_eq!(FieldElement::from_str("0x"), Err(FieldElementError::InvalidHex));
+        assert_eq!(FieldElement::from_str("0xzz"), Err(FieldElementError::InvalidHex));
+        assert_eq!(
+            FieldElement::from_str("0x222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7111111"),
+            Err(FieldElementError::TooLong)
+        );
+        assert_eq!(
+            FieldElement::from_str("0x800000000000011000000000000000000000000000000000000000000000001"),
+            Err(FieldElementError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn serializes_without_leading_zeros() {
+        let felt = FieldElement::from_str("0x000000000000000000000000000000000000000000000000000000000000abc").unwrap();
+        assert_eq!(serde_json::to_string(&felt).unwrap(), "\"0xabc\"");
+        assert_eq!(serde_json::to_string(&FieldElement::default()).unwrap(), "\"0x0\"");
+        assert_eq!(serde_json::from_str::<FieldElement>("\"0xabc\"").unwrap(), felt);
+        assert!(serde_json::from_str::<FieldElement>("\"abc\"").is_err());
+    }
+}
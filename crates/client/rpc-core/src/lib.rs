@@ -3,14 +3,25 @@
 //! Starkware maintains [a description of the Starknet API](https://github.com/starkware-libs/starknet-specs/blob/master/api/starknet_api_openrpc.json)
 //! using the openRPC specification.
 //! This crate uses `jsonrpsee` to define such an API in Rust terms.
+//!
+//! The spec changes incompatibly between versions, so each supported version has its own module
+//! holding the types specific to it and its API trait. Types shared by all versions live at the
+//! root of the crate.
+
+mod felt;
+pub mod v0_3;
+pub mod v0_4;
 
-use jsonrpsee::core::RpcResult;
+use std::fmt;
+
+pub use felt::{FieldElement, FieldElementError};
 use jsonrpsee::proc_macros::rpc;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, IntoDeserializer, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 
-pub type FieldElement = String;
 pub type BlockNumber = u64;
 pub type BlockHash = FieldElement;
+pub type NumAsHex = String;
 
 /// A tag specifying a dynamic reference to a block
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -36,32 +47,449 @@ pub struct FunctionCall {
 }
 
 /// A block hash, number or tag
-/// TODO: fix block_tag
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+///
+/// Serialized as in the spec: a plain tag (`"latest"`) or an object holding either the hash
+/// (`{"block_hash": "0x1"}`) or the number (`{"block_number": 1}`) of the block.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(into = "BlockIdRepr")]
 pub enum BlockId {
-    #[serde(rename = "block_hash")]
     BlockHash(BlockHash),
-    #[serde(rename = "block_number")]
     BlockNumber(BlockNumber),
     BlockTag(BlockTag),
 }
 
-/// Starknet rpc interface.
+/// The JSON representation of a `BlockId`
+#[derive(Serialize)]
+#[serde(untagged)]
+enum BlockIdRepr {
+    Tag(BlockTag),
+    Hash { block_hash: BlockHash },
+    Number { block_number: BlockNumber },
+}
+
+impl From<BlockId> for BlockIdRepr {
+    fn from(block_id: BlockId) -> Self {
+        match block_id {
+            BlockId::BlockTag(tag) => BlockIdRepr::Tag(tag),
+            BlockId::BlockHash(block_hash) => BlockIdRepr::Hash { block_hash },
+            BlockId::BlockNumber(block_number) => BlockIdRepr::Number { block_number },
+        }
+    }
+}
+
+/// Deserializes a `BlockId` by looking at its shape and key, so that an invalid hash or number
+/// is reported as such instead of as a value matching none of the variants.
+impl<'de> Deserialize<'de> for BlockId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BlockIdVisitor)
+    }
+}
+
+struct BlockIdVisitor;
+
+impl<'de> Visitor<'de> for BlockIdVisitor {
+    type Value = BlockId;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(r#"a block tag, or an object with either a "block_hash" or a "block_number" key"#)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        BlockTag::deserialize(value.into_deserializer()).map(BlockId::BlockTag)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let key = map.next_key::<String>()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let block_id = match key.as_str() {
+            "block_hash" => BlockId::BlockHash(map.next_value()?),
+            "block_number" => BlockId::BlockNumber(map.next_value()?),
+            _ => return Err(de::Error::unknown_field(&key, &["block_hash", "block_number"])),
+        };
+
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom("a block id holds a single key"));
+        }
+
+        Ok(block_id)
+    }
+}
+
+/// An invoke transaction
+///
+/// Version 0 transactions call the `entry_point_selector` entrypoint of the `sender_address`
+/// contract directly, and have no nonce.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct InvokeTransaction {
+    pub transaction_hash: FieldElement,
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    #[serde(default)]
+    pub nonce: FieldElement,
+    #[serde(alias = "contract_address")]
+    pub sender_address: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point_selector: Option<FieldElement>,
+}
+
+/// A declare transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DeclareTransaction {
+    pub transaction_hash: FieldElement,
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    pub nonce: FieldElement,
+    pub class_hash: FieldElement,
+    pub sender_address: FieldElement,
+}
+
+/// A deploy account transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DeployAccountTransaction {
+    pub transaction_hash: FieldElement,
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    pub nonce: FieldElement,
+    pub contract_address_salt: FieldElement,
+    pub constructor_calldata: Vec<FieldElement>,
+    pub class_hash: FieldElement,
+}
+
+/// A transaction sent from L1 and handled by a contract `l1_handler` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct L1HandlerTransaction {
+    pub transaction_hash: FieldElement,
+    pub version: FieldElement,
+    pub nonce: FieldElement,
+    pub contract_address: FieldElement,
+    pub entry_point_selector: FieldElement,
+    pub calldata: Vec<FieldElement>,
+}
+
+/// A Starknet transaction, tagged by its type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum Transaction {
+    #[serde(rename = "INVOKE")]
+    Invoke(InvokeTransaction),
+    #[serde(rename = "DECLARE")]
+    Declare(DeclareTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(DeployAccountTransaction),
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler(L1HandlerTransaction),
+}
+
+/// The type of a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TransactionType {
+    #[serde(rename = "INVOKE")]
+    Invoke,
+    #[serde(rename = "DECLARE")]
+    Declare,
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount,
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler,
+}
+
+/// The type of an invoked entry point
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum EntryPointType {
+    #[serde(rename = "EXTERNAL")]
+    External,
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler,
+    #[serde(rename = "CONSTRUCTOR")]
+    Constructor,
+}
+
+/// The type of a call, either a regular one or a library call
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum CallType {
+    #[serde(rename = "CALL")]
+    Call,
+    #[serde(rename = "LIBRARY_CALL")]
+    LibraryCall,
+}
+
+/// An event emitted during a function invocation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Event {
+    pub keys: Vec<FieldElement>,
+    pub data: Vec<FieldElement>,
+}
+
+/// An event emitted by a transaction, along with the contract which emitted it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TransactionEvent {
+    pub from_address: FieldElement,
+    pub keys: Vec<FieldElement>,
+    pub data: Vec<FieldElement>,
+}
+
+/// A message sent to L1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct MessageToL1 {
+    pub to_address: FieldElement,
+    pub payload: Vec<FieldElement>,
+}
+
+/// An invoke transaction to be broadcasted, without its hash
+///
+/// Version 0 transactions set the `contract_address` and `entry_point_selector` of the called
+/// entrypoint instead of a sender account and a nonce.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BroadcastedInvokeTransaction {
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    #[serde(default)]
+    pub nonce: FieldElement,
+    #[serde(alias = "contract_address")]
+    pub sender_address: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point_selector: Option<FieldElement>,
+}
+
+/// A deploy account transaction to be broadcasted, without its hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BroadcastedDeployAccountTransaction {
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    pub nonce: FieldElement,
+    pub contract_address_salt: FieldElement,
+    pub constructor_calldata: Vec<FieldElement>,
+    pub class_hash: FieldElement,
+}
+
+/// A declare transaction to be broadcasted, without its hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BroadcastedDeclareTransaction {
+    pub max_fee: FieldElement,
+    pub version: FieldElement,
+    pub signature: Vec<FieldElement>,
+    pub nonce: FieldElement,
+    pub sender_address: FieldElement,
+    /// The declared class, in the RPC format, which is kept undecoded
+    pub contract_class: serde_json::Value,
+    /// Only set from version 2 on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<FieldElement>,
+}
+
+/// A Starknet transaction to be broadcasted, tagged by its type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum BroadcastedTransaction {
+    #[serde(rename = "INVOKE")]
+    Invoke(BroadcastedInvokeTransaction),
+    /// Accepted so that it can be rejected explicitly, as contract classes cannot be decoded from
+    /// the RPC format yet
+    #[serde(rename = "DECLARE")]
+    Declare(BroadcastedDeclareTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(BroadcastedDeployAccountTransaction),
+}
+
+/// A flag altering the way transactions are simulated
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SimulationFlag {
+    #[serde(rename = "SKIP_VALIDATE")]
+    SkipValidate,
+    #[serde(rename = "SKIP_FEE_CHARGE")]
+    SkipFeeCharge,
+}
+
+/// Fee estimation of a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct FeeEstimate {
+    pub gas_consumed: FieldElement,
+    pub gas_price: FieldElement,
+    pub overall_fee: FieldElement,
+}
+
+/// The synchronization status of a node
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SyncStatus {
+    pub starting_block_hash: BlockHash,
+    pub starting_block_num: NumAsHex,
+    pub current_block_hash: BlockHash,
+    pub current_block_num: NumAsHex,
+    /// Unknown until the highest block has been imported, in which case it is omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highest_block_hash: Option<BlockHash>,
+    pub highest_block_num: NumAsHex,
+}
+
+/// The synchronization status of a node, or `false` if it is not syncing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum SyncStatusType {
+    Syncing(SyncStatus),
+    NotSyncing(bool),
+}
+
+/// The header of a Starknet block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BlockHeader {
+    pub block_hash: BlockHash,
+    pub parent_hash: BlockHash,
+    pub block_number: BlockNumber,
+    pub new_root: FieldElement,
+    pub timestamp: u64,
+    pub sequencer_address: FieldElement,
+}
+
+/// A filter on the events emitted in new blocks
+///
+/// `keys` is matched position by position: an event matches if, for each position, its key is
+/// one of the given values. An empty list at a position matches any key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct EventFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<FieldElement>,
+    #[serde(default)]
+    pub keys: Vec<Vec<FieldElement>>,
+}
+
+/// An event emitted in a block, along with the transaction which emitted it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct EmittedEvent {
+    pub from_address: FieldElement,
+    pub keys: Vec<FieldElement>,
+    pub data: Vec<FieldElement>,
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    pub transaction_hash: FieldElement,
+}
+
+/// The status of a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TransactionStatus {
+    #[serde(rename = "NOT_RECEIVED")]
+    NotReceived,
+    #[serde(rename = "RECEIVED")]
+    Received,
+    #[serde(rename = "ACCEPTED_ON_L2")]
+    AcceptedOnL2,
+    #[serde(rename = "REVERTED")]
+    Reverted,
+    #[serde(rename = "REJECTED")]
+    Rejected,
+}
+
+/// Starknet pubsub interface.
 #[rpc(server, namespace = "starknet")]
-pub trait StarknetRpcApi {
-    /// Get the most recent accepted block number
-    #[method(name = "blockNumber")]
-    fn block_number(&self) -> RpcResult<BlockNumber>;
-
-    /// Get the most recent accepted block hash and number
-    #[method(name = "blockHashAndNumber")]
-    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber>;
-
-    /// Get the number of transactions in a block given a block id
-    #[method(name = "getBlockTransactionCount")]
-    fn get_block_transaction_count(&self, block_id: BlockId) -> RpcResult<u128>;
-
-    /// Call a contract function at a given block id
-    #[method(name = "call")]
-    fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<String>>;
+pub trait StarknetPubSubApi {
+    /// Subscribe to the headers of the new best Starknet blocks
+    #[subscription(name = "subscribeNewHeads" => "newHead", unsubscribe = "unsubscribeNewHeads", item = BlockHeader)]
+    fn subscribe_new_heads(&self);
+
+    /// Subscribe to the events matching a filter, as they are emitted in new best blocks
+    #[subscription(name = "subscribeEvents" => "event", unsubscribe = "unsubscribeEvents", item = EmittedEvent)]
+    fn subscribe_events(&self, filter: EventFilter);
+
+    /// Subscribe to the status updates of a transaction, until it is accepted on L2, reverted or
+    /// rejected
+    #[subscription(
+        name = "subscribeTransactionStatus" => "transactionStatus",
+        unsubscribe = "unsubscribeTransactionStatus",
+        item = TransactionStatus
+    )]
+    fn subscribe_transaction_status(&self, transaction_hash: FieldElement);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn assert_round_trip(value: serde_json::Value, block_id: BlockId) {
+        assert_eq!(serde_json::from_value::<BlockId>(value.clone()).unwrap(), block_id);
+        assert_eq!(serde_json::to_value(&block_id).unwrap(), value);
+    }
+
+    #[test]
+    fn block_id_tags_round_trip() {
+        assert_round_trip(json!("latest"), BlockId::BlockTag(BlockTag::Latest));
+        assert_round_trip(json!("pending"), BlockId::BlockTag(BlockTag::Pending));
+    }
+
+    #[test]
+    fn block_id_hash_round_trips() {
+        assert_round_trip(
+            json!({ "block_hash": "0x4e6e6b3e9c5e6c7a0d3b1f0fa8c3c6f1b1e4a9e1b6d2e5b5c6a1e8f2d3b4a5c" }),
+            BlockId::BlockHash("0x4e6e6b3e9c5e6c7a0d3b1f0fa8c3c6f1b1e4a9e1b6d2e5b5c6a1e8f2d3b4a5c".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn block_id_number_round_trips() {
+        assert_round_trip(json!({ "block_number": 0 }), BlockId::BlockNumber(0));
+        assert_round_trip(json!({ "block_number": 42 }), BlockId::BlockNumber(42));
+    }
+
+    #[test]
+    fn block_id_rejects_invalid_values() {
+        // The former externally tagged representation
+        assert!(serde_json::from_value::<BlockId>(json!({ "BlockTag": "latest" })).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!("earliest")).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!({ "block_number": -1 })).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!({ "block_hash": "not a hash" })).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!(42)).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!({})).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!({ "block_index": 1 })).is_err());
+        assert!(serde_json::from_value::<BlockId>(json!({ "block_number": 1, "block_hash": "0x1" })).is_err());
+    }
+
+    #[test]
+    fn block_id_reports_invalid_hashes() {
+        let error = serde_json::from_value::<BlockId>(json!({ "block_hash": "0x" })).unwrap_err();
+        assert!(error.to_string().contains("invalid field element '0x'"), "{error}");
+
+        let error = serde_json::from_value::<BlockId>(json!({ "block_hash": "1" })).unwrap_err();
+        assert!(error.to_string().contains("field element must start with 0x"), "{error}");
+    }
+
+    #[test]
+    fn block_id_round_trips_the_spec_shapes() {
+        let spec: serde_json::Value = serde_json::from_str(include_str!("../starknet_openRPC.json")).unwrap();
+        let schemas = &spec["components"]["schemas"];
+
+        for tag in schemas["BLOCK_TAG"]["enum"].as_array().unwrap() {
+            let block_id = serde_json::from_value::<BlockId>(tag.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&block_id).unwrap(), *tag);
+        }
+
+        let keys: Vec<&str> = schemas["BLOCK_ID"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|shape| shape["required"].get(0))
+            .map(|key| key.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, ["block_hash", "block_number"]);
+
+        assert_round_trip(json!({ keys[0]: "0x1" }), BlockId::BlockHash(FieldElement::from(1u64)));
+        assert_round_trip(json!({ keys[1]: 1 }), BlockId::BlockNumber(1));
+    }
+
+    #[test]
+    fn sync_status_omits_an_unknown_highest_block_hash() {
+        let status = SyncStatus { highest_block_num: "0x5".to_string(), ..Default::default() };
+        let value = serde_json::to_value(&status).unwrap();
+        assert!(value.get("highest_block_hash").is_none());
+        assert_eq!(value["highest_block_num"], json!("0x5"));
+
+        let status = SyncStatus { highest_block_hash: Some(FieldElement::from(1u64)), ..status };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(serde_json::from_value::<SyncStatus>(value).unwrap(), status);
+    }
 }
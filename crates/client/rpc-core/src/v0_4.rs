@@ -0,0 +1,174 @@
+//! Starknet RPC API, as of the v0.4.0 spec
+//!
+//! Served under the `starknet_v0_4` namespace. Compared to v0.3.0, transaction traces depend on
+//! the transaction type, a reverted execution reports its revert reason and function invocations
+//! list the messages sent to L1.
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BlockHash, BlockHashAndNumber, BlockId, BlockNumber, BroadcastedTransaction, CallType, EntryPointType, Event,
+    FeeEstimate, FieldElement, FunctionCall, MessageToL1, SimulationFlag, SyncStatusType, Transaction,
+    TransactionEvent, TransactionStatus, TransactionType,
+};
+
+/// The version of the spec served by this module
+pub const SPEC_VERSION: &str = "0.4.0";
+
+/// The execution trace of a function invocation, including its inner calls
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FunctionInvocation {
+    pub contract_address: FieldElement,
+    pub entry_point_selector: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    pub caller_address: FieldElement,
+    pub class_hash: FieldElement,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    pub result: Vec<FieldElement>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<Event>,
+    pub messages: Vec<MessageToL1>,
+}
+
+/// The execution of an invoke transaction, either successful or reverted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Success(FunctionInvocation),
+    Reverted { revert_reason: String },
+}
+
+/// The execution trace of a transaction, depending on its type
+///
+/// Variants are ordered from the most to the least constrained, so that deserialization picks the
+/// right one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum TransactionTrace {
+    L1Handler {
+        function_invocation: FunctionInvocation,
+    },
+    DeployAccount {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        constructor_invocation: FunctionInvocation,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+    },
+    Invoke {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        execute_invocation: ExecuteInvocation,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+    },
+    Declare {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+    },
+}
+
+/// The execution trace of a transaction, along with its hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TransactionTraceWithHash {
+    pub transaction_hash: FieldElement,
+    pub trace_root: TransactionTrace,
+}
+
+/// The execution trace and fee estimation of a simulated transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SimulatedTransaction {
+    pub transaction_trace: TransactionTrace,
+    pub fee_estimation: FeeEstimate,
+}
+
+/// The result of the execution of a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ExecutionStatus {
+    #[serde(rename = "SUCCEEDED")]
+    Succeeded,
+    #[serde(rename = "REVERTED")]
+    Reverted,
+}
+
+/// The receipt of a transaction
+///
+/// Compared to v0.3.0, the status is split into an execution and a finality status, and a
+/// reverted transaction reports its revert reason.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TransactionReceipt {
+    pub transaction_hash: FieldElement,
+    pub actual_fee: FieldElement,
+    pub execution_status: ExecutionStatus,
+    pub finality_status: TransactionStatus,
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub messages_sent: Vec<MessageToL1>,
+    pub events: Vec<TransactionEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Only set for deploy account transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<FieldElement>,
+}
+
+/// Starknet rpc interface, as of the v0.4.0 spec.
+#[rpc(server, namespace = "starknet_v0_4")]
+pub trait StarknetRpcApi {
+    /// Get the version of the Starknet RPC spec served by this interface
+    #[method(name = "specVersion")]
+    fn spec_version(&self) -> RpcResult<String>;
+
+    /// Get the most recent accepted block number
+    #[method(name = "blockNumber")]
+    fn block_number(&self) -> RpcResult<BlockNumber>;
+
+    /// Get the most recent accepted block hash and number
+    #[method(name = "blockHashAndNumber")]
+    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber>;
+
+    /// Get the number of transactions in a block given a block id
+    #[method(name = "getBlockTransactionCount")]
+    fn get_block_transaction_count(&self, block_id: BlockId) -> RpcResult<u128>;
+
+    /// Call a contract function at a given block id
+    #[method(name = "call")]
+    fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<FieldElement>>;
+
+    /// Get the synchronization status of the node, or `false` if it is not syncing
+    #[method(name = "syncing")]
+    async fn syncing(&self) -> RpcResult<SyncStatusType>;
+
+    /// Get the transactions currently in the transaction pool, not yet included in a block
+    #[method(name = "pendingTransactions")]
+    fn pending_transactions(&self) -> RpcResult<Vec<Transaction>>;
+
+    /// Get the receipt of a transaction given its hash
+    #[method(name = "getTransactionReceipt")]
+    fn get_transaction_receipt(&self, transaction_hash: FieldElement) -> RpcResult<TransactionReceipt>;
+
+    /// Get the execution trace of a transaction given its hash
+    #[method(name = "traceTransaction")]
+    fn trace_transaction(&self, transaction_hash: FieldElement) -> RpcResult<TransactionTrace>;
+
+    /// Get the execution traces of all the transactions of a block given its hash
+    #[method(name = "traceBlockTransactions")]
+    fn trace_block_transactions(&self, block_hash: BlockHash) -> RpcResult<Vec<TransactionTraceWithHash>>;
+
+    /// Simulate a sequence of transactions on top of a given block id, without adding them to the
+    /// pool
+    #[method(name = "simulateTransactions")]
+    fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>>;
+}
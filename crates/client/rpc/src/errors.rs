@@ -1,9 +1,12 @@
 use jsonrpsee::types::error::{CallError, ErrorObject};
+use serde::Serialize;
 
-#[derive(thiserror::Error, Clone, Copy, Debug)]
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StarknetRpcApiError {
     #[error("Failed to write transaction")]
     FailedToReceiveTxn = 1,
+    #[error("No trace available for transaction")]
+    NoTraceAvailable = 10,
     #[error("Contract not found")]
     ContractNotFound = 20,
     #[error("Invalid message selector")]
@@ -28,6 +31,8 @@ pub enum StarknetRpcApiError {
     ContractError = 40,
     #[error("Invalid contract class")]
     InvalidContractClass = 50,
+    #[error("The contract class version is not supported")]
+    UnsupportedContractClassVersion = 62,
     #[error("Too many storage keys requested")]
     ProofLimitExceeded = 10000,
     #[error("Too many keys provided in a filter")]
@@ -38,8 +43,101 @@ pub enum StarknetRpcApiError {
     FailedToFetchPendingTransactions = 38,
 }
 
+impl StarknetRpcApiError {
+    /// Attaches the reason of a failed contract execution to the error
+    pub fn with_revert_error(self, revert_error: impl ToString) -> StarknetRpcApiErrorWithData {
+        StarknetRpcApiErrorWithData {
+            error: self,
+            data: StarknetRpcApiErrorData::Revert { revert_error: revert_error.to_string() },
+        }
+    }
+
+    /// Attaches the name of the offending request parameter to the error
+    pub fn with_invalid_parameter(self, parameter: &str, reason: impl ToString) -> StarknetRpcApiErrorWithData {
+        StarknetRpcApiErrorWithData {
+            error: self,
+            data: StarknetRpcApiErrorData::InvalidParameter {
+                parameter: parameter.to_string(),
+                reason: reason.to_string(),
+            },
+        }
+    }
+}
+
+/// Structured data attached to the JSON-RPC error object
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum StarknetRpcApiErrorData {
+    /// A contract execution failed
+    Revert { revert_error: String },
+    /// A request parameter could not be used
+    InvalidParameter { parameter: String, reason: String },
+}
+
+/// A Starknet RPC error along with its structured data
+#[derive(Clone, Debug, PartialEq)]
+pub struct StarknetRpcApiErrorWithData {
+    pub error: StarknetRpcApiError,
+    pub data: StarknetRpcApiErrorData,
+}
+
 impl From<StarknetRpcApiError> for jsonrpsee::core::Error {
     fn from(err: StarknetRpcApiError) -> Self {
-        jsonrpsee::core::Error::Call(CallError::Custom(ErrorObject::owned(err as i32, err.to_string(), None::<()>)))
+        to_rpc_error(err, None)
+    }
+}
+
+impl From<StarknetRpcApiErrorWithData> for jsonrpsee::core::Error {
+    fn from(err: StarknetRpcApiErrorWithData) -> Self {
+        to_rpc_error(err.error, Some(err.data))
+    }
+}
+
+/// Builds the JSON-RPC error of a Starknet RPC error, with its optional data
+fn to_rpc_error(err: StarknetRpcApiError, data: Option<StarknetRpcApiErrorData>) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Call(CallError::Custom(ErrorObject::owned(err as i32, err.to_string(), data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_object(err: jsonrpsee::core::Error) -> ErrorObject<'static> {
+        match err {
+            jsonrpsee::core::Error::Call(CallError::Custom(error_object)) => error_object,
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn error_without_data() {
+        let error_object = error_object(StarknetRpcApiError::BlockNotFound.into());
+
+        assert_eq!(error_object.code(), 24);
+        assert_eq!(error_object.message(), "Block not found");
+        assert!(error_object.data().is_none());
+    }
+
+    #[test]
+    fn error_with_revert_error() {
+        let error_object = error_object(StarknetRpcApiError::ContractError.with_revert_error("out of gas").into());
+
+        assert_eq!(error_object.code(), 40);
+        assert_eq!(error_object.data().unwrap().get(), r#"{"revert_error":"out of gas"}"#);
+    }
+
+    #[test]
+    fn error_with_invalid_parameter() {
+        let error_object = error_object(
+            StarknetRpcApiError::InvalidCallData
+                .with_invalid_parameter("version", "unsupported transaction version")
+                .into(),
+        );
+
+        assert_eq!(error_object.code(), 22);
+        assert_eq!(
+            error_object.data().unwrap().get(),
+            r#"{"parameter":"version","reason":"unsupported transaction version"}"#
+        );
     }
 }
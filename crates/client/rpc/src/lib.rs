@@ -3,43 +3,100 @@
 //! It uses the madara client and backend in order to answer queries.
 
 mod errors;
-mod madara_backend_client;
+pub mod madara_backend_client;
+mod metrics;
+mod pubsub;
+mod syncing;
+mod v0_4;
 
 use std::marker::PhantomData;
-use std::str::FromStr;
 use std::sync::Arc;
 
-use errors::StarknetRpcApiError;
-use hex::FromHex;
-use jsonrpsee::core::RpcResult;
+pub use errors::{StarknetRpcApiError, StarknetRpcApiErrorData, StarknetRpcApiErrorWithData};
+use jsonrpsee::core::{async_trait, RpcResult};
 use log::error;
-pub use mc_rpc_core::StarknetRpcApiServer;
-use mc_rpc_core::{BlockHashAndNumber, BlockId as StarknetBlockId, FunctionCall};
+pub use mc_rpc_core::v0_3::StarknetRpcApiServer;
+use mc_rpc_core::v0_3::{
+    FunctionInvocation, SimulatedTransaction, TransactionReceipt, TransactionTrace, TransactionTraceWithHash,
+};
+pub use mc_rpc_core::v0_4::StarknetRpcApiServer as StarknetRpcApiV0_4Server;
+pub use mc_rpc_core::StarknetPubSubApiServer;
+use mc_rpc_core::{
+    BlockHash, BlockHashAndNumber, BlockId as StarknetBlockId, BlockTag, BroadcastedTransaction, CallType,
+    DeclareTransaction, DeployAccountTransaction, EntryPointType, Event, FeeEstimate, FieldElement, FunctionCall,
+    InvokeTransaction, L1HandlerTransaction, MessageToL1, SimulationFlag, SyncStatus, SyncStatusType,
+    Transaction as RpcTransaction, TransactionEvent, TransactionStatus, TransactionType,
+};
 use mc_storage::OverrideHandle;
+pub use metrics::RpcMetrics;
+use metrics::{instrument, instrument_async};
+use mp_starknet::block::{BlockTransactions, CHAIN_ID};
+use mp_starknet::execution::{calculate_contract_address, CallTypeWrapper, EntryPointTypeWrapper};
+use mp_starknet::transaction::types::{
+    DeployAccountTransaction as StarknetDeployAccountTransaction, EventWrapper, FeeEstimateWrapper,
+    FunctionInvocationWrapper, InvokeTransaction as StarknetInvokeTransaction, MessageToL1Wrapper,
+    SimulatedTransactionWrapper, SimulationFlags, Transaction, TransactionExecutionStatus, TransactionReceiptWrapper,
+    TransactionTraceWrapper,
+};
 use pallet_starknet::runtime_api::StarknetRuntimeApi;
+pub use pubsub::StarknetPubSub;
 use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::BlockBackend;
+use sc_network_sync::SyncingService;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
 use sp_api::{ApiError, ProvideRuntimeApi};
 use sp_arithmetic::traits::UniqueSaturatedInto;
 use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
 use sp_core::U256;
 use sp_runtime::testing::H256;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+pub use syncing::{track_sync_start, SyncStartingBlock};
 
 /// A Starknet RPC server for Madara
-pub struct Starknet<B: BlockT, BE, C> {
+///
+/// It serves every supported version of the spec, all sharing the same handlers.
+pub struct Starknet<B: BlockT, BE, C, P> {
     client: Arc<C>,
     backend: Arc<mc_db::Backend<B>>,
     overrides: Arc<OverrideHandle<B>>,
+    pool: Arc<P>,
+    sync_service: Arc<SyncingService<B>>,
+    starting_block: SyncStartingBlock<B>,
+    metrics: Option<RpcMetrics>,
     _marker: PhantomData<(B, BE)>,
 }
 
-impl<B: BlockT, BE, C> Starknet<B, BE, C> {
-    pub fn new(client: Arc<C>, backend: Arc<mc_db::Backend<B>>, overrides: Arc<OverrideHandle<B>>) -> Self {
-        Self { client, backend, overrides, _marker: PhantomData }
+impl<B: BlockT, BE, C, P> Starknet<B, BE, C, P> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<mc_db::Backend<B>>,
+        overrides: Arc<OverrideHandle<B>>,
+        pool: Arc<P>,
+        sync_service: Arc<SyncingService<B>>,
+        starting_block: SyncStartingBlock<B>,
+        metrics: Option<RpcMetrics>,
+    ) -> Self {
+        Self { client, backend, overrides, pool, sync_service, starting_block, metrics, _marker: PhantomData }
     }
 }
 
-impl<B, BE, C> Starknet<B, BE, C>
+impl<B: BlockT, BE, C, P> Clone for Starknet<B, BE, C, P> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            backend: self.backend.clone(),
+            overrides: self.overrides.clone(),
+            pool: self.pool.clone(),
+            sync_service: self.sync_service.clone(),
+            starting_block: self.starting_block.clone(),
+            metrics: self.metrics.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<B, BE, C, P> Starknet<B, BE, C, P>
 where
     B: BlockT,
     C: HeaderBackend<B> + 'static,
@@ -47,9 +104,47 @@ where
     pub fn current_block_number(&self) -> RpcResult<u64> {
         Ok(UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number))
     }
+
+    /// Returns the hash of the Substrate block wrapping the Starknet block with given id
+    ///
+    /// The pending block is not wrapped in a Substrate block until it is sealed, so it is not
+    /// found.
+    fn substrate_block_hash_from_starknet(&self, block_id: StarknetBlockId) -> Result<B::Hash, StarknetRpcApiError> {
+        match block_id {
+            StarknetBlockId::BlockHash(h) => {
+                madara_backend_client::load_hash(self.client.as_ref(), &self.backend, h.into()).map_err(|e| {
+                    error!("Failed to load Starknet block hash for Substrate block with hash '{h}': {e}");
+                    StarknetRpcApiError::BlockNotFound
+                })?
+            }
+            StarknetBlockId::BlockNumber(n) => {
+                self.client.hash(UniqueSaturatedInto::unique_saturated_into(n)).map_err(|e| {
+                    error!("Failed to retrieve the hash of block number '{n}': {e}");
+                    StarknetRpcApiError::BlockNotFound
+                })?
+            }
+            StarknetBlockId::BlockTag(BlockTag::Latest) => Some(self.client.info().best_hash),
+            StarknetBlockId::BlockTag(BlockTag::Pending) => None,
+        }
+        .ok_or(StarknetRpcApiError::BlockNotFound)
+    }
+
+    /// Returns the hash of the Substrate block which included the Starknet transaction with given
+    /// hash
+    fn substrate_block_hash_of_transaction(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<B::Hash, StarknetRpcApiError> {
+        madara_backend_client::load_transaction_hash(self.client.as_ref(), &self.backend, transaction_hash.into())
+            .map_err(|e| {
+                error!("Failed to load the block of Starknet transaction with hash '{transaction_hash}': {e}");
+                StarknetRpcApiError::TxnHashNotFound
+            })?
+            .ok_or(StarknetRpcApiError::TxnHashNotFound)
+    }
 }
 
-impl<B, BE, C> Starknet<B, BE, C>
+impl<B, BE, C, P> Starknet<B, BE, C, P>
 where
     B: BlockT,
     C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
@@ -68,179 +163,713 @@ where
 
         Ok(block.header().hash())
     }
+
+    /// Returns a transaction given its hash, along with its receipt and its block
+    fn transaction_receipt(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<(Transaction, TransactionReceiptWrapper, BlockHashAndNumber), StarknetRpcApiError> {
+        let starknet_transaction_hash = H256::from(transaction_hash);
+        let substrate_block_hash = self.substrate_block_hash_of_transaction(transaction_hash)?;
+
+        let block = self
+            .overrides
+            .for_block_hash(self.client.as_ref(), substrate_block_hash)
+            .current_block(substrate_block_hash)
+            .ok_or(StarknetRpcApiError::BlockNotFound)?;
+        let transaction = match block.transactions() {
+            BlockTransactions::Full(transactions) => {
+                transactions.iter().find(|transaction| transaction.hash() == starknet_transaction_hash).cloned()
+            }
+            BlockTransactions::Hashes(_) => None,
+        }
+        .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+
+        let receipt = self
+            .client
+            .runtime_api()
+            .transaction_receipts(substrate_block_hash)
+            .map_err(|e| {
+                error!("Failed to retrieve the receipts of block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .into_iter()
+            .find(|receipt| receipt.transaction_hash == starknet_transaction_hash)
+            .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+
+        let block = BlockHashAndNumber {
+            block_hash: block.header().hash().into(),
+            block_number: block.header().block_number.as_u64(),
+        };
+
+        Ok((transaction, receipt, block))
+    }
+
+    /// Returns the hash of the Starknet block wrapped in the Substrate block with given number, if
+    /// the node knows about it
+    fn starknet_block_hash(&self, block_number: NumberFor<B>) -> Result<Option<H256>, StarknetRpcApiError> {
+        let substrate_block_hash = self.client.hash(block_number).map_err(|e| {
+            error!("Failed to retrieve the hash of block number '{block_number}': {e}");
+            StarknetRpcApiError::InternalServerError
+        })?;
+
+        Ok(substrate_block_hash.map(|substrate_block_hash| {
+            self.overrides
+                .for_block_hash(self.client.as_ref(), substrate_block_hash)
+                .current_block(substrate_block_hash)
+                .unwrap_or_default()
+                .header()
+                .hash()
+        }))
+    }
+}
+
+impl<B, BE, C, P> Starknet<B, BE, C, P>
+where
+    B: BlockT,
+    C: HeaderBackend<B> + BlockBackend<B> + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+{
+    /// Re-executes the Starknet transactions of a Substrate block on top of its parent state and
+    /// returns their traces, stopping after the `target` transaction if set.
+    fn trace_block(
+        &self,
+        substrate_block_hash: B::Hash,
+        target: Option<H256>,
+    ) -> Result<Vec<TransactionTraceWrapper>, StarknetRpcApiError> {
+        let extrinsics = self
+            .client
+            .block_body(substrate_block_hash)
+            .map_err(|e| {
+                error!("Failed to retrieve the body of block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::BlockNotFound
+            })?
+            .ok_or(StarknetRpcApiError::BlockNotFound)?;
+        let parent_hash = *self
+            .client
+            .header(substrate_block_hash)
+            .map_err(|e| {
+                error!("Failed to retrieve the header of block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::BlockNotFound
+            })?
+            .ok_or(StarknetRpcApiError::BlockNotFound)?
+            .parent_hash();
+
+        let transactions =
+            self.client.runtime_api().extrinsic_filter(substrate_block_hash, extrinsics).map_err(|e| {
+                error!("Failed to filter the Starknet transactions of block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::InternalServerError
+            })?;
+
+        self.client
+            .runtime_api()
+            .trace_transactions(parent_hash, transactions, target)
+            .map_err(|e| {
+                error!("Failed to call the runtime to trace the transactions of block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .map_err(|e| {
+                error!("Failed to trace transactions: {:#?}", e);
+                StarknetRpcApiError::NoTraceAvailable
+            })
+    }
+
+    /// Returns the trace of a transaction given its hash
+    fn transaction_trace(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<TransactionTraceWrapper, StarknetRpcApiError> {
+        let starknet_transaction_hash = H256::from(transaction_hash);
+        let substrate_block_hash = self.substrate_block_hash_of_transaction(transaction_hash)?;
+
+        self.trace_block(substrate_block_hash, Some(starknet_transaction_hash))?
+            .into_iter()
+            .find(|trace| trace.transaction_hash == starknet_transaction_hash)
+            .ok_or(StarknetRpcApiError::NoTraceAvailable)
+    }
+
+    /// Returns the traces of all the transactions of a block given its hash
+    fn block_transaction_traces(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<Vec<TransactionTraceWrapper>, StarknetRpcApiError> {
+        let substrate_block_hash = self.substrate_block_hash_from_starknet(StarknetBlockId::BlockHash(block_hash))?;
+
+        self.trace_block(substrate_block_hash, None)
+    }
+}
+
+impl<B, BE, C, P> Starknet<B, BE, C, P>
+where
+    B: BlockT,
+    C: HeaderBackend<B> + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
+{
+    /// Filters the Starknet transactions out of extrinsics of the transaction pool
+    fn pool_transactions(&self, extrinsics: Vec<B::Extrinsic>) -> Result<Vec<Transaction>, StarknetRpcApiError> {
+        self.client.runtime_api().extrinsic_filter(self.client.info().best_hash, extrinsics).map_err(|e| {
+            error!("Failed to filter the Starknet transactions out of the pool: {e}");
+            StarknetRpcApiError::FailedToFetchPendingTransactions
+        })
+    }
+
+    /// Returns the Starknet transactions of the pending block
+    ///
+    /// The pending block is made of the ready transactions of the pool, which the next block will
+    /// include.
+    fn ready_transactions(&self) -> Result<Vec<Transaction>, StarknetRpcApiError> {
+        let extrinsics = self.pool.ready().map(|tx| tx.data().clone()).collect();
+        self.pool_transactions(extrinsics)
+    }
+
+    /// Returns the Substrate block whose state a Starknet block id is read at, along with the
+    /// transactions to execute on top of it
+    ///
+    /// The state of the pending block is the state of the best block with the pending transactions
+    /// executed on top of it.
+    fn state_at(&self, block_id: StarknetBlockId) -> Result<(B::Hash, Vec<Transaction>), StarknetRpcApiError> {
+        if block_id == StarknetBlockId::BlockTag(BlockTag::Pending) {
+            return Ok((self.client.info().best_hash, self.ready_transactions()?));
+        }
+
+        Ok((self.substrate_block_hash_from_starknet(block_id)?, Vec::new()))
+    }
+
+    /// Simulates a sequence of transactions on top of a given block id
+    fn simulate(
+        &self,
+        block_id: StarknetBlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransactionWrapper>> {
+        let (substrate_block_hash, pending_transactions) = self.state_at(block_id)?;
+
+        let transactions =
+            transactions.into_iter().map(to_starknet_transaction).collect::<Result<Vec<Transaction>, _>>()?;
+        let simulation_flags = SimulationFlags {
+            skip_validate: simulation_flags.contains(&SimulationFlag::SkipValidate),
+            skip_fee_charge: simulation_flags.contains(&SimulationFlag::SkipFeeCharge),
+        };
+
+        let simulations = self
+            .client
+            .runtime_api()
+            .simulate_transactions(substrate_block_hash, pending_transactions, transactions, simulation_flags)
+            .map_err(|e| {
+                error!("Failed to call the runtime to simulate transactions on block '{substrate_block_hash:?}': {e}");
+                StarknetRpcApiError::InternalServerError
+            })?
+            .map_err(|e| {
+                error!("Failed to simulate transactions: {:#?}", e);
+                StarknetRpcApiError::ContractError.with_revert_error(format!("{e:?}"))
+            })?;
+
+        Ok(simulations)
+    }
 }
 
-impl<B, BE, C> StarknetRpcApiServer for Starknet<B, BE, C>
+#[async_trait]
+impl<B, BE, C, P> StarknetRpcApiServer for Starknet<B, BE, C, P>
 where
     B: BlockT,
     BE: Backend<B> + 'static,
-    C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+    C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
     C: ProvideRuntimeApi<B>,
     C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
 {
+    fn spec_version(&self) -> RpcResult<String> {
+        instrument(self.metrics.as_ref(), "starknet_specVersion", || Ok(mc_rpc_core::v0_3::SPEC_VERSION.to_string()))
+    }
+
     fn block_number(&self) -> RpcResult<mc_rpc_core::BlockNumber> {
-        self.current_block_number()
+        instrument(self.metrics.as_ref(), "starknet_blockNumber", || self.current_block_number())
     }
 
     fn block_hash_and_number(&self) -> RpcResult<mc_rpc_core::BlockHashAndNumber> {
-        let block_number = self.current_block_number()?;
-        let block_hash = self.current_block_hash().map_err(|e| {
-            error!("Failed to retrieve the current block hash: {}", e);
-            StarknetRpcApiError::NoBlocks
-        })?;
+        instrument(self.metrics.as_ref(), "starknet_blockHashAndNumber", || {
+            let block_number = self.current_block_number()?;
+            let block_hash = self.current_block_hash().map_err(|e| {
+                error!("Failed to retrieve the current block hash: {}", e);
+                StarknetRpcApiError::NoBlocks
+            })?;
 
-        Ok(BlockHashAndNumber { block_hash: format!("{:#x}", block_hash), block_number })
+            Ok(BlockHashAndNumber { block_hash: block_hash.into(), block_number })
+        })
     }
 
     fn get_block_transaction_count(&self, block_id: StarknetBlockId) -> RpcResult<u128> {
-        let substrate_block_hash = match block_id {
-            StarknetBlockId::BlockHash(h) => madara_backend_client::load_hash(
-                self.client.as_ref(),
-                &self.backend,
-                H256::from_str(&h).map_err(|e| {
-                    error!("Failed to convert '{h}' to H256: {e}");
-                    StarknetRpcApiError::BlockNotFound
-                })?,
-            )
-            .map_err(|e| {
-                error!("Failed to load Starknet block hash for Substrate block with hash '{h}': {e}");
-                StarknetRpcApiError::BlockNotFound
-            })?,
-            StarknetBlockId::BlockNumber(n) => {
-                self.client.hash(UniqueSaturatedInto::unique_saturated_into(n)).map_err(|e| {
-                    error!("Failed to retrieve the hash of block number '{n}': {e}");
-                    StarknetRpcApiError::BlockNotFound
-                })?
+        instrument(self.metrics.as_ref(), "starknet_getBlockTransactionCount", || {
+            if block_id == StarknetBlockId::BlockTag(BlockTag::Pending) {
+                return Ok(self.ready_transactions()?.len() as u128);
             }
-            StarknetBlockId::BlockTag(t) => match t {
-                mc_rpc_core::BlockTag::Latest => Some(self.client.info().best_hash),
-                mc_rpc_core::BlockTag::Pending => None,
-            },
-        }
-        .ok_or(StarknetRpcApiError::BlockNotFound)?;
 
-        let block = self
-            .overrides
-            .for_block_hash(self.client.as_ref(), substrate_block_hash)
-            .current_block(substrate_block_hash)
-            .unwrap_or_default();
+            let substrate_block_hash = self.substrate_block_hash_from_starknet(block_id)?;
 
-        Ok(block.header().transaction_count)
+            let block = self
+                .overrides
+                .for_block_hash(self.client.as_ref(), substrate_block_hash)
+                .current_block(substrate_block_hash)
+                .unwrap_or_default();
+
+            Ok(block.header().transaction_count)
+        })
     }
 
-    fn call(&self, request: FunctionCall, block_id: StarknetBlockId) -> RpcResult<Vec<String>> {
-        let substrate_block_hash = match block_id {
-            StarknetBlockId::BlockHash(h) => madara_backend_client::load_hash(
-                self.client.as_ref(),
-                &self.backend,
-                H256::from_str(&h).map_err(|e| {
-                    error!("Failed to convert '{h}' to H256: {e}");
-                    StarknetRpcApiError::BlockNotFound
-                })?,
-            )
-            .map_err(|e| {
-                error!("Failed to load Starknet block hash for Substrate block with hash '{h}': {e}");
-                StarknetRpcApiError::BlockNotFound
-            })?,
-            StarknetBlockId::BlockNumber(n) => {
-                self.client.hash(UniqueSaturatedInto::unique_saturated_into(n)).map_err(|e| {
-                    error!("Failed to retrieve the hash of block number '{n}': {e}");
-                    StarknetRpcApiError::BlockNotFound
+    fn call(&self, request: FunctionCall, block_id: StarknetBlockId) -> RpcResult<Vec<FieldElement>> {
+        instrument(self.metrics.as_ref(), "starknet_call", || {
+            let (substrate_block_hash, pending_transactions) = self.state_at(block_id)?;
+
+            let runtime_api = self.client.runtime_api();
+
+            let calldata = request.calldata.into_iter().map(U256::from).collect();
+
+            let result = runtime_api
+                .call(
+                    substrate_block_hash,
+                    pending_transactions,
+                    request.contract_address.into(),
+                    request.entry_point_selector.into(),
+                    calldata,
+                )
+                .map_err(|e| {
+                    error!("Failed to call the runtime to call a contract on block '{substrate_block_hash:?}': {e}");
+                    StarknetRpcApiError::InternalServerError
                 })?
+                .map_err(|e| {
+                    error!("Failed to call function: {:#?}", e);
+                    StarknetRpcApiError::ContractError.with_revert_error(format!("{e:?}"))
+                })?;
+
+            Ok(result.into_iter().map(FieldElement::from).collect())
+        })
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncStatusType> {
+        instrument_async(self.metrics.as_ref(), "starknet_syncing", async {
+            if !self.sync_service.is_major_syncing() {
+                return Ok(SyncStatusType::NotSyncing(false));
             }
-            StarknetBlockId::BlockTag(t) => match t {
-                mc_rpc_core::BlockTag::Latest => Some(self.client.info().best_hash),
-                mc_rpc_core::BlockTag::Pending => None,
-            },
-        }
-        .ok_or(StarknetRpcApiError::BlockNotFound)?;
 
-        let runtime_api = self.client.runtime_api();
+            let current_block_num = self.client.info().best_number;
+            let highest_block_num = self
+                .sync_service
+                .best_seen_block()
+                .await
+                .map_err(|e| {
+                    error!("Failed to retrieve the best seen block: {e}");
+                    StarknetRpcApiError::InternalServerError
+                })?
+                .unwrap_or(current_block_num);
+            // The sync may have started after the starting block was last recorded.
+            let starting_block_num = match self.starting_block.lock() {
+                Ok(starting_block) => starting_block.unwrap_or(current_block_num),
+                Err(e) => {
+                    error!("Failed to read the starting block of the sync: {e}");
+                    current_block_num
+                }
+            };
 
-        let calldata: Result<Vec<U256>, StarknetRpcApiError> = request
-            .calldata
-            .iter()
-            .map(|x| {
-                U256::from_str(x).map_err(|e| {
-                    error!("Calldata: Failed to convert '{x}' to U256: {e}");
-                    StarknetRpcApiError::InvalidCallData
-                })
+            let format_hash = |hash: Option<H256>| FieldElement::from(hash.unwrap_or_default());
+            let format_num =
+                |num: NumberFor<B>| format!("{:#x}", UniqueSaturatedInto::<u64>::unique_saturated_into(num));
+
+            Ok(SyncStatusType::Syncing(SyncStatus {
+                starting_block_hash: format_hash(self.starknet_block_hash(starting_block_num)?),
+                starting_block_num: format_num(starting_block_num),
+                current_block_hash: format_hash(self.starknet_block_hash(current_block_num)?),
+                current_block_num: format_num(current_block_num),
+                // The highest block is only known by its number until it has been imported.
+                highest_block_hash: self.starknet_block_hash(highest_block_num)?.map(FieldElement::from),
+                highest_block_num: format_num(highest_block_num),
+            }))
+        })
+        .await
+    }
+
+    fn pending_transactions(&self) -> RpcResult<Vec<RpcTransaction>> {
+        instrument(self.metrics.as_ref(), "starknet_pendingTransactions", || {
+            let mut extrinsics: Vec<<B as BlockT>::Extrinsic> = self.pool.ready().map(|tx| tx.data().clone()).collect();
+            extrinsics.extend(self.pool.futures().iter().map(|tx| tx.data().clone()));
+
+            Ok(self.pool_transactions(extrinsics)?.into_iter().map(to_rpc_transaction).collect())
+        })
+    }
+
+    fn get_transaction_receipt(&self, transaction_hash: FieldElement) -> RpcResult<TransactionReceipt> {
+        instrument(self.metrics.as_ref(), "starknet_getTransactionReceipt", || {
+            let (transaction, receipt, block) = self.transaction_receipt(transaction_hash)?;
+
+            Ok(TransactionReceipt {
+                transaction_hash: receipt.transaction_hash.into(),
+                actual_fee: receipt.actual_fee.into(),
+                status: match receipt.execution_status {
+                    TransactionExecutionStatus::Succeeded => TransactionStatus::AcceptedOnL2,
+                    TransactionExecutionStatus::Reverted => TransactionStatus::Reverted,
+                },
+                block_hash: block.block_hash,
+                block_number: block.block_number,
+                transaction_type: to_rpc_transaction_type(&transaction),
+                messages_sent: receipt.messages_sent.iter().map(to_rpc_message).collect(),
+                events: receipt.events.iter().map(to_rpc_transaction_event).collect(),
+                contract_address: deployed_contract_address(&transaction),
             })
-            .collect();
-
-        match calldata {
-            Ok(calldata) => {
-                let result = runtime_api
-                    .call(
-                        substrate_block_hash,
-                        <[u8; 32]>::from_hex(remove_prefix(&request.contract_address)).map_err(|e| {
-                            error!("Address: Failed to convert '{0}' to [u8; 32]: {e}", request.contract_address);
-                            StarknetRpcApiError::BlockNotFound
-                        })?,
-                        string_to_h256(&request.entry_point_selector).map_err(|e| {
-                            error!("Entrypoint: Failed to convert '{0}' to H256: {e}", request.entry_point_selector);
-                            StarknetRpcApiError::BlockNotFound
-                        })?,
-                        calldata,
-                    )
-                    .map_err(|e| {
-                        error!("Request parameters error: {e}");
-                        StarknetRpcApiError::InternalServerError
-                    })?
-                    .map_err(|e| {
-                        error!("Failed to call function: {:#?}", e);
-                        StarknetRpcApiError::ContractError
-                    })?;
-                Ok(result.iter().map(|x| format!("{:#x}", x)).collect())
+        })
+    }
+
+    fn trace_transaction(&self, transaction_hash: FieldElement) -> RpcResult<TransactionTrace> {
+        instrument(self.metrics.as_ref(), "starknet_traceTransaction", || {
+            Ok(to_rpc_transaction_trace(self.transaction_trace(transaction_hash)?))
+        })
+    }
+
+    fn trace_block_transactions(&self, block_hash: BlockHash) -> RpcResult<Vec<TransactionTraceWithHash>> {
+        instrument(self.metrics.as_ref(), "starknet_traceBlockTransactions", || {
+            Ok(self
+                .block_transaction_traces(block_hash)?
+                .into_iter()
+                .map(|trace| TransactionTraceWithHash {
+                    transaction_hash: trace.transaction_hash.into(),
+                    trace_root: to_rpc_transaction_trace(trace),
+                })
+                .collect())
+        })
+    }
+
+    fn simulate_transactions(
+        &self,
+        block_id: StarknetBlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        instrument(self.metrics.as_ref(), "starknet_simulateTransactions", || {
+            Ok(self
+                .simulate(block_id, transactions, simulation_flags)?
+                .into_iter()
+                .map(|simulation| SimulatedTransaction {
+                    transaction_trace: to_rpc_transaction_trace(simulation.transaction_trace),
+                    fee_estimation: to_rpc_fee_estimate(simulation.fee_estimation),
+                })
+                .collect())
+        })
+    }
+}
+
+/// Converts a broadcasted RPC transaction to a Starknet transaction, along with its hash
+pub fn to_starknet_transaction(
+    transaction: BroadcastedTransaction,
+) -> Result<Transaction, StarknetRpcApiErrorWithData> {
+    let invalid_parameter = |parameter: &str, reason: &str| {
+        error!("Invalid transaction {parameter}: {reason}");
+        StarknetRpcApiError::InvalidCallData.with_invalid_parameter(parameter, reason)
+    };
+    let to_version = |x: FieldElement| -> Result<u8, StarknetRpcApiErrorWithData> {
+        U256::from(x).try_into().map_err(|_| invalid_parameter("version", "unsupported transaction version"))
+    };
+
+    let mut transaction: Transaction = match transaction {
+        BroadcastedTransaction::Invoke(tx) => {
+            let calldata: Vec<U256> = tx.calldata.into_iter().map(U256::from).collect();
+
+            StarknetInvokeTransaction {
+                version: to_version(tx.version)?,
+                hash: H256::zero(),
+                signature: tx
+                    .signature
+                    .into_iter()
+                    .map(H256::from)
+                    .collect::<Vec<H256>>()
+                    .try_into()
+                    .map_err(|_| invalid_parameter("signature", "too many elements"))?,
+                sender_address: tx.sender_address.into(),
+                nonce: tx.nonce.into(),
+                max_fee: tx.max_fee.into(),
+                calldata: calldata.try_into().map_err(|_| invalid_parameter("calldata", "too many elements"))?,
+                entry_point_selector: tx.entry_point_selector.map(H256::from),
+            }
+            .into()
+        }
+        // TODO: decode the contract classes of the RPC format, which requires computing the class
+        // hash of their compressed program.
+        BroadcastedTransaction::Declare(_) => {
+            error!("Broadcasted declare transactions are not supported");
+            return Err(StarknetRpcApiError::UnsupportedContractClassVersion.with_invalid_parameter(
+                "contract_class",
+                "decoding contract classes from the RPC format is not supported yet",
+            ));
+        }
+        BroadcastedTransaction::DeployAccount(tx) => {
+            let class_hash = tx.class_hash.into();
+            let contract_address_salt = tx.contract_address_salt.into();
+            let calldata: Vec<U256> = tx.constructor_calldata.into_iter().map(U256::from).collect();
+            let contract_address =
+                calculate_contract_address(class_hash, contract_address_salt, &calldata).map_err(|e| {
+                    error!("Failed to compute the address of the deployed account: {:?}", e);
+                    StarknetRpcApiError::InvalidCallData
+                        .with_invalid_parameter("contract_address_salt", "cannot compute the account address")
+                })?;
+
+            StarknetDeployAccountTransaction {
+                version: to_version(tx.version)?,
+                hash: H256::zero(),
+                signature: tx
+                    .signature
+                    .into_iter()
+                    .map(H256::from)
+                    .collect::<Vec<H256>>()
+                    .try_into()
+                    .map_err(|_| invalid_parameter("signature", "too many elements"))?,
+                nonce: tx.nonce.into(),
+                max_fee: tx.max_fee.into(),
+                contract_address,
+                class_hash,
+                contract_address_salt,
+                constructor_calldata: calldata
+                    .try_into()
+                    .map_err(|_| invalid_parameter("constructor_calldata", "too many elements"))?,
             }
-            Err(e) => Err(e.into()),
+            .into()
         }
+    };
+
+    let hash = transaction
+        .compute_hash(CHAIN_ID)
+        .map_err(|_| invalid_parameter("transaction", "a field is not a valid field element"))?;
+    match &mut transaction {
+        Transaction::Invoke(tx) => tx.hash = hash,
+        Transaction::Declare(tx) => tx.hash = hash,
+        Transaction::DeployAccount(tx) => tx.hash = hash,
+        Transaction::L1Handler(tx) => tx.hash = hash,
+    }
+    Ok(transaction)
+}
+
+/// Converts a transaction trace to the RPC format
+fn to_rpc_transaction_trace(trace: TransactionTraceWrapper) -> TransactionTrace {
+    TransactionTrace {
+        validate_invocation: trace.validate_invocation.map(to_rpc_function_invocation),
+        function_invocation: trace.function_invocation.map(to_rpc_function_invocation),
+        fee_transfer_invocation: trace.fee_transfer_invocation.map(to_rpc_function_invocation),
+        signature: trace.signature.into_iter().map(FieldElement::from).collect(),
+    }
+}
+
+/// Converts a function invocation and its inner calls to the RPC format
+fn to_rpc_function_invocation(invocation: FunctionInvocationWrapper) -> FunctionInvocation {
+    FunctionInvocation {
+        contract_address: invocation.contract_address.into(),
+        entry_point_selector: invocation.entry_point_selector.into(),
+        calldata: invocation.calldata.into_iter().map(FieldElement::from).collect(),
+        caller_address: invocation.caller_address.into(),
+        class_hash: invocation.class_hash.unwrap_or_default().into(),
+        entry_point_type: to_rpc_entry_point_type(invocation.entry_point_type),
+        call_type: to_rpc_call_type(invocation.call_type),
+        result: invocation.result.into_iter().map(FieldElement::from).collect(),
+        calls: invocation.calls.into_iter().map(to_rpc_function_invocation).collect(),
+        events: invocation.events.iter().map(to_rpc_event).collect(),
+    }
+}
+
+/// Converts an entry point type to the RPC format
+fn to_rpc_entry_point_type(entry_point_type: EntryPointTypeWrapper) -> EntryPointType {
+    match entry_point_type {
+        EntryPointTypeWrapper::External => EntryPointType::External,
+        EntryPointTypeWrapper::L1Handler => EntryPointType::L1Handler,
+        EntryPointTypeWrapper::Constructor => EntryPointType::Constructor,
+    }
+}
+
+/// Converts a call type to the RPC format
+fn to_rpc_call_type(call_type: CallTypeWrapper) -> CallType {
+    match call_type {
+        CallTypeWrapper::Call => CallType::Call,
+        CallTypeWrapper::Delegate => CallType::LibraryCall,
+    }
+}
+
+/// Converts an event emitted during a function invocation to the RPC format
+fn to_rpc_event(event: &EventWrapper) -> Event {
+    Event {
+        keys: event.keys.iter().map(|k| FieldElement::from(*k)).collect(),
+        data: event.data.iter().map(|d| FieldElement::from(*d)).collect(),
+    }
+}
+
+/// Converts an event emitted by a transaction to the RPC format
+fn to_rpc_transaction_event(event: &EventWrapper) -> TransactionEvent {
+    TransactionEvent {
+        from_address: event.from_address.into(),
+        keys: event.keys.iter().map(|k| FieldElement::from(*k)).collect(),
+        data: event.data.iter().map(|d| FieldElement::from(*d)).collect(),
+    }
+}
+
+/// Converts a message sent to L1 to the RPC format
+fn to_rpc_message(message: &MessageToL1Wrapper) -> MessageToL1 {
+    MessageToL1 {
+        to_address: H256::from(message.to_address).into(),
+        payload: message.payload.iter().map(|x| FieldElement::from(*x)).collect(),
+    }
+}
+
+/// Returns the type of a transaction in the RPC format
+fn to_rpc_transaction_type(transaction: &Transaction) -> TransactionType {
+    match transaction {
+        Transaction::Invoke(_) => TransactionType::Invoke,
+        Transaction::Declare(_) => TransactionType::Declare,
+        Transaction::DeployAccount(_) => TransactionType::DeployAccount,
+        Transaction::L1Handler(_) => TransactionType::L1Handler,
+    }
+}
+
+/// Returns the address of the contract deployed by a deploy account transaction, which its
+/// receipt reports
+fn deployed_contract_address(transaction: &Transaction) -> Option<FieldElement> {
+    match transaction {
+        Transaction::DeployAccount(tx) => Some(tx.contract_address.into()),
+        _ => None,
     }
 }
 
-/// Removes the "0x" prefix from a given hexadecimal string
-fn remove_prefix(input: &str) -> &str {
-    input.strip_prefix("0x").unwrap_or(input)
+/// Converts a fee estimation to the RPC format
+fn to_rpc_fee_estimate(fee_estimate: FeeEstimateWrapper) -> FeeEstimate {
+    FeeEstimate {
+        gas_consumed: fee_estimate.gas_consumed.into(),
+        gas_price: fee_estimate.gas_price.into(),
+        overall_fee: fee_estimate.overall_fee.into(),
+    }
 }
 
-/// Converts a hexadecimal string to an H256 value, padding with zero bytes on the left if necessary
-fn string_to_h256(hex_str: &str) -> Result<H256, String> {
-    let hex_str = remove_prefix(hex_str);
-    let mut padded_hex_str = hex_str.to_string();
-    while padded_hex_str.len() < 64 {
-        padded_hex_str.insert(0, '0');
+/// Converts a Starknet transaction to the RPC transaction format
+pub fn to_rpc_transaction(transaction: Transaction) -> RpcTransaction {
+    let transaction_hash = transaction.hash().into();
+    let version = FieldElement::from(u64::from(transaction.version()));
+    let signature = transaction.signature().iter().map(|s| FieldElement::from(*s)).collect();
+    let nonce = transaction.nonce().into();
+    let to_calldata =
+        |calldata: &[U256]| -> Vec<FieldElement> { calldata.iter().map(|x| FieldElement::from(*x)).collect() };
+
+    match transaction {
+        Transaction::Invoke(tx) => RpcTransaction::Invoke(InvokeTransaction {
+            transaction_hash,
+            max_fee: tx.max_fee.into(),
+            version,
+            signature,
+            nonce,
+            sender_address: tx.sender_address.into(),
+            calldata: to_calldata(&tx.calldata),
+            entry_point_selector: tx.entry_point_selector.map(FieldElement::from),
+        }),
+        Transaction::Declare(tx) => RpcTransaction::Declare(DeclareTransaction {
+            transaction_hash,
+            max_fee: tx.max_fee.into(),
+            version,
+            signature,
+            nonce,
+            class_hash: tx.class_hash.into(),
+            sender_address: tx.sender_address.into(),
+        }),
+        Transaction::DeployAccount(tx) => RpcTransaction::DeployAccount(DeployAccountTransaction {
+            transaction_hash,
+            max_fee: tx.max_fee.into(),
+            version,
+            signature,
+            nonce,
+            contract_address_salt: tx.contract_address_salt.into(),
+            constructor_calldata: to_calldata(&tx.constructor_calldata),
+            class_hash: tx.class_hash.into(),
+        }),
+        Transaction::L1Handler(tx) => RpcTransaction::L1Handler(L1HandlerTransaction {
+            transaction_hash,
+            version,
+            nonce,
+            contract_address: tx.contract_address.into(),
+            entry_point_selector: tx.entry_point_selector.into(),
+            calldata: to_calldata(&tx.calldata),
+        }),
     }
-    let bytes =
-        Vec::from_hex(&padded_hex_str).map_err(|e| format!("Failed to convert hex string to bytes: {:?}", e))?;
-    Ok(H256::from_slice(&bytes))
 }
 
 #[cfg(test)]
 mod tests {
+    use mc_rpc_core::BroadcastedInvokeTransaction;
+    use mp_starknet::transaction::types::DeclareTransaction as StarknetDeclareTransaction;
+    use sp_core::H160;
+
     use super::*;
 
-    // Test case for the string_to_h256 function
     #[test]
-    fn test_string_to_h256() {
-        // Test case 1: Valid input with 64 characters
-        let hex_str_1 = "0x0222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7";
-        let expected_result_1 = H256::from_str(hex_str_1).unwrap();
-        assert_eq!(string_to_h256(hex_str_1).unwrap(), expected_result_1);
-
-        // Test case 2: Input with leading zeros
-        let hex_str_2 = "0x0123456789abcdef";
-        let expected_result_2 =
-            H256::from_str("0x0000000000000000000000000000000000000000000000000123456789abcdef").unwrap();
-        assert_eq!(string_to_h256(hex_str_2).unwrap(), expected_result_2);
-
-        // Test case 3: Input with missing "0x" prefix
-        let hex_str_3 = "222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7";
-        let expected_result_3 =
-            H256::from_str("0x0222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7").unwrap();
-        assert_eq!(string_to_h256(hex_str_3).unwrap(), expected_result_3);
-
-        // Test case 4: Input with invalid length
-        let hex_str_4 = "0x222882e457847df7ebaf981db2ff8ebb22c19d5b0a6a41dcc13cc2d775fbeb7111111";
-        assert!(string_to_h256(hex_str_4).is_err());
+    fn pending_transactions_report_their_max_fee() {
+        let transaction = Transaction::from(StarknetInvokeTransaction {
+            max_fee: U256::from(1_000),
+            ..StarknetInvokeTransaction::default()
+        });
+
+        match to_rpc_transaction(transaction) {
+            RpcTransaction::Invoke(tx) => assert_eq!(tx.max_fee, FieldElement::from(1_000_u64)),
+            tx => panic!("unexpected transaction: {tx:?}"),
+        }
+
+        let transaction = Transaction::from(StarknetDeclareTransaction {
+            max_fee: U256::from(2_000),
+            ..StarknetDeclareTransaction::default()
+        });
+
+        match to_rpc_transaction(transaction) {
+            RpcTransaction::Declare(tx) => assert_eq!(tx.max_fee, FieldElement::from(2_000_u64)),
+            tx => panic!("unexpected transaction: {tx:?}"),
+        }
+    }
+
+    #[test]
+    fn receipts_report_the_messages_sent_to_l1() {
+        let message = MessageToL1Wrapper {
+            from_address: [1; 32],
+            to_address: H160::from_low_u64_be(2),
+            payload: vec![H256::from_low_u64_be(3), H256::from_low_u64_be(4)].try_into().unwrap(),
+        };
+
+        assert_eq!(
+            to_rpc_message(&message),
+            MessageToL1 {
+                to_address: FieldElement::from(2_u64),
+                payload: vec![FieldElement::from(3_u64), FieldElement::from(4_u64)],
+            }
+        );
+    }
+
+    #[test]
+    fn broadcasted_transactions_are_hashed() {
+        let transaction = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction {
+            max_fee: FieldElement::from(3_u64),
+            version: FieldElement::from(1_u64),
+            signature: vec![FieldElement::from(10_u64)],
+            nonce: FieldElement::from(2_u64),
+            sender_address: FieldElement::from(1_u64),
+            calldata: vec![FieldElement::from(4_u64), FieldElement::from(5_u64)],
+            entry_point_selector: None,
+        });
+
+        let transaction = to_starknet_transaction(transaction).unwrap();
+        assert_ne!(transaction.hash(), H256::zero());
+        assert_eq!(transaction.hash(), transaction.compute_hash(CHAIN_ID).unwrap());
+    }
+
+    #[test]
+    fn broadcasted_declare_transactions_are_rejected_as_unsupported() {
+        let transaction = BroadcastedTransaction::Declare(Default::default());
+
+        let error = to_starknet_transaction(transaction).unwrap_err();
+        assert_eq!(error.error, StarknetRpcApiError::UnsupportedContractClassVersion);
+        assert_eq!(
+            error.data,
+            StarknetRpcApiErrorData::InvalidParameter {
+                parameter: "contract_class".to_string(),
+                reason: "decoding contract classes from the RPC format is not supported yet".to_string(),
+            }
+        );
     }
 }
@@ -31,3 +31,24 @@ where
     }
     false
 }
+
+pub fn load_transaction_hash<B: BlockT, C>(
+    client: &C,
+    backend: &mc_db::Backend<B>,
+    transaction_hash: H256,
+) -> Result<Option<B::Hash>, String>
+where
+    B: BlockT,
+    C: HeaderBackend<B> + 'static,
+{
+    let substrate_hashes = backend.mapping().transaction_block_hash(&transaction_hash)?;
+
+    if let Some(substrate_hashes) = substrate_hashes {
+        for substrate_hash in substrate_hashes {
+            if is_canon::<B, C>(client, substrate_hash) {
+                return Ok(Some(substrate_hash));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,132 @@
+//! Prometheus metrics of the Starknet RPC handlers
+//!
+//! Methods are labelled by their name in the spec, whatever the version of the spec they are served
+//! under: methods forwarded from a version to another are only recorded once.
+
+use std::future::Future;
+use std::time::Instant;
+
+use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::types::error::{CallError, ErrorCode};
+use prometheus_endpoint::{
+    exponential_buckets, register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// Metrics of the Starknet RPC handlers
+#[derive(Clone)]
+pub struct RpcMetrics {
+    /// Number of calls, by method
+    calls: CounterVec<U64>,
+    /// Number of failed calls, by method and error code
+    errors: CounterVec<U64>,
+    /// Time spent handling calls, by method
+    call_duration: HistogramVec,
+}
+
+impl RpcMetrics {
+    /// Creates the metrics and registers them in a Prometheus registry
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            calls: register(
+                CounterVec::new(Opts::new("madara_rpc_calls_total", "Number of Starknet RPC calls"), &["method"])?,
+                registry,
+            )?,
+            errors: register(
+                CounterVec::new(
+                    Opts::new("madara_rpc_errors_total", "Number of failed Starknet RPC calls, by error code"),
+                    &["method", "code"],
+                )?,
+                registry,
+            )?,
+            call_duration: register(
+                HistogramVec::new(
+                    HistogramOpts::new("madara_rpc_call_duration_seconds", "Time spent handling Starknet RPC calls")
+                        // From 1ms to ~16s
+                        .buckets(exponential_buckets(0.001, 2.0, 15)?),
+                    &["method"],
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    /// Records a call of a method, which lasted since `started`
+    fn observe<T>(&self, method: &str, started: Instant, result: &RpcResult<T>) {
+        self.calls.with_label_values(&[method]).inc();
+        self.call_duration.with_label_values(&[method]).observe(started.elapsed().as_secs_f64());
+        if let Err(e) = result {
+            self.errors.with_label_values(&[method, &error_code(e).to_string()]).inc();
+        }
+    }
+}
+
+/// Runs the handler of a method, recording the call if there are metrics
+pub(crate) fn instrument<T>(
+    metrics: Option<&RpcMetrics>,
+    method: &str,
+    handler: impl FnOnce() -> RpcResult<T>,
+) -> RpcResult<T> {
+    let started = Instant::now();
+    let result = handler();
+    if let Some(metrics) = metrics {
+        metrics.observe(method, started, &result);
+    }
+    result
+}
+
+/// Runs the asynchronous handler of a method, recording the call if there are metrics
+pub(crate) async fn instrument_async<T>(
+    metrics: Option<&RpcMetrics>,
+    method: &str,
+    handler: impl Future<Output = RpcResult<T>>,
+) -> RpcResult<T> {
+    let started = Instant::now();
+    let result = handler.await;
+    if let Some(metrics) = metrics {
+        metrics.observe(method, started, &result);
+    }
+    result
+}
+
+/// Returns the JSON-RPC code of an error, which is the `StarknetRpcApiError` code for Starknet
+/// errors
+fn error_code(error: &JsonRpseeError) -> i32 {
+    match error {
+        JsonRpseeError::Call(CallError::Custom(error_object)) => error_object.code(),
+        JsonRpseeError::Call(CallError::InvalidParams(_)) => ErrorCode::InvalidParams.code(),
+        _ => ErrorCode::InternalError.code(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StarknetRpcApiError;
+
+    #[test]
+    fn calls_and_errors_are_recorded_by_method() {
+        let metrics = RpcMetrics::register(&Registry::new()).unwrap();
+
+        instrument(Some(&metrics), "starknet_call", || Ok(())).unwrap();
+        instrument::<()>(Some(&metrics), "starknet_call", || Err(StarknetRpcApiError::BlockNotFound.into()))
+            .unwrap_err();
+        instrument::<()>(Some(&metrics), "starknet_call", || {
+            Err(StarknetRpcApiError::ContractError.with_revert_error("out of gas").into())
+        })
+        .unwrap_err();
+
+        assert_eq!(metrics.calls.with_label_values(&["starknet_call"]).get(), 3);
+        assert_eq!(metrics.errors.with_label_values(&["starknet_call", "24"]).get(), 1);
+        assert_eq!(metrics.errors.with_label_values(&["starknet_call", "40"]).get(), 1);
+        assert_eq!(metrics.call_duration.with_label_values(&["starknet_call"]).get_sample_count(), 3);
+        assert_eq!(metrics.calls.with_label_values(&["starknet_blockNumber"]).get(), 0);
+    }
+
+    #[test]
+    fn metrics_are_registered_once() {
+        let registry = Registry::new();
+
+        RpcMetrics::register(&registry).unwrap();
+        assert!(RpcMetrics::register(&registry).is_err());
+    }
+}
@@ -0,0 +1,531 @@
+//! Starknet pubsub server API implementation
+//!
+//! Subscriptions are driven by the notifications the `MappingSyncWorker` sends once it has synced
+//! a new block in the Madara db.
+
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use futures::channel::mpsc;
+use futures::{future, stream, FutureExt, Stream, StreamExt};
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::SubscriptionSink;
+use log::error;
+use mc_mapping_sync::{StarknetBlockNotification, StarknetBlockNotificationSinks};
+use mc_rpc_core::{BlockHeader, EmittedEvent, EventFilter, FieldElement, StarknetPubSubApiServer, TransactionStatus};
+use mc_storage::OverrideHandle;
+use mp_starknet::block::Block as StarknetBlock;
+use mp_starknet::transaction::types::{EventWrapper, TransactionExecutionStatus, TransactionReceiptWrapper};
+use pallet_starknet::runtime_api::StarknetRuntimeApi;
+use sc_client_api::backend::{Backend, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TxHash};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::madara_backend_client;
+
+/// A Starknet pubsub server for Madara
+pub struct StarknetPubSub<B: BlockT, BE, C, P: TransactionPool> {
+    client: Arc<C>,
+    backend: Arc<mc_db::Backend<B>>,
+    overrides: Arc<OverrideHandle<B>>,
+    pool: Arc<P>,
+    pool_index: Arc<Mutex<PoolIndex<P>>>,
+    executor: SubscriptionTaskExecutor,
+    notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
+    _marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, BE, C, P: TransactionPool> StarknetPubSub<B, BE, C, P> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<mc_db::Backend<B>>,
+        overrides: Arc<OverrideHandle<B>>,
+        pool: Arc<P>,
+        executor: SubscriptionTaskExecutor,
+        notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
+    ) -> Self {
+        Self {
+            client,
+            backend,
+            overrides,
+            pool,
+            pool_index: Arc::new(Mutex::new(PoolIndex::default())),
+            executor,
+            notification_sinks,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Registers a new sink and returns the stream of the blocks synced from now on
+    fn block_notification_stream(&self) -> mpsc::UnboundedReceiver<StarknetBlockNotification<B>> {
+        let (sink, stream) = mpsc::unbounded();
+        match self.notification_sinks.lock() {
+            Ok(mut sinks) => sinks.push(sink),
+            Err(e) => error!("Failed to register a block notification sink: {e}"),
+        }
+        stream
+    }
+}
+
+impl<B, BE, C, P> StarknetPubSubApiServer for StarknetPubSub<B, BE, C, P>
+where
+    B: BlockT,
+    BE: Backend<B> + 'static,
+    C: HeaderBackend<B> + StorageProvider<B, BE> + Send + Sync + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
+{
+    fn subscribe_new_heads(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        sink.accept()?;
+
+        let client = self.client.clone();
+        let overrides = self.overrides.clone();
+        let synced_blocks =
+            self.block_notification_stream().map(|notification| (notification.hash, notification.is_new_best));
+        let stream =
+            new_heads(synced_blocks, move |hash| overrides.for_block_hash(client.as_ref(), hash).current_block(hash));
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+        self.executor.spawn("starknet-rpc-subscription", Some("rpc"), fut.boxed());
+
+        Ok(())
+    }
+
+    fn subscribe_events(&self, mut sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
+        sink.accept()?;
+
+        let client = self.client.clone();
+        let overrides = self.overrides.clone();
+        let stream = self
+            .block_notification_stream()
+            .filter_map(move |notification| {
+                let events = if notification.is_new_best {
+                    match block_events(client.as_ref(), overrides.as_ref(), notification.hash, &filter) {
+                        Ok(events) => Some(stream::iter(events)),
+                        Err(e) => {
+                            error!("Failed to retrieve the events of block '{:?}': {e}", notification.hash);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                future::ready(events)
+            })
+            .flatten();
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+        self.executor.spawn("starknet-rpc-subscription", Some("rpc"), fut.boxed());
+
+        Ok(())
+    }
+
+    fn subscribe_transaction_status(
+        &self,
+        mut sink: SubscriptionSink,
+        transaction_hash: FieldElement,
+    ) -> SubscriptionResult {
+        let transaction_hash = H256::from(transaction_hash);
+        sink.accept()?;
+
+        let client = self.client.clone();
+        let backend = self.backend.clone();
+        let pool = self.pool.clone();
+        let pool_index = self.pool_index.clone();
+        let status = move |was_received: bool| {
+            transaction_status(
+                execution_status(client.as_ref(), backend.as_ref(), transaction_hash),
+                is_in_pool(client.as_ref(), pool.as_ref(), &pool_index, transaction_hash),
+                was_received,
+            )
+        };
+        let stream = status_updates(self.block_notification_stream(), status);
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+        self.executor.spawn("starknet-rpc-subscription", Some("rpc"), fut.boxed());
+
+        Ok(())
+    }
+}
+
+/// Returns the headers of the new best blocks, given the synced blocks along with whether they
+/// are the new best block
+fn new_heads<H>(
+    synced_blocks: impl Stream<Item = (H, bool)>,
+    block: impl Fn(H) -> Option<StarknetBlock>,
+) -> impl Stream<Item = BlockHeader> {
+    synced_blocks.filter_map(move |(hash, is_new_best)| {
+        future::ready(if is_new_best { block(hash).map(|block| to_rpc_block_header(&block)) } else { None })
+    })
+}
+
+/// Returns whether an event matches the filter
+fn matches_filter(filter: &EventFilter, event: &EventWrapper) -> bool {
+    let address_matches = filter.address.map_or(true, |address| address == FieldElement::from(event.from_address));
+    let keys_match = filter.keys.iter().enumerate().all(|(position, keys)| {
+        keys.is_empty() || event.keys.get(position).map_or(false, |key| keys.contains(&FieldElement::from(*key)))
+    });
+
+    address_matches && keys_match
+}
+
+/// Returns the events emitted in a block which match the filter
+fn block_events<B, BE, C>(
+    client: &C,
+    overrides: &OverrideHandle<B>,
+    substrate_block_hash: B::Hash,
+    filter: &EventFilter,
+) -> Result<Vec<EmittedEvent>, String>
+where
+    B: BlockT,
+    BE: Backend<B>,
+    C: HeaderBackend<B> + StorageProvider<B, BE> + ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+{
+    let block = overrides
+        .for_block_hash(client, substrate_block_hash)
+        .current_block(substrate_block_hash)
+        .ok_or("Starknet block not found")?;
+    let receipts = client.runtime_api().transaction_receipts(substrate_block_hash).map_err(|e| format!("{:?}", e))?;
+
+    Ok(emitted_events(&block, &receipts, filter))
+}
+
+/// Returns the events emitted by the transactions of a block which match the filter
+fn emitted_events(
+    block: &StarknetBlock,
+    receipts: &[TransactionReceiptWrapper],
+    filter: &EventFilter,
+) -> Vec<EmittedEvent> {
+    let block_hash: FieldElement = block.header().hash().into();
+    let block_number = block.header().block_number.as_u64();
+
+    receipts
+        .iter()
+        .flat_map(|receipt| {
+            receipt.events.iter().filter(|event| matches_filter(filter, event)).map(move |event| EmittedEvent {
+                from_address: event.from_address.into(),
+                keys: event.keys.iter().map(|key| FieldElement::from(*key)).collect(),
+                data: event.data.iter().map(|data| FieldElement::from(*data)).collect(),
+                block_hash,
+                block_number,
+                transaction_hash: receipt.transaction_hash.into(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the status updates of a transaction: its current status, then its new status each time
+/// it changes after a block is synced, until the status is final
+///
+/// `status` returns the status of the transaction given whether it was waiting in the pool
+/// before, so that a transaction dropped from the pool without being included is rejected.
+fn status_updates<N>(
+    notifications: N,
+    mut status: impl FnMut(bool) -> TransactionStatus + Send + 'static,
+) -> impl Stream<Item = TransactionStatus> + Send
+where
+    N: Stream + Send + 'static,
+{
+    let current_status = status(false);
+    if is_final(&current_status) {
+        return stream::iter(vec![current_status]).left_stream();
+    }
+
+    let updates = notifications
+        .scan(current_status.clone(), move |last_status, _| {
+            if is_final(last_status) {
+                return future::ready(None);
+            }
+            let new_status = status(*last_status == TransactionStatus::Received);
+            let update = (new_status != *last_status).then(|| new_status.clone());
+            *last_status = new_status;
+            future::ready(Some(update))
+        })
+        .filter_map(future::ready);
+    stream::iter(vec![current_status]).chain(updates).right_stream()
+}
+
+/// Returns the status of a transaction, given the execution status of the transaction if it was
+/// included in a block, whether it is waiting in the pool and whether it was waiting in the pool
+/// before
+fn transaction_status(
+    execution_status: Option<TransactionExecutionStatus>,
+    is_in_pool: bool,
+    was_received: bool,
+) -> TransactionStatus {
+    match execution_status {
+        Some(TransactionExecutionStatus::Succeeded) => TransactionStatus::AcceptedOnL2,
+        Some(TransactionExecutionStatus::Reverted) => TransactionStatus::Reverted,
+        None if is_in_pool => TransactionStatus::Received,
+        None if was_received => TransactionStatus::Rejected,
+        None => TransactionStatus::NotReceived,
+    }
+}
+
+/// Returns whether a transaction status can no longer change
+fn is_final(status: &TransactionStatus) -> bool {
+    matches!(status, TransactionStatus::AcceptedOnL2 | TransactionStatus::Reverted | TransactionStatus::Rejected)
+}
+
+/// Returns the execution status of a Starknet transaction, if it was included in a synced block
+fn execution_status<B, C>(
+    client: &C,
+    backend: &mc_db::Backend<B>,
+    transaction_hash: H256,
+) -> Option<TransactionExecutionStatus>
+where
+    B: BlockT,
+    C: HeaderBackend<B> + ProvideRuntimeApi<B> + 'static,
+    C::Api: StarknetRuntimeApi<B>,
+{
+    let substrate_block_hash =
+        madara_backend_client::load_transaction_hash(client, backend, transaction_hash).ok()??;
+
+    match client.runtime_api().transaction_receipts(substrate_block_hash) {
+        Ok(receipts) => receipts
+            .into_iter()
+            .find(|receipt| receipt.transaction_hash == transaction_hash)
+            .map(|receipt| receipt.execution_status),
+        Err(e) => {
+            error!("Failed to retrieve the receipts of block '{substrate_block_hash:?}': {e}");
+            None
+        }
+    }
+}
+
+/// Returns whether a Starknet transaction is waiting in the transaction pool
+fn is_in_pool<B, C, P>(client: &C, pool: &P, pool_index: &Mutex<PoolIndex<P>>, transaction_hash: H256) -> bool
+where
+    B: BlockT,
+    C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B>,
+{
+    match pool_index.lock() {
+        Ok(mut pool_index) => {
+            pool_index.refresh(client, pool);
+            pool_index.contains(&transaction_hash)
+        }
+        Err(e) => {
+            error!("Failed to read the index of the transaction pool: {e}");
+            false
+        }
+    }
+}
+
+/// The Starknet transactions waiting in the transaction pool, shared by the subscriptions
+///
+/// Each extrinsic of the pool is only decoded once, when it is first seen, and the index is only
+/// refreshed when the pool or the best block changed since the last refresh.
+struct PoolIndex<P: TransactionPool> {
+    /// The Starknet hash of each extrinsic of the pool, `None` for other extrinsics
+    starknet_hashes: HashMap<TxHash<P>, Option<H256>>,
+    /// The Starknet transactions of the pool
+    transactions: HashSet<H256>,
+    /// The best block and the pool status at the last refresh
+    refreshed_at: Option<(<P::Block as BlockT>::Hash, [usize; 4])>,
+}
+
+impl<P: TransactionPool> Default for PoolIndex<P> {
+    fn default() -> Self {
+        Self { starknet_hashes: HashMap::new(), transactions: HashSet::new(), refreshed_at: None }
+    }
+}
+
+impl<P: TransactionPool> PoolIndex<P> {
+    /// Returns whether a Starknet transaction is in the pool
+    fn contains(&self, transaction_hash: &H256) -> bool {
+        self.transactions.contains(transaction_hash)
+    }
+
+    /// Indexes the extrinsics added to the pool, and forgets the ones which left it
+    fn refresh<C>(&mut self, client: &C, pool: &P)
+    where
+        C: HeaderBackend<P::Block> + ProvideRuntimeApi<P::Block>,
+        C::Api: StarknetRuntimeApi<P::Block>,
+    {
+        let best_hash = client.info().best_hash;
+        let status = pool.status();
+        let refreshed_at = Some((best_hash, [status.ready, status.ready_bytes, status.future, status.future_bytes]));
+        if self.refreshed_at == refreshed_at {
+            return;
+        }
+
+        let mut known_hashes = std::mem::take(&mut self.starknet_hashes);
+        let mut index = |transaction: &P::InPoolTransaction| {
+            let starknet_hash = known_hashes
+                .remove(transaction.hash())
+                .unwrap_or_else(|| starknet_hash(client, best_hash, transaction.data().clone()));
+            self.starknet_hashes.insert(transaction.hash().clone(), starknet_hash);
+        };
+        pool.ready().for_each(|transaction| index(&*transaction));
+        pool.futures().iter().for_each(&mut index);
+
+        self.transactions = self.starknet_hashes.values().flatten().copied().collect();
+        self.refreshed_at = refreshed_at;
+    }
+}
+
+/// Returns the hash of the Starknet transaction wrapped in an extrinsic, if any
+fn starknet_hash<B, C>(client: &C, best_hash: B::Hash, extrinsic: B::Extrinsic) -> Option<H256>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+{
+    match client.runtime_api().extrinsic_filter(best_hash, vec![extrinsic]) {
+        Ok(transactions) => transactions.first().map(|transaction| transaction.hash()),
+        Err(e) => {
+            error!("Failed to filter the Starknet transaction out of a pool extrinsic: {e}");
+            None
+        }
+    }
+}
+
+/// Converts the header of a Starknet block to the RPC format
+fn to_rpc_block_header(block: &StarknetBlock) -> BlockHeader {
+    let header = block.header();
+    BlockHeader {
+        block_hash: header.hash().into(),
+        parent_hash: header.parent_block_hash.into(),
+        block_number: header.block_number.as_u64(),
+        new_root: header.global_state_root.into(),
+        timestamp: header.block_timestamp,
+        sequencer_address: header.sequencer_address.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use futures::executor::block_on;
+    use mp_starknet::block::{BlockTransactions, Header as StarknetHeader};
+    use sp_core::U256;
+
+    use super::*;
+
+    fn block(block_number: u64) -> StarknetBlock {
+        StarknetBlock::new(
+            StarknetHeader { block_number: U256::from(block_number), ..StarknetHeader::default() },
+            BlockTransactions::default(),
+        )
+    }
+
+    fn event(from_address: u64, key: u64) -> EventWrapper {
+        EventWrapper {
+            keys: vec![H256::from_low_u64_be(key)].try_into().unwrap(),
+            data: vec![H256::from_low_u64_be(7)].try_into().unwrap(),
+            from_address: H256::from_low_u64_be(from_address).to_fixed_bytes(),
+        }
+    }
+
+    /// Returns the status updates of a transaction whose successive statuses are scripted
+    fn scripted_status_updates(statuses: Vec<TransactionStatus>, blocks: usize) -> Vec<TransactionStatus> {
+        let mut statuses = VecDeque::from(statuses);
+        let status = move |_| statuses.pop_front().expect("no more statuses");
+        block_on(status_updates(stream::iter(vec![(); blocks]), status).collect())
+    }
+
+    #[test]
+    fn new_heads_are_the_headers_of_the_new_best_blocks() {
+        let synced_blocks = stream::iter(vec![(1_u64, true), (2, false), (3, true)]);
+
+        let headers: Vec<BlockHeader> = block_on(new_heads(synced_blocks, |number| Some(block(number))).collect());
+        assert_eq!(headers, vec![to_rpc_block_header(&block(1)), to_rpc_block_header(&block(3))]);
+        assert_eq!(headers[1].block_number, 3);
+    }
+
+    #[test]
+    fn emitted_events_match_the_filter() {
+        let receipts = vec![
+            TransactionReceiptWrapper {
+                transaction_hash: H256::from_low_u64_be(10),
+                events: vec![event(1, 2), event(3, 4)].try_into().unwrap(),
+                ..TransactionReceiptWrapper::default()
+            },
+            TransactionReceiptWrapper {
+                transaction_hash: H256::from_low_u64_be(11),
+                events: vec![event(1, 5)].try_into().unwrap(),
+                ..TransactionReceiptWrapper::default()
+            },
+        ];
+        let filter = EventFilter {
+            address: Some(FieldElement::from(1_u64)),
+            keys: vec![vec![FieldElement::from(2_u64), FieldElement::from(5_u64)]],
+        };
+
+        let events = emitted_events(&block(4), &receipts, &filter);
+        assert_eq!(
+            events.iter().map(|event| (event.transaction_hash, event.keys.clone())).collect::<Vec<_>>(),
+            vec![
+                (FieldElement::from(10_u64), vec![FieldElement::from(2_u64)]),
+                (FieldElement::from(11_u64), vec![FieldElement::from(5_u64)]),
+            ]
+        );
+        assert!(events.iter().all(|event| event.block_number == 4 && event.data == vec![FieldElement::from(7_u64)]));
+    }
+
+    #[test]
+    fn transaction_status_reflects_the_pool_and_the_execution() {
+        use TransactionExecutionStatus::{Reverted, Succeeded};
+
+        assert_eq!(transaction_status(Some(Succeeded), false, true), TransactionStatus::AcceptedOnL2);
+        assert_eq!(transaction_status(Some(Reverted), false, true), TransactionStatus::Reverted);
+        assert_eq!(transaction_status(None, true, false), TransactionStatus::Received);
+        assert_eq!(transaction_status(None, false, true), TransactionStatus::Rejected);
+        assert_eq!(transaction_status(None, false, false), TransactionStatus::NotReceived);
+    }
+
+    #[test]
+    fn status_updates_end_once_the_transaction_is_accepted() {
+        let updates = scripted_status_updates(
+            vec![TransactionStatus::Received, TransactionStatus::Received, TransactionStatus::AcceptedOnL2],
+            5,
+        );
+
+        assert_eq!(updates, vec![TransactionStatus::Received, TransactionStatus::AcceptedOnL2]);
+    }
+
+    #[test]
+    fn status_updates_report_reverted_transactions() {
+        let updates = scripted_status_updates(
+            vec![TransactionStatus::NotReceived, TransactionStatus::Received, TransactionStatus::Reverted],
+            5,
+        );
+
+        assert_eq!(
+            updates,
+            vec![TransactionStatus::NotReceived, TransactionStatus::Received, TransactionStatus::Reverted]
+        );
+    }
+
+    #[test]
+    fn status_updates_report_transactions_dropped_from_the_pool_as_rejected() {
+        let mut in_pool = VecDeque::from(vec![true, true, false]);
+        let status = move |was_received| transaction_status(None, in_pool.pop_front().unwrap(), was_received);
+
+        let updates: Vec<TransactionStatus> = block_on(status_updates(stream::iter(vec![(); 5]), status).collect());
+        assert_eq!(updates, vec![TransactionStatus::Received, TransactionStatus::Rejected]);
+    }
+
+    #[test]
+    fn status_updates_of_a_final_transaction_end_immediately() {
+        let updates = scripted_status_updates(vec![TransactionStatus::Reverted], 5);
+
+        assert_eq!(updates, vec![TransactionStatus::Reverted]);
+    }
+}
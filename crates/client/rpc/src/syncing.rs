@@ -0,0 +1,79 @@
+//! Tracking of the block the node started syncing from
+//!
+//! The sync oracle only tells whether the node is major syncing, so the best block is recorded
+//! when a major sync starts, and forgotten once the node caught up with its peers.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_timer::Delay;
+use log::error;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+
+/// The number of the block the ongoing major sync started from, if the node is syncing
+pub type SyncStartingBlock<B> = Arc<Mutex<Option<NumberFor<B>>>>;
+
+/// Records the block each major sync starts from, polling the sync oracle every `interval`
+///
+/// The node should spawn it among its services, sharing `starting_block` with the RPC server.
+pub async fn track_sync_start<B, C>(
+    client: Arc<C>,
+    sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
+    starting_block: SyncStartingBlock<B>,
+    interval: Duration,
+) where
+    B: BlockT,
+    C: HeaderBackend<B>,
+{
+    loop {
+        let is_major_syncing = sync_oracle.is_major_syncing();
+        match starting_block.lock() {
+            Ok(mut starting_block) => {
+                update_starting_block(&mut starting_block, is_major_syncing, client.info().best_number)
+            }
+            Err(e) => error!("Failed to record the starting block of the sync: {e}"),
+        }
+        Delay::new(interval).await;
+    }
+}
+
+/// Records the best block when a major sync starts, and forgets it once the sync is over
+fn update_starting_block<N>(starting_block: &mut Option<N>, is_major_syncing: bool, best_number: N) {
+    if !is_major_syncing {
+        *starting_block = None;
+    } else if starting_block.is_none() {
+        *starting_block = Some(best_number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_best_block_is_recorded_when_the_sync_starts() {
+        let mut starting_block = None;
+
+        update_starting_block(&mut starting_block, false, 3);
+        assert_eq!(starting_block, None);
+
+        update_starting_block(&mut starting_block, true, 5);
+        assert_eq!(starting_block, Some(5));
+    }
+
+    #[test]
+    fn the_starting_block_is_kept_until_the_sync_is_over() {
+        let mut starting_block = Some(5);
+
+        update_starting_block(&mut starting_block, true, 8);
+        assert_eq!(starting_block, Some(5));
+
+        update_starting_block(&mut starting_block, false, 13);
+        assert_eq!(starting_block, None);
+
+        update_starting_block(&mut starting_block, true, 13);
+        assert_eq!(starting_block, Some(13));
+    }
+}
@@ -0,0 +1,181 @@
+//! Starknet RPC server API implementation, as of the v0.4.0 spec
+//!
+//! Methods whose format did not change since v0.3.0 are forwarded to the default implementation,
+//! which records their metrics.
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use mc_rpc_core::v0_4::{
+    ExecuteInvocation, ExecutionStatus, FunctionInvocation, SimulatedTransaction,
+    StarknetRpcApiServer as StarknetRpcApiV0_4Server, TransactionReceipt, TransactionTrace, TransactionTraceWithHash,
+    SPEC_VERSION,
+};
+use mc_rpc_core::{
+    BlockHash, BlockHashAndNumber, BlockId as StarknetBlockId, BlockNumber, BroadcastedTransaction, FieldElement,
+    FunctionCall, SimulationFlag, SyncStatusType, Transaction as RpcTransaction, TransactionStatus,
+};
+use mp_starknet::transaction::types::{
+    FunctionInvocationWrapper, TransactionExecutionStatus, TransactionTraceWrapper, TxType,
+};
+use pallet_starknet::runtime_api::StarknetRuntimeApi;
+use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::BlockBackend;
+use sc_transaction_pool_api::TransactionPool;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::errors::StarknetRpcApiError;
+use crate::metrics::instrument;
+use crate::{
+    deployed_contract_address, to_rpc_call_type, to_rpc_entry_point_type, to_rpc_event, to_rpc_fee_estimate,
+    to_rpc_message, to_rpc_transaction_event, to_rpc_transaction_type, Starknet, StarknetRpcApiServer,
+};
+
+#[async_trait]
+impl<B, BE, C, P> StarknetRpcApiV0_4Server for Starknet<B, BE, C, P>
+where
+    B: BlockT,
+    BE: Backend<B> + 'static,
+    C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+    C: ProvideRuntimeApi<B>,
+    C::Api: StarknetRuntimeApi<B>,
+    P: TransactionPool<Block = B> + 'static,
+{
+    fn spec_version(&self) -> RpcResult<String> {
+        instrument(self.metrics.as_ref(), "starknet_specVersion", || Ok(SPEC_VERSION.to_string()))
+    }
+
+    fn block_number(&self) -> RpcResult<BlockNumber> {
+        StarknetRpcApiServer::block_number(self)
+    }
+
+    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber> {
+        StarknetRpcApiServer::block_hash_and_number(self)
+    }
+
+    fn get_block_transaction_count(&self, block_id: StarknetBlockId) -> RpcResult<u128> {
+        StarknetRpcApiServer::get_block_transaction_count(self, block_id)
+    }
+
+    fn call(&self, request: FunctionCall, block_id: StarknetBlockId) -> RpcResult<Vec<FieldElement>> {
+        StarknetRpcApiServer::call(self, request, block_id)
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncStatusType> {
+        StarknetRpcApiServer::syncing(self).await
+    }
+
+    fn pending_transactions(&self) -> RpcResult<Vec<RpcTransaction>> {
+        StarknetRpcApiServer::pending_transactions(self)
+    }
+
+    fn get_transaction_receipt(&self, transaction_hash: FieldElement) -> RpcResult<TransactionReceipt> {
+        instrument(self.metrics.as_ref(), "starknet_getTransactionReceipt", || {
+            let (transaction, receipt, block) = self.transaction_receipt(transaction_hash)?;
+
+            Ok(TransactionReceipt {
+                transaction_hash: receipt.transaction_hash.into(),
+                actual_fee: receipt.actual_fee.into(),
+                execution_status: match receipt.execution_status {
+                    TransactionExecutionStatus::Succeeded => ExecutionStatus::Succeeded,
+                    TransactionExecutionStatus::Reverted => ExecutionStatus::Reverted,
+                },
+                finality_status: TransactionStatus::AcceptedOnL2,
+                block_hash: block.block_hash,
+                block_number: block.block_number,
+                transaction_type: to_rpc_transaction_type(&transaction),
+                messages_sent: receipt.messages_sent.iter().map(to_rpc_message).collect(),
+                events: receipt.events.iter().map(to_rpc_transaction_event).collect(),
+                revert_reason: receipt
+                    .revert_reason
+                    .map(|revert_reason| String::from_utf8_lossy(&revert_reason).into_owned()),
+                contract_address: deployed_contract_address(&transaction),
+            })
+        })
+    }
+
+    fn trace_transaction(&self, transaction_hash: FieldElement) -> RpcResult<TransactionTrace> {
+        instrument(self.metrics.as_ref(), "starknet_traceTransaction", || {
+            Ok(to_rpc_transaction_trace(self.transaction_trace(transaction_hash)?)?)
+        })
+    }
+
+    fn trace_block_transactions(&self, block_hash: BlockHash) -> RpcResult<Vec<TransactionTraceWithHash>> {
+        instrument(self.metrics.as_ref(), "starknet_traceBlockTransactions", || {
+            self.block_transaction_traces(block_hash)?
+                .into_iter()
+                .map(|trace| {
+                    Ok(TransactionTraceWithHash {
+                        transaction_hash: trace.transaction_hash.into(),
+                        trace_root: to_rpc_transaction_trace(trace)?,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn simulate_transactions(
+        &self,
+        block_id: StarknetBlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        instrument(self.metrics.as_ref(), "starknet_simulateTransactions", || {
+            self.simulate(block_id, transactions, simulation_flags)?
+                .into_iter()
+                .map(|simulation| {
+                    Ok(SimulatedTransaction {
+                        transaction_trace: to_rpc_transaction_trace(simulation.transaction_trace)?,
+                        fee_estimation: to_rpc_fee_estimate(simulation.fee_estimation),
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// Converts a transaction trace to the RPC format, which depends on the transaction type
+fn to_rpc_transaction_trace(trace: TransactionTraceWrapper) -> Result<TransactionTrace, StarknetRpcApiError> {
+    let validate_invocation = trace.validate_invocation.map(to_rpc_function_invocation);
+    let fee_transfer_invocation = trace.fee_transfer_invocation.map(to_rpc_function_invocation);
+    let function_invocation = trace.function_invocation.map(to_rpc_function_invocation);
+
+    Ok(match trace.tx_type {
+        TxType::InvokeTx => TransactionTrace::Invoke {
+            validate_invocation,
+            execute_invocation: match trace.revert_reason {
+                Some(revert_reason) => {
+                    ExecuteInvocation::Reverted { revert_reason: String::from_utf8_lossy(&revert_reason).into_owned() }
+                }
+                None => ExecuteInvocation::Success(function_invocation.ok_or(StarknetRpcApiError::NoTraceAvailable)?),
+            },
+            fee_transfer_invocation,
+        },
+        TxType::DeclareTx => TransactionTrace::Declare { validate_invocation, fee_transfer_invocation },
+        TxType::DeployAccountTx => TransactionTrace::DeployAccount {
+            validate_invocation,
+            constructor_invocation: function_invocation.ok_or(StarknetRpcApiError::NoTraceAvailable)?,
+            fee_transfer_invocation,
+        },
+        TxType::L1HandlerTx => TransactionTrace::L1Handler {
+            function_invocation: function_invocation.ok_or(StarknetRpcApiError::NoTraceAvailable)?,
+        },
+    })
+}
+
+/// Converts a function invocation and its inner calls to the RPC format
+fn to_rpc_function_invocation(invocation: FunctionInvocationWrapper) -> FunctionInvocation {
+    FunctionInvocation {
+        contract_address: invocation.contract_address.into(),
+        entry_point_selector: invocation.entry_point_selector.into(),
+        calldata: invocation.calldata.into_iter().map(FieldElement::from).collect(),
+        caller_address: invocation.caller_address.into(),
+        class_hash: invocation.class_hash.unwrap_or_default().into(),
+        entry_point_type: to_rpc_entry_point_type(invocation.entry_point_type),
+        call_type: to_rpc_call_type(invocation.call_type),
+        result: invocation.result.into_iter().map(FieldElement::from).collect(),
+        calls: invocation.calls.into_iter().map(to_rpc_function_invocation).collect(),
+        events: invocation.events.iter().map(to_rpc_event).collect(),
+        messages: invocation.messages.iter().map(to_rpc_message).collect(),
+    }
+}
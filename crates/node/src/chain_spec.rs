@@ -100,6 +100,8 @@ pub fn development_config(enable_manual_seal: Option<bool>) -> Result<DevChainSp
                         get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                     ],
                     true,
+                    // Development calls, to shape the state from the dev RPC
+                    true,
                 ),
                 enable_manual_seal,
             }
@@ -150,6 +152,8 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
                 true,
+                // Development calls
+                false,
             )
         },
         // Bootnodes
@@ -173,6 +177,7 @@ fn testnet_genesis(
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
     _enable_println: bool,
+    dev_calls_enabled: bool,
 ) -> GenesisConfig {
     let account_class = get_contract_class(ACCOUNT_CONTRACT_PATH);
 
@@ -255,6 +260,7 @@ fn testnet_genesis(
                 ),
             ],
             fee_token_address,
+            dev_calls_enabled,
             _phantom: Default::default(),
         },
     }
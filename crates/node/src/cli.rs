@@ -1,3 +1,5 @@
+use std::net::{IpAddr, Ipv4Addr};
+
 use sc_cli::RunCmd;
 
 /// Available Sealing methods.
@@ -21,6 +23,21 @@ pub struct Cli {
     /// Choose sealing method.
     #[arg(long, value_enum, ignore_case = true)]
     pub sealing: Option<Sealing>,
+
+    /// Serve the feeder gateway HTTP API on this port.
+    #[arg(long)]
+    pub feeder_gateway_port: Option<u16>,
+
+    /// Address the feeder gateway HTTP API listens on, when it is served.
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    pub feeder_gateway_addr: IpAddr,
+
+    /// Serve the `madara_admin` RPC namespace, whose calls must carry this token.
+    ///
+    /// The namespace is not served without it. Its methods are unsafe, so they also need
+    /// `--rpc-methods=unsafe`.
+    #[arg(long)]
+    pub admin_rpc_token: Option<String>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use madara_runtime::{Block, EXISTENTIAL_DEPOSIT};
 use sc_cli::{ChainSpec, RuntimeVersion, SubstrateCli};
@@ -179,8 +181,11 @@ pub fn run() -> sc_cli::Result<()> {
         }
         None => {
             let runner = cli.create_runner(&cli.run)?;
+            let feeder_gateway_addr =
+                cli.feeder_gateway_port.map(|port| SocketAddr::new(cli.feeder_gateway_addr, port));
             runner.run_node_until_exit(|config| async move {
-                service::new_full(config, cli.sealing).map_err(sc_cli::Error::Service)
+                service::new_full(config, cli.sealing, feeder_gateway_addr, cli.admin_rpc_token)
+                    .map_err(sc_cli::Error::Service)
             })
         }
     }
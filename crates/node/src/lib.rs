@@ -0,0 +1,12 @@
+//! Madara node, as a library.
+//!
+//! The `madara` binary is a thin wrapper around [`command::run`]. Exposing the node as a library
+//! allows integration tests to start it in-process.
+
+mod benchmarking;
+pub mod chain_spec;
+pub mod cli;
+pub mod command;
+pub mod rpc;
+pub mod service;
+mod starknet;
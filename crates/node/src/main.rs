@@ -1,15 +1,6 @@
 //! Madara node command line.
 #![warn(missing_docs)]
 
-#[macro_use]
-mod service;
-mod benchmarking;
-mod chain_spec;
-mod cli;
-mod command;
-mod rpc;
-mod starknet;
-
 fn main() -> sc_cli::Result<()> {
-    command::run()
+    madara::command::run()
 }
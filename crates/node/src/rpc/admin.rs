@@ -0,0 +1,220 @@
+//! Administration RPC methods, to update the parameters of the Starknet pallet.
+//!
+//! They are served under the `madara_admin` namespace, and are unsafe. Parameters are updated by
+//! root calls of the Starknet pallet, dispatched through the sudo pallet with the sudo key of the
+//! chain, looked up in the node keystore. Each method waits for its call to be included in a block,
+//! and returns the events it emitted. If the call is not included within `INCLUSION_TIMEOUT`, as
+//! when no block is sealed under manual sealing, the method fails with the hash of the extrinsic,
+//! which stays in the pool.
+//!
+//! The namespace is only served when the node is given an admin token, and every call must carry
+//! it, so that clients of an RPC endpoint serving unsafe methods cannot use the sudo key of the
+//! node without it. The token travels in clear, so the endpoint must be served over a trusted
+//! channel.
+//!
+//! Only the fee token address is a parameter of the pallet. The chain id is part of the transaction
+//! hashes, so it cannot change once the chain started, and the step limits and the L1 core contract
+//! address are not parameters of the pallet.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::{CallError, ErrorCode, ErrorObject};
+use madara_runtime::opaque::Block;
+use madara_runtime::{AccountId, Hash, Index, Runtime, RuntimeCall, RuntimeEvent};
+use mc_rpc_core::FieldElement;
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource, TransactionStatus};
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::{blake2_256, twox_128};
+use sp_core::storage::StorageKey;
+use sp_core::Decode;
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::generic::BlockId;
+use substrate_frame_rpc_system::AccountNonceApi;
+
+use super::dev::internal_error;
+use super::sudo;
+
+/// How long to wait for a sudo extrinsic to be included in a block
+const INCLUSION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An event emitted by an administration call
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum AdminEvent {
+    FeeTokenAddressChanged { old_fee_token_address: FieldElement, new_fee_token_address: FieldElement },
+}
+
+/// Madara admin RPC interface.
+#[rpc(server, namespace = "madara_admin")]
+pub trait MadaraAdminApi {
+    /// Set the address of the fee token contract
+    #[method(name = "setFeeTokenAddress")]
+    async fn set_fee_token_address(
+        &self,
+        auth_token: String,
+        fee_token_address: FieldElement,
+    ) -> RpcResult<Vec<AdminEvent>>;
+}
+
+/// A struct that implements the `MadaraAdminApi`.
+pub struct MadaraAdmin<C, P, BE> {
+    client: Arc<C>,
+    pool: Arc<P>,
+    keystore: SyncCryptoStorePtr,
+    /// The hash of the admin token, so that tokens are compared in constant time
+    auth_token_hash: [u8; 32],
+    deny_unsafe: DenyUnsafe,
+    _marker: PhantomData<BE>,
+}
+
+impl<C, P, BE> MadaraAdmin<C, P, BE> {
+    pub fn new(
+        client: Arc<C>,
+        pool: Arc<P>,
+        keystore: SyncCryptoStorePtr,
+        auth_token: &str,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        let auth_token_hash = blake2_256(auth_token.as_bytes());
+        Self { client, pool, keystore, auth_token_hash, deny_unsafe, _marker: PhantomData }
+    }
+
+    /// Fails unless unsafe methods are allowed and the token is the admin token of the node
+    fn authenticate(&self, auth_token: &str) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        if blake2_256(auth_token.as_bytes()) != self.auth_token_hash {
+            return Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                "Invalid admin token",
+                None::<()>,
+            ))));
+        }
+        Ok(())
+    }
+}
+
+impl<C, P, BE> MadaraAdmin<C, P, BE>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, BE>,
+    C: Send + Sync + 'static,
+    C::Api: AccountNonceApi<Block, AccountId, Index>,
+    P: TransactionPool<Block = Block> + 'static,
+    BE: Backend<Block> + 'static,
+{
+    /// Dispatches a root call of the Starknet pallet, and returns the events it emitted once it is
+    /// included in a block, or fails after `INCLUSION_TIMEOUT`
+    async fn dispatch_sudo(&self, call: pallet_starknet::Call<Runtime>) -> RpcResult<Vec<RuntimeEvent>> {
+        let best_hash = self.client.info().best_hash;
+        let extrinsic = sudo::keystore_sudo_extrinsic::<_, _, BE>(
+            &*self.client,
+            &*self.pool,
+            &self.keystore,
+            RuntimeCall::Starknet(call),
+        )?
+        .into();
+        let transaction_hash = self.pool.hash_of(&extrinsic);
+
+        let mut statuses = self
+            .pool
+            .submit_and_watch(&BlockId::hash(best_hash), TransactionSource::Local, extrinsic)
+            .await
+            .map_err(internal_error)?;
+        let inclusion = async {
+            loop {
+                match statuses.next().await {
+                    Some(TransactionStatus::InBlock(block_hash)) => return Ok(block_hash),
+                    Some(TransactionStatus::Future | TransactionStatus::Ready | TransactionStatus::Broadcast(_)) => {}
+                    Some(status) => return Err(internal_error(format!("Sudo extrinsic not included: {status:?}"))),
+                    None => return Err(internal_error("Sudo extrinsic not included")),
+                }
+            }
+        };
+        let block_hash = tokio::time::timeout(INCLUSION_TIMEOUT, inclusion).await.map_err(|_| {
+            internal_error(format!(
+                "Sudo extrinsic {transaction_hash:?} not included in a block after {}s, it is still in the pool",
+                INCLUSION_TIMEOUT.as_secs()
+            ))
+        })??;
+
+        self.extrinsic_events(block_hash, transaction_hash)
+    }
+
+    /// Returns the events emitted by an extrinsic of a block
+    fn extrinsic_events(&self, block_hash: Hash, transaction_hash: P::Hash) -> RpcResult<Vec<RuntimeEvent>> {
+        let extrinsics = self
+            .client
+            .block_body(block_hash)
+            .map_err(internal_error)?
+            .ok_or_else(|| internal_error("Block body not found"))?;
+        let index = extrinsics
+            .iter()
+            .position(|extrinsic| self.pool.hash_of(extrinsic) == transaction_hash)
+            .ok_or_else(|| internal_error("Sudo extrinsic not found in its block"))?;
+
+        let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+        let events = match self.client.storage(block_hash, &key).map_err(internal_error)? {
+            Some(data) => Vec::<frame_system::EventRecord<RuntimeEvent, Hash>>::decode(&mut &data.0[..])
+                .map_err(internal_error)?,
+            None => Vec::new(),
+        };
+
+        Ok(events
+            .into_iter()
+            .filter(|record| record.phase == frame_system::Phase::ApplyExtrinsic(index as u32))
+            .map(|record| record.event)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<C, P, BE> MadaraAdminApiServer for MadaraAdmin<C, P, BE>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block> + StorageProvider<Block, BE>,
+    C: Send + Sync + 'static,
+    C::Api: AccountNonceApi<Block, AccountId, Index>,
+    P: TransactionPool<Block = Block> + 'static,
+    BE: Backend<Block> + 'static,
+{
+    async fn set_fee_token_address(
+        &self,
+        auth_token: String,
+        fee_token_address: FieldElement,
+    ) -> RpcResult<Vec<AdminEvent>> {
+        self.authenticate(&auth_token)?;
+        let events = self
+            .dispatch_sudo(pallet_starknet::Call::set_fee_token_address { fee_token_address: fee_token_address.into() })
+            .await?;
+
+        to_admin_events(events)
+    }
+}
+
+/// Converts the events of a sudo extrinsic, failing if the root call failed
+fn to_admin_events(events: Vec<RuntimeEvent>) -> RpcResult<Vec<AdminEvent>> {
+    let mut admin_events = Vec::new();
+    for event in events {
+        match event {
+            RuntimeEvent::Sudo(pallet_sudo::Event::Sudid { sudo_result: Err(e) }) => {
+                return Err(internal_error(format!("Sudo call failed: {e:?}")));
+            }
+            RuntimeEvent::Starknet(pallet_starknet::Event::FeeTokenAddressChanged {
+                old_fee_token_address,
+                new_fee_token_address,
+            }) => admin_events.push(AdminEvent::FeeTokenAddressChanged {
+                old_fee_token_address: old_fee_token_address.into(),
+                new_fee_token_address: new_fee_token_address.into(),
+            }),
+            _ => {}
+        }
+    }
+    Ok(admin_events)
+}
@@ -0,0 +1,195 @@
+//! Development RPC methods, to shape the state of a dev chain from tests.
+//!
+//! They are served under the `madara_dev` namespace when blocks are sealed manually, and are
+//! unsafe. State changes are root calls of the Starknet pallet, dispatched through the sudo pallet
+//! with the sudo key of the chain, looked up in the node keystore, so they take effect in the next
+//! sealed block. The pallet only accepts them on chains enabling its development calls at genesis.
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use futures::channel::{mpsc, oneshot};
+use futures::SinkExt;
+use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::{CallError, ErrorCode, ErrorObject};
+use madara_runtime::opaque::Block;
+use madara_runtime::{AccountId, Hash, Index, Runtime, RuntimeCall};
+use mc_rpc_core::FieldElement;
+use pallet_starknet::runtime_api::StarknetRuntimeApi;
+use sc_client_api::{Backend, StorageProvider};
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{H256, U256};
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::generic::BlockId;
+use substrate_frame_rpc_system::AccountNonceApi;
+
+use super::sudo;
+
+/// The timestamp of the next block sealed manually, when set through the dev RPC
+pub type NextBlockTimestamp = Arc<Mutex<Option<u64>>>;
+
+/// Madara dev RPC interface.
+#[rpc(server, namespace = "madara_dev")]
+pub trait MadaraDevApi {
+    /// Set the value of a contract storage slot
+    #[method(name = "setStorageAt")]
+    async fn set_storage_at(
+        &self,
+        contract_address: FieldElement,
+        key: FieldElement,
+        value: FieldElement,
+    ) -> RpcResult<()>;
+
+    /// Set the nonce of a contract
+    #[method(name = "setNonce")]
+    async fn set_nonce(&self, contract_address: FieldElement, nonce: FieldElement) -> RpcResult<()>;
+
+    /// Mint fee tokens to an account
+    #[method(name = "mint")]
+    async fn mint(&self, recipient: FieldElement, amount: FieldElement) -> RpcResult<()>;
+
+    /// Set the timestamp of the next block, which must increase by the minimum period of the chain
+    #[method(name = "setNextBlockTimestamp")]
+    fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()>;
+
+    /// Seal a new block with the pending transactions, even if there are none, and return its hash
+    #[method(name = "createBlock")]
+    async fn create_block(&self) -> RpcResult<Hash>;
+}
+
+/// A struct that implements the `MadaraDevApi`.
+pub struct MadaraDev<C, P, BE> {
+    client: Arc<C>,
+    pool: Arc<P>,
+    keystore: SyncCryptoStorePtr,
+    command_sink: mpsc::Sender<EngineCommand<Hash>>,
+    next_block_timestamp: NextBlockTimestamp,
+    deny_unsafe: DenyUnsafe,
+    _marker: PhantomData<BE>,
+}
+
+impl<C, P, BE> MadaraDev<C, P, BE> {
+    pub fn new(
+        client: Arc<C>,
+        pool: Arc<P>,
+        keystore: SyncCryptoStorePtr,
+        command_sink: mpsc::Sender<EngineCommand<Hash>>,
+        next_block_timestamp: NextBlockTimestamp,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self { client, pool, keystore, command_sink, next_block_timestamp, deny_unsafe, _marker: PhantomData }
+    }
+}
+
+impl<C, P, BE> MadaraDev<C, P, BE>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+    C::Api: AccountNonceApi<Block, AccountId, Index>,
+    P: TransactionPool<Block = Block> + 'static,
+    BE: Backend<Block> + 'static,
+{
+    /// Submits a root call of the Starknet pallet to the pool, signed by the sudo key
+    async fn submit_sudo(&self, call: pallet_starknet::Call<Runtime>) -> RpcResult<()> {
+        let best_hash = self.client.info().best_hash;
+        let extrinsic = sudo::keystore_sudo_extrinsic::<_, _, BE>(
+            &*self.client,
+            &*self.pool,
+            &self.keystore,
+            RuntimeCall::Starknet(call),
+        )?;
+
+        self.pool
+            .submit_one(&BlockId::hash(best_hash), TransactionSource::Local, extrinsic.into())
+            .await
+            .map_err(internal_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C, P, BE> MadaraDevApiServer for MadaraDev<C, P, BE>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+    C::Api: AccountNonceApi<Block, AccountId, Index> + StarknetRuntimeApi<Block>,
+    P: TransactionPool<Block = Block> + 'static,
+    BE: Backend<Block> + 'static,
+{
+    async fn set_storage_at(
+        &self,
+        contract_address: FieldElement,
+        key: FieldElement,
+        value: FieldElement,
+    ) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        self.submit_sudo(pallet_starknet::Call::set_storage {
+            contract_address: contract_address.into(),
+            key: H256::from(key),
+            value: value.into(),
+        })
+        .await
+    }
+
+    async fn set_nonce(&self, contract_address: FieldElement, nonce: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        self.submit_sudo(pallet_starknet::Call::set_nonce {
+            contract_address: contract_address.into(),
+            nonce: nonce.into(),
+        })
+        .await
+    }
+
+    async fn mint(&self, recipient: FieldElement, amount: FieldElement) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        self.submit_sudo(pallet_starknet::Call::mint_fee_token {
+            recipient: recipient.into(),
+            amount: U256::from(amount),
+        })
+        .await
+    }
+
+    fn set_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()> {
+        self.deny_unsafe.check_if_safe()?;
+        let best_hash = self.client.info().best_hash;
+        let latest_timestamp =
+            self.client.runtime_api().current_block(best_hash).map_err(internal_error)?.header().block_timestamp;
+        // The timestamp pallet rejects blocks whose timestamp does not increase by its minimum period
+        let minimum_timestamp = latest_timestamp.saturating_add(madara_runtime::SLOT_DURATION / 2);
+        if timestamp < minimum_timestamp {
+            return Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                format!("Timestamp must be at least {minimum_timestamp}"),
+                None::<()>,
+            ))));
+        }
+
+        *self.next_block_timestamp.lock().map_err(internal_error)? = Some(timestamp);
+        Ok(())
+    }
+
+    async fn create_block(&self) -> RpcResult<Hash> {
+        self.deny_unsafe.check_if_safe()?;
+        let (sender, receiver) = oneshot::channel();
+        self.command_sink
+            .clone()
+            .send(EngineCommand::SealNewBlock {
+                create_empty: true,
+                finalize: true,
+                parent_hash: None,
+                sender: Some(sender),
+            })
+            .await
+            .map_err(internal_error)?;
+
+        let created_block = receiver.await.map_err(internal_error)?.map_err(internal_error)?;
+        Ok(created_block.hash)
+    }
+}
+
+pub(super) fn internal_error(e: impl ToString) -> JsonRpseeError {
+    JsonRpseeError::Custom(e.to_string())
+}
@@ -5,20 +5,26 @@
 
 #![warn(missing_docs)]
 
+mod admin;
+mod dev;
 mod starknet;
+mod sudo;
 use std::sync::Arc;
 
+pub use dev::NextBlockTimestamp;
 use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
 use madara_runtime::opaque::Block;
 use madara_runtime::{AccountId, Balance, Hash, Index};
-use sc_client_api::{Backend, StorageProvider};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
 use sc_consensus_manual_seal::rpc::EngineCommand;
+use sc_rpc::SubscriptionTaskExecutor;
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_keystore::SyncCryptoStorePtr;
 pub use starknet::StarknetDeps;
 
 /// Full client dependencies.
@@ -27,44 +33,97 @@ pub struct FullDeps<C, P> {
     pub client: Arc<C>,
     /// Transaction pool instance.
     pub pool: Arc<P>,
+    /// Keystore of the node, holding the sudo key for the admin and dev RPC
+    pub keystore: SyncCryptoStorePtr,
+    /// Token authenticating the admin RPC calls, which is only served when it is set
+    pub admin_rpc_token: Option<String>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
     /// Manual seal command sink
     pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+    /// Timestamp of the next block sealed manually
+    pub next_block_timestamp: NextBlockTimestamp,
     /// Starknet dependencies
     pub starknet: StarknetDeps<C, Block>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P, BE>(deps: FullDeps<C, P>) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
+pub fn create_full<C, P, BE>(
+    deps: FullDeps<C, P>,
+    subscription_executor: SubscriptionTaskExecutor,
+) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
     C: ProvideRuntimeApi<Block>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + StorageProvider<Block, BE> + 'static,
+    C: BlockBackend<Block>,
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BlockBuilder<Block>,
     C::Api: pallet_starknet::runtime_api::StarknetRuntimeApi<Block>,
-    P: TransactionPool + 'static,
+    P: TransactionPool<Block = Block> + 'static,
     BE: Backend<Block> + 'static,
 {
-    use mc_rpc::{Starknet, StarknetRpcApiServer};
+    use mc_rpc::{Starknet, StarknetPubSub, StarknetPubSubApiServer, StarknetRpcApiServer, StarknetRpcApiV0_4Server};
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
     use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
     use substrate_frame_rpc_system::{System, SystemApiServer};
 
+    use self::admin::{MadaraAdmin, MadaraAdminApiServer};
+    use self::dev::{MadaraDev, MadaraDevApiServer};
+
     let mut module = RpcModule::new(());
-    let FullDeps { client, pool, deny_unsafe, starknet: starknet_params, command_sink } = deps;
+    let FullDeps {
+        client,
+        pool,
+        keystore,
+        admin_rpc_token,
+        deny_unsafe,
+        starknet: starknet_params,
+        command_sink,
+        next_block_timestamp,
+    } = deps;
 
-    module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
+    module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
     module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
-    module.merge(Starknet::new(client, starknet_params.madara_backend, starknet_params.overrides).into_rpc())?;
+    let starknet = Starknet::new(
+        client.clone(),
+        starknet_params.madara_backend.clone(),
+        starknet_params.overrides.clone(),
+        pool.clone(),
+        starknet_params.sync_service,
+        starknet_params.starting_block,
+        starknet_params.metrics,
+    );
+    // The default `starknet` namespace serves the v0.3 spec, other versions have their own namespace.
+    module.merge(StarknetRpcApiServer::into_rpc(starknet.clone()))?;
+    module.merge(StarknetRpcApiV0_4Server::into_rpc(starknet))?;
+    module.merge(
+        StarknetPubSub::new(
+            client.clone(),
+            starknet_params.madara_backend,
+            starknet_params.overrides,
+            pool.clone(),
+            subscription_executor,
+            starknet_params.notification_sinks,
+        )
+        .into_rpc(),
+    )?;
+
+    if let Some(admin_rpc_token) = admin_rpc_token {
+        module.merge(
+            MadaraAdmin::new(client.clone(), pool.clone(), keystore.clone(), &admin_rpc_token, deny_unsafe).into_rpc(),
+        )?;
+    }
 
     if let Some(command_sink) = command_sink {
         module.merge(
             // We provide the rpc handler with the sending end of the channel to allow the rpc
             // send EngineCommands to the background block authorship task.
-            ManualSeal::new(command_sink).into_rpc(),
+            ManualSeal::new(command_sink.clone()).into_rpc(),
+        )?;
+        module.merge(
+            MadaraDev::new(client, pool, keystore, command_sink, next_block_timestamp, deny_unsafe).into_rpc(),
         )?;
     }
 
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use mc_db::Backend;
+use mc_mapping_sync::{StarknetBlockNotification, StarknetBlockNotificationSinks};
+use mc_rpc::{RpcMetrics, SyncStartingBlock};
 use mc_storage::OverrideHandle;
+use sc_network_sync::SyncingService;
 use sp_api::BlockT;
 
 /// Extra dependencies for Starknet compatibility.
@@ -12,6 +15,14 @@ pub struct StarknetDeps<C, B: BlockT> {
     pub madara_backend: Arc<Backend<B>>,
     /// Starknet data access overrides.
     pub overrides: Arc<OverrideHandle<B>>,
+    /// The Substrate client sync service.
+    pub sync_service: Arc<SyncingService<B>>,
+    /// The block the ongoing sync started from, kept up to date by `mc_rpc::track_sync_start`.
+    pub starting_block: SyncStartingBlock<B>,
+    /// Sinks notified when a new Starknet block has been synced.
+    pub notification_sinks: Arc<StarknetBlockNotificationSinks<StarknetBlockNotification<B>>>,
+    /// Metrics of the Starknet RPC handlers, when Prometheus is enabled.
+    pub metrics: Option<RpcMetrics>,
 }
 
 impl<C, B: BlockT> Clone for StarknetDeps<C, B> {
@@ -20,6 +31,10 @@ impl<C, B: BlockT> Clone for StarknetDeps<C, B> {
             client: self.client.clone(),
             madara_backend: self.madara_backend.clone(),
             overrides: self.overrides.clone(),
+            sync_service: self.sync_service.clone(),
+            starting_block: self.starting_block.clone(),
+            notification_sinks: self.notification_sinks.clone(),
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -0,0 +1,139 @@
+//! Sudo extrinsics crafted by the node, shared by the RPC namespaces dispatching root calls.
+//!
+//! They are signed with the sudo key of the chain, which must be in the node keystore, either as an
+//! `acco` key or, as for dev chains, as an `aura` key.
+
+use jsonrpsee::core::RpcResult;
+use madara_runtime::opaque::Block;
+use madara_runtime::{
+    AccountId, Hash, Index, Runtime, RuntimeCall, Signature, SignedExtra, SignedPayload, SudoCall, UncheckedExtrinsic,
+};
+use sc_client_api::{Backend, StorageProvider};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::crypto::{key_types, ByteArray, CryptoTypePublicPair, KeyTypeId};
+use sp_core::hashing::twox_128;
+use sp_core::storage::StorageKey;
+use sp_core::{sr25519, Decode, Encode};
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_runtime::generic::Era;
+use substrate_frame_rpc_system::AccountNonceApi;
+
+use super::dev::internal_error;
+
+/// The key types under which the sudo key is looked up in the keystore
+const SUDO_KEY_TYPES: [KeyTypeId; 2] = [key_types::ACCOUNT, key_types::AURA];
+
+/// Wraps a call into a sudo call, signed with the sudo key of the keystore
+///
+/// The extrinsic follows the transactions of the sudo account that are ready in the pool.
+pub fn keystore_sudo_extrinsic<C, P, BE>(
+    client: &C,
+    pool: &P,
+    keystore: &SyncCryptoStorePtr,
+    call: RuntimeCall,
+) -> RpcResult<UncheckedExtrinsic>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE>,
+    C::Api: AccountNonceApi<Block, AccountId, Index>,
+    P: TransactionPool<Block = Block>,
+    BE: Backend<Block>,
+{
+    let info = client.info();
+    let sudo_account = sudo_account(client, info.best_hash)?;
+    let (key_type, public) = sudo_key(keystore, &sudo_account)?;
+    let nonce = client.runtime_api().account_nonce(info.best_hash, sudo_account.clone()).map_err(internal_error)?;
+    let nonce = next_nonce(pool, &sudo_account, nonce);
+
+    let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_raw_vec());
+    sudo_extrinsic(call, sudo_account, nonce, info.genesis_hash, |payload| {
+        let signature = SyncCryptoStore::sign_with(&**keystore, key_type, &key, payload).ok()??;
+        sr25519::Signature::try_from(signature.as_slice()).ok()
+    })
+    .ok_or_else(|| internal_error("Failed to sign the sudo extrinsic with the keystore"))
+}
+
+/// Returns the sudo account of the chain
+fn sudo_account<C, BE>(client: &C, block_hash: Hash) -> RpcResult<AccountId>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+{
+    let key = StorageKey([twox_128(b"Sudo"), twox_128(b"Key")].concat());
+    let data = client
+        .storage(block_hash, &key)
+        .map_err(internal_error)?
+        .ok_or_else(|| internal_error("The chain has no sudo key"))?;
+
+    AccountId::decode(&mut &data.0[..]).map_err(internal_error)
+}
+
+/// Returns the key type and public key of the sudo account in the keystore
+fn sudo_key(keystore: &SyncCryptoStorePtr, sudo_account: &AccountId) -> RpcResult<(KeyTypeId, sr25519::Public)> {
+    SUDO_KEY_TYPES
+        .into_iter()
+        .find_map(|key_type| {
+            SyncCryptoStore::sr25519_public_keys(&**keystore, key_type)
+                .into_iter()
+                .find(|public| AccountId::from(*public) == *sudo_account)
+                .map(|public| (key_type, public))
+        })
+        .ok_or_else(|| internal_error("The sudo key is not in the keystore"))
+}
+
+/// Wraps a call into a sudo call, signed by the sudo account
+///
+/// The extrinsic is immortal, so that it only depends on the genesis hash. Returns `None` if the
+/// payload could not be signed.
+fn sudo_extrinsic(
+    call: RuntimeCall,
+    sudo: AccountId,
+    nonce: Index,
+    genesis_hash: Hash,
+    sign: impl FnOnce(&[u8]) -> Option<sr25519::Signature>,
+) -> Option<UncheckedExtrinsic> {
+    let call = RuntimeCall::Sudo(SudoCall::sudo { call: Box::new(call) });
+    let extra: SignedExtra = (
+        frame_system::CheckNonZeroSender::<Runtime>::new(),
+        frame_system::CheckSpecVersion::<Runtime>::new(),
+        frame_system::CheckTxVersion::<Runtime>::new(),
+        frame_system::CheckGenesis::<Runtime>::new(),
+        frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+        frame_system::CheckNonce::<Runtime>::from(nonce),
+        frame_system::CheckWeight::<Runtime>::new(),
+        pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+    );
+    let payload = SignedPayload::from_raw(
+        call.clone(),
+        extra.clone(),
+        (
+            (),
+            madara_runtime::VERSION.spec_version,
+            madara_runtime::VERSION.transaction_version,
+            genesis_hash,
+            genesis_hash,
+            (),
+            (),
+            (),
+        ),
+    );
+    let signature = payload.using_encoded(sign)?;
+
+    Some(UncheckedExtrinsic::new_signed(call, sudo.into(), Signature::Sr25519(signature), extra))
+}
+
+/// Returns the next nonce of an account, after its ready transactions in the pool
+///
+/// Transactions of an account provide the tag `(account, nonce)`, so that the ready ones can be
+/// followed from the on-chain nonce.
+fn next_nonce<P: TransactionPool>(pool: &P, account: &AccountId, mut nonce: Index) -> Index {
+    let mut current_tag = (account, nonce).encode();
+    for transaction in pool.ready() {
+        if transaction.provides().first() == Some(&current_tag) {
+            nonce += 1;
+            current_tag = (account, nonce).encode();
+        }
+    }
+    nonce
+}
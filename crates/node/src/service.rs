@@ -1,6 +1,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use std::cell::RefCell;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -9,7 +10,9 @@ use futures::future;
 use futures::prelude::*;
 use madara_runtime::opaque::Block;
 use madara_runtime::{self, Hash, RuntimeApi};
-use mc_mapping_sync::MappingSyncWorker;
+use mc_feeder_gateway::FeederGateway;
+use mc_mapping_sync::{MappingSyncWorker, StarknetBlockNotificationSinks};
+use mc_rpc::{track_sync_start, SyncStartingBlock};
 use mc_storage::overrides_handle;
 use prometheus_endpoint::Registry;
 use sc_client_api::{BlockBackend, BlockchainEvents};
@@ -28,7 +31,7 @@ use sp_runtime::traits::BlakeTwo256;
 use sp_trie::PrefixedMemoryDB;
 
 use crate::cli::Sealing;
-use crate::rpc::StarknetDeps;
+use crate::rpc::{NextBlockTimestamp, StarknetDeps};
 use crate::starknet::{db_config_dir, MadaraBackend};
 
 // Our native executor instance.
@@ -237,7 +240,12 @@ where
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+    mut config: Configuration,
+    sealing: Option<Sealing>,
+    feeder_gateway_addr: Option<SocketAddr>,
+    admin_rpc_token: Option<String>,
+) -> Result<TaskManager, ServiceError> {
     let build_import_queue =
         if sealing.is_some() { build_manual_seal_import_queue } else { build_aura_grandpa_import_queue };
 
@@ -302,23 +310,51 @@ pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<T
     // parameter
     let (command_sink, commands_stream) = mpsc::channel(1000);
 
+    // Timestamp of the next block sealed manually, set by the dev RPC.
+    let next_block_timestamp: NextBlockTimestamp = Default::default();
+
     let overrides = overrides_handle(client.clone());
-    let starknet_rpc_params =
-        StarknetDeps { client: client.clone(), madara_backend: madara_backend.clone(), overrides: overrides.clone() };
+    // Sinks for the pubsub subscriptions, notified by the mapping sync worker on each synced block.
+    let starknet_notification_sinks: Arc<StarknetBlockNotificationSinks<_>> = Default::default();
+
+    // The block the ongoing sync started from, reported by `starknet_syncing`.
+    let sync_starting_block: SyncStartingBlock<_> = Default::default();
+    task_manager.spawn_handle().spawn(
+        "mc-rpc-sync-start",
+        Some("madara"),
+        track_sync_start(client.clone(), sync_service.clone(), sync_starting_block.clone(), Duration::from_secs(1)),
+    );
+
+    // Registered once, as the RPC extensions may be built several times.
+    let starknet_rpc_metrics = prometheus_registry.as_ref().map(mc_rpc::RpcMetrics::register).transpose()?;
+
+    let starknet_rpc_params = StarknetDeps {
+        client: client.clone(),
+        madara_backend: madara_backend.clone(),
+        overrides: overrides.clone(),
+        sync_service: sync_service.clone(),
+        starting_block: sync_starting_block,
+        notification_sinks: starknet_notification_sinks.clone(),
+        metrics: starknet_rpc_metrics,
+    };
 
     let rpc_extensions_builder = {
         let client = client.clone();
         let pool = transaction_pool.clone();
+        let keystore = keystore_container.sync_keystore();
 
-        Box::new(move |deny_unsafe, _| {
+        Box::new(move |deny_unsafe, subscription_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: pool.clone(),
+                keystore: keystore.clone(),
+                admin_rpc_token: admin_rpc_token.clone(),
                 deny_unsafe,
                 starknet: starknet_rpc_params.clone(),
                 command_sink: if sealing.is_some() { Some(command_sink.clone()) } else { None },
+                next_block_timestamp: next_block_timestamp.clone(),
             };
-            crate::rpc::create_full(deps).map_err(Into::into)
+            crate::rpc::create_full(deps, subscription_executor).map_err(Into::into)
         })
     };
 
@@ -337,6 +373,16 @@ pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<T
         telemetry: telemetry.as_mut(),
     })?;
 
+    if let Some(addr) = feeder_gateway_addr {
+        let feeder_gateway = FeederGateway::<_, FullBackend, _, _>::new(
+            client.clone(),
+            madara_backend.clone(),
+            overrides.clone(),
+            transaction_pool.clone(),
+        );
+        task_manager.spawn_handle().spawn("mc-feeder-gateway", Some("madara"), feeder_gateway.run(addr));
+    }
+
     task_manager.spawn_essential_handle().spawn(
         "mc-mapping-sync-worker",
         Some("madara"),
@@ -349,6 +395,8 @@ pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<T
             madara_backend,
             3,
             0,
+            sync_service.clone(),
+            starknet_notification_sinks,
         )
         .for_each(|()| future::ready(())),
     );
@@ -366,6 +414,7 @@ pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<T
                 prometheus_registry.as_ref(),
                 telemetry.as_ref(),
                 commands_stream,
+                next_block_timestamp,
             )?;
 
             network_starter.start_network();
@@ -474,6 +523,7 @@ fn run_manual_seal_authorship(
     prometheus_registry: Option<&Registry>,
     telemetry: Option<&Telemetry>,
     commands_stream: mpsc::Receiver<sc_consensus_manual_seal::rpc::EngineCommand<Hash>>,
+    next_block_timestamp: NextBlockTimestamp,
 ) -> Result<(), ServiceError>
 where
     RuntimeApi: ConstructRuntimeApi<Block, FullClient>,
@@ -490,8 +540,11 @@ where
     thread_local!(static TIMESTAMP: RefCell<u64> = RefCell::new(0));
 
     /// Provide a mock duration starting at 0 in millisecond for timestamp inherent.
-    /// Each call will increment timestamp by slot_duration making Aura think time has passed.
-    struct MockTimestampInherentDataProvider;
+    /// Each call will increment timestamp by slot_duration making Aura think time has passed,
+    /// unless the timestamp of the block was set through the dev RPC.
+    struct MockTimestampInherentDataProvider {
+        timestamp: Option<u64>,
+    }
 
     #[async_trait::async_trait]
     impl sp_inherents::InherentDataProvider for MockTimestampInherentDataProvider {
@@ -500,7 +553,10 @@ where
             inherent_data: &mut sp_inherents::InherentData,
         ) -> Result<(), sp_inherents::Error> {
             TIMESTAMP.with(|x| {
-                *x.borrow_mut() += madara_runtime::SLOT_DURATION;
+                match self.timestamp {
+                    Some(timestamp) => *x.borrow_mut() = timestamp,
+                    None => *x.borrow_mut() += madara_runtime::SLOT_DURATION,
+                }
                 inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &*x.borrow())
             })
         }
@@ -515,9 +571,9 @@ where
         }
     }
 
-    let create_inherent_data_providers = move |_, ()| async move {
-        let timestamp = MockTimestampInherentDataProvider;
-        Ok(timestamp)
+    let create_inherent_data_providers = move |_, ()| {
+        let timestamp = next_block_timestamp.lock().ok().and_then(|mut timestamp| timestamp.take());
+        async move { Ok(MockTimestampInherentDataProvider { timestamp }) }
     };
 
     let manual_seal = match sealing {
@@ -0,0 +1,277 @@
+//! Conformance of the Starknet RPC with its OpenRPC specification
+//!
+//! An in-process dev node sealing blocks manually is started, and every method of the
+//! specification is called with the parameters of its examples, or with fixtures when it has none.
+//! Results are validated against the method result schema, and errors must be declared by the
+//! method. Methods the node does not serve yet must be listed in `UNIMPLEMENTED_METHODS`, and the
+//! methods the spec does not cover are called with fixtures as well.
+//!
+//! Every namespace serving a version of the spec is checked against the specification bundled
+//! with `mc-rpc-core`, which only covers the read API shared by these versions.
+
+use std::net::{Ipv4Addr, TcpListener};
+
+use clap::Parser;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::types::error::{CallError, ErrorCode};
+use jsonschema::{Draft, JSONSchema};
+use madara::cli::Cli;
+use sc_cli::CliConfiguration;
+use sc_service::TaskManager;
+use serde_json::{json, Value};
+use sp_core::hashing::keccak_256;
+use sp_core::U256;
+
+const OPENRPC_SPEC: &str = include_str!("../../client/rpc-core/starknet_openRPC.json");
+
+/// The test contract deployed at genesis, see `chain_spec.rs`
+const TEST_CONTRACT_ADDRESS: &str = "0x1111";
+const TEST_CLASS_HASH: &str = "0x1000";
+/// The account deployed at genesis, see `chain_spec.rs`
+const TEST_ACCOUNT_ADDRESS: &str = "0x1";
+
+/// The namespaces serving a version of the spec
+const NAMESPACES: &[&str] = &["starknet", "starknet_v0_4"];
+
+/// Methods of the specification the node does not serve yet, which must not be found
+///
+/// A method must be removed from the list once it is served, so that its responses are checked.
+const UNIMPLEMENTED_METHODS: &[&str] = &[
+    "getBlockWithTxHashes",
+    "getBlockWithTxs",
+    "getStateUpdate",
+    "getStorageAt",
+    "getTransactionByHash",
+    "getTransactionByBlockIdAndIndex",
+    "getClass",
+    "getClassHashAt",
+    "getClassAt",
+    "estimateFee",
+    "chainId",
+    "getEvents",
+    "getNonce",
+];
+
+/// Methods served by the node which the bundled specification does not cover, with their parameters
+///
+/// Their results cannot be validated, so they are only checked to be served and to fail with errors
+/// declared by the spec.
+const METHODS_OUTSIDE_SPEC: &[(&str, &[&str])] = &[
+    ("traceTransaction", &["transaction_hash"]),
+    ("traceBlockTransactions", &["block_hash"]),
+    ("simulateTransactions", &["block_id", "transactions", "simulation_flags"]),
+];
+
+#[tokio::test(flavor = "multi_thread")]
+async fn starknet_rpc_conforms_to_openrpc_spec() {
+    let spec: Value = serde_json::from_str(OPENRPC_SPEC).expect("the OpenRPC spec is valid JSON");
+    let rpc_port = free_port();
+    let _node = start_dev_node(rpc_port);
+    let client = HttpClientBuilder::default().build(format!("http://127.0.0.1:{rpc_port}")).unwrap();
+
+    // Seal a block, so that the latest block is a Starknet block
+    let _: Value =
+        client.request("engine_createBlock", params(vec![json!(true), json!(true), Value::Null])).await.unwrap();
+    let latest_block: Value = client.request("starknet_blockHashAndNumber", ArrayParams::new()).await.unwrap();
+    let latest_block_hash = &latest_block["block_hash"];
+
+    let mut failures = Vec::new();
+    for namespace in NAMESPACES {
+        let mut unimplemented = Vec::new();
+        for method in spec["methods"].as_array().unwrap() {
+            let spec_name = method["name"].as_str().unwrap();
+            let method_name = spec_name.strip_prefix("starknet_").unwrap();
+            let name = format!("{namespace}_{method_name}");
+            let Some(requests) = requests(&spec, method, latest_block_hash) else {
+                failures.push(format!("{name}: no example nor fixture to call it with"));
+                continue;
+            };
+
+            for request in requests {
+                match client.request::<Value, _>(&name, params(request)).await {
+                    Ok(result) => {
+                        if let Err(e) = validate(&spec, &method["result"]["schema"], &result) {
+                            failures.push(format!("{name}: invalid result {result}: {e}"));
+                        }
+                    }
+                    Err(RpcError::Call(CallError::Custom(error)))
+                        if error.code() == ErrorCode::MethodNotFound.code() =>
+                    {
+                        unimplemented.push(method_name);
+                        break;
+                    }
+                    Err(RpcError::Call(CallError::Custom(error))) => {
+                        if !declared_error_codes(&spec, method).contains(&i64::from(error.code())) {
+                            failures.push(format!("{name}: undeclared error {} ({})", error.code(), error.message()));
+                        }
+                    }
+                    Err(e) => failures.push(format!("{name}: {e}")),
+                }
+            }
+        }
+
+        for (method_name, param_names) in METHODS_OUTSIDE_SPEC {
+            let name = format!("{namespace}_{method_name}");
+            let request = param_names
+                .iter()
+                .map(|param| fixture(&format!("starknet_{method_name}"), param, latest_block_hash).unwrap())
+                .collect();
+            match client.request::<Value, _>(&name, params(request)).await {
+                Ok(_) => {}
+                Err(RpcError::Call(CallError::Custom(error))) => {
+                    let spec_errors = spec["components"]["errors"].as_object().unwrap();
+                    if !spec_errors.values().any(|spec_error| spec_error["code"].as_i64() == Some(error.code().into()))
+                    {
+                        failures.push(format!("{name}: undeclared error {} ({})", error.code(), error.message()));
+                    }
+                }
+                Err(e) => failures.push(format!("{name}: {e}")),
+            }
+        }
+
+        for method_name in UNIMPLEMENTED_METHODS.iter().filter(|method_name| !unimplemented.contains(method_name)) {
+            failures.push(format!("{namespace}_{method_name}: served, remove it from the unimplemented methods"));
+        }
+        for method_name in unimplemented.iter().filter(|method_name| !UNIMPLEMENTED_METHODS.contains(method_name)) {
+            failures.push(format!("{namespace}_{method_name}: not found"));
+        }
+    }
+
+    assert!(failures.is_empty(), "Responses do not conform to the OpenRPC spec:\n{}", failures.join("\n"));
+}
+
+/// Starts a dev node sealing blocks manually, serving the RPC over HTTP on the given port
+fn start_dev_node(rpc_port: u16) -> TaskManager {
+    let cli = Cli::parse_from([
+        "madara".to_string(),
+        "--dev".to_string(),
+        "--sealing=manual".to_string(),
+        "--tmp".to_string(),
+        "--no-telemetry".to_string(),
+        "--no-prometheus".to_string(),
+        "--no-grandpa".to_string(),
+        "--in-peers=0".to_string(),
+        "--out-peers=0".to_string(),
+        format!("--port={}", free_port()),
+        format!("--rpc-port={rpc_port}"),
+        format!("--ws-port={}", free_port()),
+        "--rpc-methods=unsafe".to_string(),
+    ]);
+    let config = cli.run.create_configuration(&cli, tokio::runtime::Handle::current()).unwrap();
+
+    madara::service::new_full(config, cli.sealing, None, None).unwrap()
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port()
+}
+
+fn params(values: Vec<Value>) -> ArrayParams {
+    let mut params = ArrayParams::new();
+    for value in values {
+        params.insert(value).unwrap();
+    }
+    params
+}
+
+/// Returns the parameters of every request to send for a method
+///
+/// The examples of the spec are used when there are some. Otherwise a single request is built from
+/// fixtures, or `None` is returned if a parameter has no fixture.
+fn requests(spec: &Value, method: &Value, latest_block_hash: &Value) -> Option<Vec<Vec<Value>>> {
+    if let Some(examples) = method["examples"].as_array().filter(|examples| !examples.is_empty()) {
+        return Some(
+            examples
+                .iter()
+                .map(|example| {
+                    example["params"].as_array().into_iter().flatten().map(|param| param["value"].clone()).collect()
+                })
+                .collect(),
+        );
+    }
+
+    let name = method["name"].as_str().unwrap();
+    let mut request = Vec::new();
+    for param in method["params"].as_array().unwrap() {
+        let value = fixture(name, param["name"].as_str().unwrap(), latest_block_hash)?;
+        validate(spec, &param["schema"], &value)
+            .unwrap_or_else(|e| panic!("{name}: fixture {value} does not match its schema: {e}"));
+        request.push(value);
+    }
+    Some(vec![request])
+}
+
+/// Returns the fixture of a method parameter, targeting the genesis state of the dev chain
+fn fixture(method: &str, param: &str, latest_block_hash: &Value) -> Option<Value> {
+    Some(match (method, param) {
+        (_, "block_id") => json!("latest"),
+        (_, "block_hash") => latest_block_hash.clone(),
+        (_, "contract_address") => json!(TEST_CONTRACT_ADDRESS),
+        (_, "class_hash") => json!(TEST_CLASS_HASH),
+        (_, "key") => json!("0x0"),
+        (_, "index") => json!(0),
+        (_, "transaction_hash") => json!("0x1"),
+        ("starknet_call", "request") => json!({
+            "contract_address": TEST_CONTRACT_ADDRESS,
+            "entry_point_selector": selector("return_result"),
+            "calldata": ["0x19"],
+        }),
+        ("starknet_estimateFee", "request") | (_, "transactions") => json!([invoke_transaction()]),
+        (_, "simulation_flags") => json!([]),
+        ("starknet_getEvents", "filter") => json!({
+            "from_block": { "block_number": 0 },
+            "to_block": "latest",
+            "chunk_size": 10,
+        }),
+        _ => return None,
+    })
+}
+
+/// Returns a broadcasted invoke transaction of the genesis account calling the test contract
+fn invoke_transaction() -> Value {
+    json!({
+        "type": "INVOKE",
+        "max_fee": "0x0",
+        "version": "0x1",
+        "signature": [],
+        "nonce": "0x0",
+        "sender_address": TEST_ACCOUNT_ADDRESS,
+        // The account calls `return_result(0x19)` on the test contract
+        "calldata": [TEST_CONTRACT_ADDRESS, selector("return_result"), "0x1", "0x19"],
+    })
+}
+
+/// Returns the selector of an entry point, which is its Starknet keccak
+fn selector(entry_point: &str) -> String {
+    let mut hash = keccak_256(entry_point.as_bytes());
+    // The Starknet keccak only keeps the 250 lowest bits
+    hash[0] &= 0x03;
+    format!("{:#x}", U256::from_big_endian(&hash))
+}
+
+/// Validates a value against a schema of the spec
+fn validate(spec: &Value, schema: &Value, value: &Value) -> Result<(), String> {
+    // References point to the spec components, which are embedded in the root schema to be resolved
+    let root = json!({ "allOf": [schema], "components": spec["components"] });
+    let schema = JSONSchema::options().with_draft(Draft::Draft7).compile(&root).map_err(|e| e.to_string())?;
+
+    schema
+        .validate(value)
+        .map_err(|errors| errors.map(|e| format!("{e} at {}", e.instance_path)).collect::<Vec<_>>().join(", "))
+}
+
+/// Returns the codes of the errors a method declares
+fn declared_error_codes(spec: &Value, method: &Value) -> Vec<i64> {
+    method["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|error| {
+            let name = error["$ref"].as_str()?.strip_prefix("#/components/errors/")?;
+            spec["components"]["errors"][name]["code"].as_i64()
+        })
+        .collect()
+}
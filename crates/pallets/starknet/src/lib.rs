@@ -10,9 +10,9 @@
 //! during the block lifecycle: on_finalize, on_initialize, on_runtime_upgrade, and offchain_worker.
 //!
 //! 3. Storage: Several storage items are defined, including Pending, CurrentBlock, BlockHash,
-//! ContractClassHashes, ContractClasses, Nonces, StorageView, LastKnownEthBlock, and
-//! FeeTokenAddress. These storage items are used to store and manage data related to the Starknet
-//! pallet.
+//! ContractClassHashes, ContractClasses, ClassCommitment, SierraClasses, Nonces, StorageView,
+//! LastKnownEthBlock, and FeeTokenAddress. These storage items are used to store and manage data
+//! related to the Starknet pallet.
 //!
 //! 4. Genesis Configuration: The GenesisConfig struct is defined, which is used to set up the
 //! initial state of the pallet during genesis. The struct includes fields for contracts,
@@ -76,6 +76,9 @@ pub const SEQUENCER_ADDRESS: [u8; 32] =
 pub const ETHEREUM_EXECUTION_RPC: &[u8] = b"starknet::ETHEREUM_EXECUTION_RPC";
 pub const ETHEREUM_CONSENSUS_RPC: &[u8] = b"starknet::ETHEREUM_CONSENSUS_RPC";
 
+/// The storage variable holding the balances of the fee token contract.
+pub const FEE_TOKEN_BALANCES_STORAGE_VAR: &str = "ERC20_balances";
+
 // syntactic sugar for logging.
 #[macro_export]
 macro_rules! log {
@@ -92,14 +95,15 @@ pub mod pallet {
     pub extern crate alloc;
     use alloc::str::from_utf8;
     use alloc::string::{String, ToString};
-    use alloc::vec;
     use alloc::vec::Vec;
+    use alloc::{format, vec};
 
     use blockifier::abi::abi_utils;
     use blockifier::block_context::BlockContext;
+    use blockifier::execution::contract_class::ContractClass;
     use blockifier::execution::entry_point::{CallInfo, ExecutionContext, ExecutionResources};
     use blockifier::state::cached_state::{CachedState, ContractStorageKey};
-    use blockifier::state::state_api::State;
+    use blockifier::state::state_api::{State, StateReader};
     use blockifier::test_utils::DictStateReader;
     use blockifier::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
     use blockifier::transaction::objects::AccountTransactionContext;
@@ -111,13 +115,22 @@ pub mod pallet {
     use mp_starknet::block::{Block as StarknetBlock, BlockTransactions, Header as StarknetHeader, MaxTransactions};
     use mp_starknet::crypto::commitment;
     use mp_starknet::crypto::hash::pedersen::PedersenHasher;
+    use mp_starknet::crypto::merkle_patricia_tree::stored_tree::StoredNode;
     use mp_starknet::execution::{
         CallEntryPointWrapper, ClassHashWrapper, ContractAddressWrapper, ContractClassWrapper, EntryPointTypeWrapper,
+        SierraClassWrapper,
     };
+    use mp_starknet::fees;
+    use mp_starknet::state::{commit_state_diff, ParentState};
     use mp_starknet::storage::{StarknetStorageSchemaVersion, PALLET_STARKNET_SCHEMA};
     use mp_starknet::traits::hash::Hasher;
+    use mp_starknet::transaction::messages_sent;
     use mp_starknet::transaction::types::{
-        EventError, EventWrapper as StarknetEventType, StateDiffError, Transaction, TransactionReceiptWrapper, TxType,
+        DeclareTransaction, DeployAccountTransaction, EventError, EventWrapper as StarknetEventType,
+        FeeEstimateWrapper, FunctionInvocationWrapper, InvokeTransaction, L1HandlerTransaction, MaxArraySize,
+        MessageError, MessageToL1Wrapper, SimulatedTransactionWrapper, SimulationFlags, StateDiffError, Transaction,
+        TransactionExecutionErrorWrapper, TransactionExecutionStatus, TransactionReceiptWrapper,
+        TransactionTraceWrapper, TxType,
     };
     use pallet_transaction_payment::OnChargeTransaction;
     use serde_json::from_str;
@@ -139,7 +152,10 @@ pub mod pallet {
 
     use super::*;
     use crate::message::{get_messages_events, LAST_FINALIZED_BLOCK_QUERY};
-    use crate::types::{ContractClassMapping, ContractStorageKeyWrapper, EthLogs, NonceWrapper, StarkFeltWrapper};
+    use crate::state_root::ClassTrieStorage;
+    use crate::types::{
+        ContractClassMapping, ContractStorageKeyWrapper, EthLogs, NonceWrapper, StarkFeltWrapper, TransactionCallInfos,
+    };
 
     #[pallet::pallet]
     pub struct Pallet<T>(_);
@@ -157,6 +173,11 @@ pub mod pallet {
         type SystemHash: Hasher;
         /// The time idk what.
         type TimestampProvider: Time;
+        /// Number of blocks a validated Starknet transaction stays in the pool before it is
+        /// revalidated.
+        type TransactionLongevity: Get<u64>;
+        /// Price of a unit of L1 gas, in the fee token, used to compute the transaction fees.
+        type L1GasPrice: Get<u128>;
     }
 
     /// The Starknet pallet hooks.
@@ -175,7 +196,13 @@ pub mod pallet {
 
         /// The block is being initialized. Implement to have something happen.
         fn on_initialize(_: T::BlockNumber) -> Weight {
-            Weight::zero()
+            // Forget the outputs of the previous block.
+            BlockReceipts::<T>::kill();
+            BlockEvents::<T>::kill();
+            BlockMessagesToL1::<T>::kill();
+            BlockDeclaredClasses::<T>::kill();
+            BlockStorageDiffs::<T>::kill();
+            T::DbWeight::get().writes(5)
         }
 
         /// Perform a module upgrade.
@@ -216,6 +243,29 @@ pub mod pallet {
     pub(super) type PendingEvents<T: Config> =
         StorageValue<_, BoundedVec<StarknetEventType, MaxTransactions>, ValueQuery>;
 
+    /// Receipts of the transactions of the current block.
+    /// Cleared when the next block is initialized, so that it can be read at the end of the block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_receipts)]
+    pub(super) type BlockReceipts<T: Config> =
+        StorageValue<_, BoundedVec<TransactionReceiptWrapper, MaxTransactions>, ValueQuery>;
+
+    /// Events emitted in the current block, along with the index of the extrinsic which emitted
+    /// them.
+    /// Cleared when the next block is initialized, so that it can be read at the end of the block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_events)]
+    pub(super) type BlockEvents<T: Config> =
+        StorageValue<_, BoundedVec<(u32, StarknetEventType), MaxTransactions>, ValueQuery>;
+
+    /// Messages sent to L1 in the current block, along with their hash and the index of the
+    /// extrinsic which sent them.
+    /// Cleared when the next block is initialized, so that it can be read at the end of the block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_messages_to_l1)]
+    pub(super) type BlockMessagesToL1<T: Config> =
+        StorageValue<_, BoundedVec<(u32, H256, MessageToL1Wrapper), MaxTransactions>, ValueQuery>;
+
     /// The current Starknet block.
     #[pallet::storage]
     #[pallet::getter(fn current_block)]
@@ -241,6 +291,51 @@ pub mod pallet {
     pub(super) type ContractClasses<T: Config> =
         StorageMap<_, Identity, ClassHashWrapper, ContractClassWrapper, ValueQuery>;
 
+    /// Mapping from Starknet class hash to the compiled class hash of a Cairo 1 class.
+    /// These are the leaves of the class commitment tree.
+    /// Safe to use `Identity` as the key is already a hash.
+    #[pallet::storage]
+    #[pallet::getter(fn compiled_class_hash)]
+    pub(super) type CompiledClassHashes<T: Config> =
+        StorageMap<_, Identity, ClassHashWrapper, ClassHashWrapper, OptionQuery>;
+
+    /// Root of the class commitment tree, zero until a Cairo 1 class is declared.
+    #[pallet::storage]
+    #[pallet::getter(fn class_commitment)]
+    pub(super) type ClassCommitment<T: Config> = StorageValue<_, H256, ValueQuery>;
+
+    /// Mapping from hash to node of the class commitment tree.
+    /// Safe to use `Identity` as the key is already a hash.
+    #[pallet::storage]
+    pub(super) type ClassTrieNodes<T: Config> = StorageMap<_, Identity, H256, StoredNode, OptionQuery>;
+
+    /// Mapping from Starknet class hash to the Sierra class of a Cairo 1 class.
+    /// Safe to use `Identity` as the key is already a hash.
+    #[pallet::storage]
+    #[pallet::getter(fn sierra_class_by_class_hash)]
+    pub(super) type SierraClasses<T: Config> =
+        StorageMap<_, Identity, ClassHashWrapper, SierraClassWrapper, OptionQuery>;
+
+    /// Classes declared in the current block, with their compiled class hash for Cairo 1 classes.
+    /// Cleared when the next block is initialized, so that it can be read at the end of the block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_declared_classes)]
+    pub(super) type BlockDeclaredClasses<T: Config> =
+        StorageValue<_, BoundedVec<(ClassHashWrapper, Option<ClassHashWrapper>), MaxTransactions>, ValueQuery>;
+
+    /// Storage vars written in the current block, in the order of the writes.
+    /// Cleared when the next block is initialized, so that it can be read at the end of the block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_storage_diffs)]
+    pub(super) type BlockStorageDiffs<T: Config> =
+        StorageValue<_, BoundedVec<(ContractAddressWrapper, H256, U256), MaxTransactions>, ValueQuery>;
+
+    /// Hashes of the version 0 invoke transactions included so far.
+    /// They have no nonce, so their hash is what prevents them from being replayed.
+    /// Safe to use `Identity` as the key is already a hash.
+    #[pallet::storage]
+    pub(super) type LegacyInvokeHashes<T: Config> = StorageMap<_, Identity, H256, (), OptionQuery>;
+
     /// Mapping from Starknet contract address to its nonce.
     /// Safe to use `Identity` as the key is already a hash.
     #[pallet::storage]
@@ -268,6 +363,12 @@ pub mod pallet {
     #[pallet::getter(fn fee_token_address)]
     pub(super) type FeeTokenAddress<T: Config> = StorageValue<_, ContractAddressWrapper, ValueQuery>;
 
+    /// Whether the development calls, which set the state without executing transactions, can be
+    /// dispatched. Only set at genesis, for development chains.
+    #[pallet::storage]
+    #[pallet::getter(fn dev_calls_enabled)]
+    pub(super) type DevCallsEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
     /// Starknet genesis configuration.
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
@@ -287,6 +388,9 @@ pub mod pallet {
         /// The address of the fee token.
         /// Must be set to the address of the fee token ERC20 contract.
         pub fee_token_address: ContractAddressWrapper,
+        /// Whether the development calls `set_storage`, `set_nonce` and `mint_fee_token` can be
+        /// dispatched. Must only be enabled for development chains.
+        pub dev_calls_enabled: bool,
         pub _phantom: PhantomData<T>,
     }
 
@@ -298,6 +402,7 @@ pub mod pallet {
                 contract_classes: vec![],
                 storage: vec![],
                 fee_token_address: ContractAddressWrapper::default(),
+                dev_calls_enabled: false,
                 _phantom: PhantomData,
             }
         }
@@ -326,6 +431,7 @@ pub mod pallet {
             LastKnownEthBlock::<T>::set(None);
             // Set the fee token address from the genesis config.
             FeeTokenAddress::<T>::set(self.fee_token_address);
+            DevCallsEnabled::<T>::set(self.dev_calls_enabled);
         }
     }
 
@@ -345,6 +451,26 @@ pub mod pallet {
             old_fee_token_address: ContractAddressWrapper,
             new_fee_token_address: ContractAddressWrapper,
         },
+        /// Emitted when a class is declared, either by a declare transaction or through the state
+        /// diff of a transaction. Cairo 0 classes have no compiled class hash.
+        /// [class_hash, compiled_class_hash]
+        ClassDeclared {
+            class_hash: ClassHashWrapper,
+            compiled_class_hash: Option<ClassHashWrapper>,
+        },
+        /// Emitted when the execution of a transaction is reverted.
+        /// The transaction is still included, but the state changes of its execution are rolled
+        /// back. [transaction_hash, revert_reason]
+        TransactionReverted {
+            transaction_hash: H256,
+            revert_reason: BoundedVec<u8, MaxArraySize>,
+        },
+        /// Emitted when a transaction sends a message to L1.
+        /// The hash is the one under which the message is recorded on L1. [message_hash, message]
+        MessageSentToL1 {
+            message_hash: H256,
+            message: MessageToL1Wrapper,
+        },
     }
 
     /// The Starknet pallet custom errors.
@@ -356,17 +482,22 @@ pub mod pallet {
         ClassHashAlreadyDeclared,
         ContractClassHashUnknown,
         ContractClassAlreadyAssociated,
-        ContractClassMustBeSpecified,
         AccountAlreadyDeployed,
         ContractAddressAlreadyAssociated,
         InvalidContractClass,
-        ClassHashMustBeSpecified,
         TooManyPendingTransactions,
         TooManyPendingEvents,
         StateReaderError,
         EmitEventError,
+        SendMessageError,
         StateDiffError,
         ContractNotFound,
+        TransactionValidationFailed,
+        FeeTransferFailed,
+        InvalidStorageKey,
+        FeeTokenBalanceOverflow,
+        TraceConversionError,
+        DevCallsDisabled,
     }
 
     /// The Starknet pallet external functions.
@@ -395,9 +526,13 @@ pub mod pallet {
         /// The invoke transaction is the main transaction type used to invoke contract functions in
         /// Starknet.
         /// See `https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#invoke_transaction`.
+        ///
+        /// Legacy version 0 transactions call an entrypoint of the sender contract directly: they
+        /// are neither validated by an account nor ordered by a nonce, and are not charged. Their
+        /// hash is recorded so that they cannot be replayed.
         /// # Arguments
         ///
-        /// * `origin` - The origin of the transaction.
+        /// * `origin` - The origin of the transaction, must be none.
         /// * `transaction` - The Starknet transaction.
         ///
         ///  # Returns
@@ -408,44 +543,66 @@ pub mod pallet {
         /// * Compute weight
         #[pallet::call_index(1)]
         #[pallet::weight(0)]
-        pub fn invoke(_origin: OriginFor<T>, transaction: Transaction) -> DispatchResult {
-            // TODO: add origin check when proxy pallet added
+        pub fn invoke(origin: OriginFor<T>, transaction: InvokeTransaction) -> DispatchResult {
+            // Starknet transactions are unsigned extrinsics, validated by `validate_unsigned`.
+            ensure_none(origin)?;
 
             // Check if contract is deployed
             ensure!(ContractClassHashes::<T>::contains_key(transaction.sender_address), Error::<T>::AccountNotDeployed);
 
-            // Get current block
-            let block = Self::current_block();
-            // Get fee token address
-            let fee_token_address = Self::fee_token_address();
+            let transaction = Transaction::from(transaction);
+            let legacy_hash =
+                Self::legacy_invoke_hash(&transaction).map_err(|_| Error::<T>::TransactionExecutionFailed)?;
             let state = &mut Self::create_state_reader()?;
-            let call_info = transaction.execute(state, block, TxType::InvokeTx, None, fee_token_address);
-            let receipt;
-            match call_info {
-                Ok(Some(mut v)) => {
-                    let events = Self::emit_events(&mut v).map_err(|_| Error::<T>::EmitEventError)?;
-                    receipt = TransactionReceiptWrapper {
-                        events: BoundedVec::try_from(events).unwrap(),
-                        transaction_hash: transaction.hash,
-                        tx_type: TxType::InvokeTx,
-                        actual_fee: U256::zero(), // TODO: switch to actual fee (#251)
-                    };
-                    log!(debug, "Transaction executed successfully: {:?}", v);
-                }
-                Ok(None) => {
-                    log!(error, "Transaction execution failed: no call info while it was expected");
-                    return Err(Error::<T>::TransactionExecutionFailed.into());
+            let call_infos = Self::execute_transaction(state, &transaction, None, false, true)?;
+            let messages_sent = Self::send_messages(&call_infos).map_err(|_| Error::<T>::SendMessageError)?;
+            let receipt = match call_infos.revert_reason {
+                // Reverted transactions are included along with their revert reason, with the events of
+                // their validation and fee transfer only.
+                Some(revert_reason) => {
+                    let revert_reason = BoundedVec::truncate_from(revert_reason.into_bytes());
+                    Self::deposit_event(Event::TransactionReverted {
+                        transaction_hash: transaction.hash(),
+                        revert_reason: revert_reason.clone(),
+                    });
+                    let events = Self::emit_events(&call_infos).map_err(|_| Error::<T>::EmitEventError)?;
+                    TransactionReceiptWrapper {
+                        events: BoundedVec::try_from(events).map_err(|_| Error::<T>::EmitEventError)?,
+                        transaction_hash: transaction.hash(),
+                        actual_fee: U256::from(call_infos.actual_fee),
+                        messages_sent,
+                        execution_status: TransactionExecutionStatus::Reverted,
+                        revert_reason: Some(revert_reason),
+                    }
                 }
-                Err(e) => {
-                    log!(error, "Transaction execution failed: {:?}", e);
-                    return Err(Error::<T>::TransactionExecutionFailed.into());
+                None => {
+                    let call_info = call_infos.execute_call_info.as_ref().ok_or_else(|| {
+                        log!(error, "Transaction execution failed: no call info while it was expected");
+                        Error::<T>::TransactionExecutionFailed
+                    })?;
+                    log!(debug, "Transaction executed successfully: {:?}", call_info);
+                    let events = Self::emit_events(&call_infos).map_err(|_| Error::<T>::EmitEventError)?;
+                    TransactionReceiptWrapper {
+                        events: BoundedVec::try_from(events).map_err(|_| Error::<T>::EmitEventError)?,
+                        transaction_hash: transaction.hash(),
+                        actual_fee: U256::from(call_infos.actual_fee),
+                        messages_sent,
+                        execution_status: TransactionExecutionStatus::Succeeded,
+                        revert_reason: None,
+                    }
                 }
-            }
+            };
 
             Self::apply_state_diffs(state).map_err(|_| Error::<T>::StateDiffError)?;
+            if transaction.uses_account_nonce() {
+                Self::increment_nonce(transaction.sender_address());
+            }
+            if let Some(legacy_hash) = legacy_hash {
+                LegacyInvokeHashes::<T>::insert(legacy_hash, ());
+            }
 
             // Append the transaction to the pending transactions.
-            Pending::<T>::try_append((transaction, receipt)).map_err(|_| Error::<T>::TooManyPendingTransactions)?;
+            Self::append_transaction(transaction, receipt)?;
 
             Ok(())
         }
@@ -455,7 +612,7 @@ pub mod pallet {
         /// call. See `https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#declare_transaction`.
         /// # Arguments
         ///
-        /// * `origin` - The origin of the transaction.
+        /// * `origin` - The origin of the transaction, must be none.
         /// * `transaction` - The Starknet transaction.
         ///
         ///  # Returns
@@ -466,59 +623,51 @@ pub mod pallet {
         /// * Compute weight
         #[pallet::call_index(2)]
         #[pallet::weight(0)]
-        pub fn declare(_origin: OriginFor<T>, transaction: Transaction) -> DispatchResult {
-            // TODO: add origin check when proxy pallet added
+        pub fn declare(origin: OriginFor<T>, transaction: DeclareTransaction) -> DispatchResult {
+            // Starknet transactions are unsigned extrinsics, validated by `validate_unsigned`.
+            ensure_none(origin)?;
 
             // Check if contract is deployed
             ensure!(ContractClassHashes::<T>::contains_key(transaction.sender_address), Error::<T>::AccountNotDeployed);
 
-            // Check that class hash is not None
-            ensure!(transaction.call_entrypoint.class_hash.is_some(), Error::<T>::ClassHashMustBeSpecified);
-
-            let class_hash = transaction.call_entrypoint.class_hash.unwrap();
+            let class_hash = transaction.class_hash;
 
             // Check class hash is not already declared
             ensure!(!ContractClasses::<T>::contains_key(class_hash), Error::<T>::ClassHashAlreadyDeclared);
 
-            // Check that contract class is not None
-            ensure!(transaction.contract_class.is_some(), Error::<T>::ContractClassMustBeSpecified);
-
-            // Get current block
-            let block = Self::current_block();
-            // Get fee token address
-            let fee_token_address = Self::fee_token_address();
             // Create state reader from substrate storage
             let state = &mut Self::create_state_reader()?;
 
             // Parse contract class
-            let contract_class = transaction
-                .clone()
-                .contract_class
-                .unwrap()
-                .to_starknet_contract_class()
-                .or(Err(Error::<T>::InvalidContractClass))?;
+            let contract_class =
+                transaction.contract_class.to_starknet_contract_class().or(Err(Error::<T>::InvalidContractClass))?;
 
             // Execute transaction
-            match transaction.execute(state, block, TxType::DeclareTx, Some(contract_class.clone()), fee_token_address)
-            {
-                Ok(_) => {
-                    log!(debug, "Declare Transaction executed successfully.");
-                }
-                Err(e) => {
-                    log!(error, "Transaction execution failed: {:?}", e);
-                    return Err(Error::<T>::TransactionExecutionFailed.into());
-                }
-            }
+            let transaction = Transaction::from(transaction);
+            let call_infos = Self::execute_transaction(state, &transaction, Some(contract_class.clone()), false, true)?;
+            log!(debug, "Declare Transaction executed successfully.");
+            let receipt = TransactionReceiptWrapper {
+                transaction_hash: transaction.hash(),
+                actual_fee: U256::from(call_infos.actual_fee),
+                messages_sent: Self::send_messages(&call_infos).map_err(|_| Error::<T>::SendMessageError)?,
+                events: BoundedVec::try_from(Self::emit_events(&call_infos).map_err(|_| Error::<T>::EmitEventError)?)
+                    .map_err(|_| Error::<T>::EmitEventError)?,
+                execution_status: TransactionExecutionStatus::Succeeded,
+                revert_reason: None,
+            };
 
             // Append the transaction to the pending transactions.
-            Pending::<T>::try_append((transaction.clone(), TransactionReceiptWrapper::default()))
-                .or(Err(Error::<T>::TooManyPendingTransactions))?;
+            Self::append_transaction(transaction.clone(), receipt)?;
 
             // Associate contract class to class hash
             Self::set_contract_class_hash(class_hash, contract_class.into())?;
             Self::apply_state_diffs(state).map_err(|_| Error::<T>::StateDiffError)?;
+            Self::increment_nonce(transaction.sender_address());
 
-            // TODO: Update class hashes root
+            // Cairo 0 classes are not part of the class commitment.
+            BlockDeclaredClasses::<T>::try_append((class_hash, None))
+                .or(Err(Error::<T>::TooManyPendingTransactions))?;
+            Self::deposit_event(Event::ClassDeclared { class_hash, compiled_class_hash: None });
 
             Ok(())
         }
@@ -529,7 +678,7 @@ pub mod pallet {
         /// details) . You can then send the deploy_account transaction. See `https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#deploy_account_transaction`.
         /// # Arguments
         ///
-        /// * `origin` - The origin of the transaction.
+        /// * `origin` - The origin of the transaction, must be none.
         /// * `transaction` - The Starknet transaction.
         ///
         ///  # Returns
@@ -540,37 +689,38 @@ pub mod pallet {
         /// * Compute weight
         #[pallet::call_index(3)]
         #[pallet::weight(0)]
-        pub fn deploy_account(_origin: OriginFor<T>, transaction: Transaction) -> DispatchResult {
-            // TODO: add origin check when proxy pallet added
+        pub fn deploy_account(origin: OriginFor<T>, transaction: DeployAccountTransaction) -> DispatchResult {
+            // Starknet transactions are unsigned extrinsics, validated by `validate_unsigned`.
+            ensure_none(origin)?;
 
             // Check if contract is deployed
             ensure!(
-                !ContractClassHashes::<T>::contains_key(transaction.sender_address),
+                !ContractClassHashes::<T>::contains_key(transaction.contract_address),
                 Error::<T>::AccountAlreadyDeployed
             );
 
-            // Get current block
-            let block = Self::current_block();
-            // Get fee token address
-            let fee_token_address = Self::fee_token_address();
-
+            let transaction = Transaction::from(transaction);
+            let legacy_hash =
+                Self::legacy_invoke_hash(&transaction).map_err(|_| Error::<T>::TransactionExecutionFailed)?;
             let state = &mut Self::create_state_reader()?;
-            match transaction.execute(state, block, TxType::DeployAccountTx, None, fee_token_address) {
-                Ok(v) => {
-                    log!(debug, "Transaction executed successfully: {:?}", v.unwrap());
-                }
-                Err(e) => {
-                    log!(error, "Transaction execution failed: {:?}", e);
-                    return Err(Error::<T>::TransactionExecutionFailed.into());
-                }
-            }
+            let call_infos = Self::execute_transaction(state, &transaction, None, false, true)?;
+            log!(debug, "Transaction executed successfully: {:?}", call_infos.execute_call_info);
+            let receipt = TransactionReceiptWrapper {
+                transaction_hash: transaction.hash(),
+                actual_fee: U256::from(call_infos.actual_fee),
+                messages_sent: Self::send_messages(&call_infos).map_err(|_| Error::<T>::SendMessageError)?,
+                events: BoundedVec::try_from(Self::emit_events(&call_infos).map_err(|_| Error::<T>::EmitEventError)?)
+                    .map_err(|_| Error::<T>::EmitEventError)?,
+                execution_status: TransactionExecutionStatus::Succeeded,
+                revert_reason: None,
+            };
             // Append the transaction to the pending transactions.
-            Pending::<T>::try_append((transaction.clone(), TransactionReceiptWrapper::default()))
-                .map_err(|_| Error::<T>::TooManyPendingTransactions)?;
+            Self::append_transaction(transaction.clone(), receipt)?;
 
             // Associate contract class to class hash
             // TODO: update state root
             Self::apply_state_diffs(state).map_err(|_| Error::<T>::StateDiffError)?;
+            Self::increment_nonce(transaction.sender_address());
 
             Ok(())
         }
@@ -579,7 +729,7 @@ pub mod pallet {
         ///
         /// # Arguments
         ///
-        /// * `origin` - The origin of the transaction.
+        /// * `origin` - The origin of the transaction, must be none.
         /// * `transaction` - The Starknet transaction.
         ///
         /// # Returns
@@ -590,27 +740,33 @@ pub mod pallet {
         /// * Compute weight
         #[pallet::call_index(4)]
         #[pallet::weight(0)]
-        pub fn consume_l1_message(_origin: OriginFor<T>, transaction: Transaction) -> DispatchResult {
-            // TODO: add origin check when proxy pallet added
+        pub fn consume_l1_message(origin: OriginFor<T>, transaction: L1HandlerTransaction) -> DispatchResult {
+            // Starknet transactions are unsigned extrinsics, validated by `validate_unsigned`.
+            ensure_none(origin)?;
             // Check if contract is deployed
-            ensure!(ContractClassHashes::<T>::contains_key(transaction.sender_address), Error::<T>::AccountNotDeployed);
+            ensure!(
+                ContractClassHashes::<T>::contains_key(transaction.contract_address),
+                Error::<T>::AccountNotDeployed
+            );
 
-            let block = Self::current_block();
-            let fee_token_address = Self::fee_token_address();
+            let transaction = Transaction::from(transaction);
+            let legacy_hash =
+                Self::legacy_invoke_hash(&transaction).map_err(|_| Error::<T>::TransactionExecutionFailed)?;
             let state = &mut Self::create_state_reader()?;
-            match transaction.execute(state, block, TxType::L1HandlerTx, None, fee_token_address) {
-                Ok(v) => {
-                    log!(debug, "Transaction executed successfully: {:?}", v.unwrap());
-                }
-                Err(e) => {
-                    log!(error, "Transaction execution failed: {:?}", e);
-                    return Err(Error::<T>::TransactionExecutionFailed.into());
-                }
-            }
+            let call_infos = Self::execute_transaction(state, &transaction, None, false, true)?;
+            log!(debug, "Transaction executed successfully: {:?}", call_infos.execute_call_info);
+            let receipt = TransactionReceiptWrapper {
+                transaction_hash: transaction.hash(),
+                actual_fee: U256::from(call_infos.actual_fee),
+                messages_sent: Self::send_messages(&call_infos).map_err(|_| Error::<T>::SendMessageError)?,
+                events: BoundedVec::try_from(Self::emit_events(&call_infos).map_err(|_| Error::<T>::EmitEventError)?)
+                    .map_err(|_| Error::<T>::EmitEventError)?,
+                execution_status: TransactionExecutionStatus::Succeeded,
+                revert_reason: None,
+            };
 
             // Append the transaction to the pending transactions.
-            Pending::<T>::try_append((transaction.clone(), TransactionReceiptWrapper::default()))
-                .or(Err(Error::<T>::TooManyPendingTransactions))?;
+            Self::append_transaction(transaction.clone(), receipt)?;
 
             Self::apply_state_diffs(state).map_err(|_| Error::<T>::StateDiffError)?;
 
@@ -649,6 +805,153 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Set the value of a contract storage slot.
+        /// Only meant for development chains, to put the state in a given shape.
+        /// Fails unless the development calls are enabled in the genesis config.
+        ///
+        /// # Arguments
+        ///
+        /// * `origin` - The origin of the transaction, must be root.
+        /// * `contract_address` - The address of the contract.
+        /// * `key` - The storage key.
+        /// * `value` - The new value of the storage slot.
+        ///
+        /// # Returns
+        ///
+        /// * `DispatchResult` - The result of the transaction.
+        #[pallet::call_index(6)]
+        #[pallet::weight(0)]
+        pub fn set_storage(
+            origin: OriginFor<T>,
+            contract_address: ContractAddressWrapper,
+            key: H256,
+            value: StarkFeltWrapper,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Self::dev_calls_enabled(), Error::<T>::DevCallsDisabled);
+            StorageView::<T>::insert((contract_address, key), value);
+            Ok(())
+        }
+
+        /// Set the nonce of a contract.
+        /// Only meant for development chains, to put the state in a given shape.
+        /// Fails unless the development calls are enabled in the genesis config.
+        ///
+        /// # Arguments
+        ///
+        /// * `origin` - The origin of the transaction, must be root.
+        /// * `contract_address` - The address of the contract.
+        /// * `nonce` - The new nonce of the contract.
+        ///
+        /// # Returns
+        ///
+        /// * `DispatchResult` - The result of the transaction.
+        #[pallet::call_index(7)]
+        #[pallet::weight(0)]
+        pub fn set_nonce(
+            origin: OriginFor<T>,
+            contract_address: ContractAddressWrapper,
+            nonce: NonceWrapper,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Self::dev_calls_enabled(), Error::<T>::DevCallsDisabled);
+            Nonces::<T>::insert(contract_address, nonce);
+            Ok(())
+        }
+
+        /// Mint fee tokens to an account, by increasing its balance in the fee token contract.
+        /// Only meant for development chains, to fund accounts.
+        /// Fails unless the development calls are enabled in the genesis config.
+        ///
+        /// # Arguments
+        ///
+        /// * `origin` - The origin of the transaction, must be root.
+        /// * `recipient` - The address of the account to fund.
+        /// * `amount` - The amount of tokens to mint.
+        ///
+        /// # Returns
+        ///
+        /// * `DispatchResult` - The result of the transaction.
+        #[pallet::call_index(8)]
+        #[pallet::weight(0)]
+        pub fn mint_fee_token(origin: OriginFor<T>, recipient: ContractAddressWrapper, amount: U256) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Self::dev_calls_enabled(), Error::<T>::DevCallsDisabled);
+            let fee_token_address = Self::fee_token_address();
+            let (low_key, high_key) = Self::fee_token_balance_keys(recipient)?;
+
+            // The balance is an Uint256, stored as its low and high 128 bits.
+            let balance =
+                Self::storage((fee_token_address, low_key)) + (Self::storage((fee_token_address, high_key)) << 128);
+            let balance = balance.checked_add(amount).ok_or(Error::<T>::FeeTokenBalanceOverflow)?;
+            StorageView::<T>::insert((fee_token_address, low_key), balance & U256::from(u128::MAX));
+            StorageView::<T>::insert((fee_token_address, high_key), balance >> 128);
+            Ok(())
+        }
+    }
+
+    /// Starknet transactions are submitted as unsigned extrinsics: they are authenticated by the
+    /// validation entrypoint of their account rather than by a Substrate signature, so that users
+    /// do not need a Substrate account.
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Validate a Starknet transaction before it enters the pool.
+        ///
+        /// Transactions with a future nonce are valid, but wait in the pool until the transaction
+        /// with the previous nonce of their sender is ready.
+        ///
+        /// # Arguments
+        ///
+        /// * `source` - The source of the transaction.
+        /// * `call` - The call wrapping the transaction.
+        ///
+        /// # Returns
+        ///
+        /// * `TransactionValidity` - The validity of the transaction, providing the nonce of its
+        ///   sender and requiring the previous one.
+        fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let transaction = Self::unsigned_transaction(source, call)?;
+            Self::validate_unsigned_transaction(&transaction)?;
+
+            let sender_address = transaction.sender_address();
+            let nonce = transaction.nonce();
+            let mut valid_transaction =
+                ValidTransaction::with_tag_prefix("starknet").longevity(T::TransactionLongevity::get()).propagate(true);
+            // Version 0 invoke transactions have no nonce, and are only told apart by their hash.
+            valid_transaction = match Self::legacy_invoke_hash(&transaction)? {
+                Some(legacy_hash) => valid_transaction.and_provides(legacy_hash),
+                None => valid_transaction.and_provides((sender_address, nonce)),
+            };
+            if transaction.uses_account_nonce() && nonce > Self::nonce(sender_address) {
+                valid_transaction = valid_transaction.and_requires((sender_address, nonce - U256::one()));
+            }
+
+            valid_transaction.build()
+        }
+
+        /// Validate a Starknet transaction before it is executed in a block.
+        ///
+        /// Only the transaction with the current nonce of its sender can be executed.
+        ///
+        /// # Arguments
+        ///
+        /// * `call` - The call wrapping the transaction.
+        ///
+        /// # Error
+        ///
+        /// Returns an error if the transaction can't be executed.
+        fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+            let transaction = Self::unsigned_transaction(TransactionSource::InBlock, call)?;
+            Self::validate_unsigned_transaction(&transaction)?;
+
+            if transaction.uses_account_nonce() {
+                ensure!(transaction.nonce() == Self::nonce(transaction.sender_address()), InvalidTransaction::Future);
+            }
+            Ok(())
+        }
     }
 
     /// The Starknet pallet internal functions.
@@ -704,7 +1007,16 @@ pub mod pallet {
         }
 
         /// Call a smart contract function.
+        ///
+        /// # Arguments
+        ///
+        /// * `pending_transactions` - The transactions of the pending block, executed before the
+        ///   call. Empty to call the contract on the current state.
+        /// * `address` - The address of the contract to call.
+        /// * `function_selector` - The selector of the function to call.
+        /// * `calldata` - The arguments of the call.
         pub fn call_contract(
+            pending_transactions: Vec<Transaction>,
             address: ContractAddressWrapper,
             function_selector: H256,
             calldata: Vec<U256>,
@@ -714,9 +1026,16 @@ pub mod pallet {
             // Get fee token address
             let fee_token_address = Self::fee_token_address();
             // Get state
-            let state = &mut Self::create_state_reader()?;
-            // Get class hash
-            let class_hash = ContractClassHashes::<T>::try_get(address).map_err(|_| Error::<T>::ContractNotFound)?;
+            let state = &mut Self::pending_state(pending_transactions)?;
+            // Get class hash, as the contract may have been deployed by a pending transaction
+            let contract_address = StarkFelt::new(address)
+                .ok()
+                .and_then(|address| ContractAddress::try_from(address).ok())
+                .ok_or(Error::<T>::ContractNotFound)?;
+            let class_hash = state.get_class_hash_at(contract_address).map_err(|_| Error::<T>::ContractNotFound)?;
+            ensure!(class_hash != ClassHash::default(), Error::<T>::ContractNotFound);
+            let class_hash: ClassHashWrapper =
+                class_hash.0.bytes().try_into().map_err(|_| Error::<T>::ContractNotFound)?;
 
             let entrypoint = CallEntryPointWrapper::new(
                 Some(class_hash),
@@ -740,6 +1059,189 @@ pub mod pallet {
             }
         }
 
+        /// Re-execute transactions on top of the current state and trace their execution.
+        ///
+        /// The transactions are replayed sequentially on a single scratch state, so that each one
+        /// sees the effects of the previous ones, as it did when the block was built.
+        ///
+        /// # Arguments
+        ///
+        /// * `transactions` - The transactions to trace, in execution order.
+        /// * `target` - If set, stop once the transaction with this hash has been traced.
+        ///
+        /// # Returns
+        ///
+        /// The execution traces of the transactions.
+        pub fn trace_transactions(
+            transactions: Vec<Transaction>,
+            target: Option<H256>,
+        ) -> Result<Vec<TransactionTraceWrapper>, DispatchError> {
+            // The scratch state is never applied to the storage.
+            let state = &mut Self::create_state_reader()?;
+            let mut traces = Vec::new();
+
+            for transaction in transactions {
+                let contract_class = Self::declared_contract_class(&transaction)?;
+
+                let call_infos = Self::execute_transaction(state, &transaction, contract_class, true, true)?;
+                traces.push(Self::transaction_trace(&transaction, call_infos)?);
+
+                if Some(transaction.hash()) == target {
+                    break;
+                }
+            }
+
+            Ok(traces)
+        }
+
+        /// Simulate transactions on top of the current state, without applying their effects.
+        ///
+        /// The transactions are executed sequentially on a single scratch state, so that each one
+        /// sees the effects of the previous ones. They are executed as if they were dispatched: a
+        /// failing invoke transaction is reported as reverted, and its fee is still estimated.
+        ///
+        /// # Arguments
+        ///
+        /// * `pending_transactions` - The transactions of the pending block, executed before the
+        ///   simulated ones but not reported. Empty to simulate on top of the current state.
+        /// * `transactions` - The transactions to simulate, in execution order.
+        /// * `simulation_flags` - Whether to skip the account validation and the fee transfer.
+        ///
+        /// # Returns
+        ///
+        /// The execution traces and fee estimations of the transactions.
+        pub fn simulate_transactions(
+            pending_transactions: Vec<Transaction>,
+            transactions: Vec<Transaction>,
+            simulation_flags: SimulationFlags,
+        ) -> Result<Vec<SimulatedTransactionWrapper>, DispatchError> {
+            // The scratch state is never applied to the storage.
+            let state = &mut Self::pending_state(pending_transactions)?;
+            let mut simulations = Vec::new();
+
+            for transaction in transactions {
+                let contract_class = Self::declared_contract_class(&transaction)?;
+
+                let call_infos = Self::execute_transaction(
+                    state,
+                    &transaction,
+                    contract_class,
+                    !simulation_flags.skip_validate,
+                    !simulation_flags.skip_fee_charge,
+                )?;
+
+                let fee_estimation = FeeEstimateWrapper {
+                    gas_consumed: U256::from(call_infos.gas_consumed),
+                    gas_price: U256::from(T::L1GasPrice::get()),
+                    overall_fee: U256::from(call_infos.actual_fee),
+                };
+                simulations.push(SimulatedTransactionWrapper {
+                    transaction_trace: Self::transaction_trace(&transaction, call_infos)?,
+                    fee_estimation,
+                });
+            }
+
+            Ok(simulations)
+        }
+
+        /// Build the state of the pending block, without applying it to the storage.
+        ///
+        /// The pending transactions are executed on top of the current state as if they were
+        /// dispatched in the next block. They already passed the account validation when they
+        /// entered the pool, so it is skipped. A transaction that fails is dropped along with its
+        /// changes, as it would be from the block.
+        ///
+        /// # Arguments
+        ///
+        /// * `pending_transactions` - The transactions of the pending block, in execution order.
+        ///
+        /// # Returns
+        ///
+        /// A scratch state holding the changes of the pending block.
+        fn pending_state(
+            pending_transactions: Vec<Transaction>,
+        ) -> Result<CachedState<DictStateReader>, DispatchError> {
+            let mut state = Self::create_state_reader()?;
+
+            for transaction in pending_transactions {
+                let contract_class = match Self::declared_contract_class(&transaction) {
+                    Ok(contract_class) => contract_class,
+                    Err(e) => {
+                        log!(debug, "Pending transaction {:?} dropped: {:?}", transaction.hash(), e);
+                        continue;
+                    }
+                };
+
+                let mut transaction_state = CachedState::new(ParentState(&mut state));
+                if let Err(e) =
+                    Self::execute_transaction(&mut transaction_state, &transaction, contract_class.clone(), false, true)
+                {
+                    log!(debug, "Pending transaction {:?} dropped: {:?}", transaction.hash(), e);
+                    continue;
+                }
+                let state_diff = transaction_state.to_state_diff();
+                commit_state_diff(&mut state, state_diff).map_err(|_| Error::<T>::StateDiffError)?;
+
+                // Nonces are incremented by the dispatched calls, outside of the execution.
+                if transaction.uses_account_nonce() {
+                    let sender_address = StarkFelt::new(transaction.sender_address())
+                        .ok()
+                        .and_then(|address| ContractAddress::try_from(address).ok())
+                        .ok_or(Error::<T>::StateDiffError)?;
+                    state.increment_nonce(sender_address).map_err(|_| Error::<T>::StateDiffError)?;
+                }
+
+                // Declared classes are not part of the committed changes.
+                if let (Transaction::Declare(tx), Some(contract_class)) = (&transaction, contract_class) {
+                    let class_hash =
+                        ClassHash(StarkFelt::new(tx.class_hash).map_err(|_| Error::<T>::InvalidContractClass)?);
+                    state.set_contract_class(&class_hash, contract_class).map_err(|_| Error::<T>::StateDiffError)?;
+                }
+            }
+
+            Ok(state)
+        }
+
+        /// Parse the contract class declared by a transaction, if it is a declare transaction.
+        fn declared_contract_class(transaction: &Transaction) -> Result<Option<ContractClass>, Error<T>> {
+            match transaction {
+                Transaction::Declare(tx) => {
+                    tx.contract_class.to_starknet_contract_class().map(Some).or(Err(Error::<T>::InvalidContractClass))
+                }
+                _ => Ok(None),
+            }
+        }
+
+        /// Build the execution trace of a transaction from the calls made by its execution.
+        fn transaction_trace(
+            transaction: &Transaction,
+            call_infos: TransactionCallInfos,
+        ) -> Result<TransactionTraceWrapper, Error<T>> {
+            let to_invocation = |call_info: Option<CallInfo>| {
+                call_info
+                    .as_ref()
+                    .map(FunctionInvocationWrapper::try_from)
+                    .transpose()
+                    .map_err(|_| Error::<T>::TraceConversionError)
+            };
+
+            Ok(TransactionTraceWrapper {
+                transaction_hash: transaction.hash(),
+                tx_type: transaction.tx_type(),
+                signature: transaction.signature().to_vec(),
+                validate_invocation: to_invocation(call_infos.validate_call_info)?,
+                function_invocation: to_invocation(call_infos.execute_call_info)?,
+                fee_transfer_invocation: to_invocation(call_infos.fee_transfer_call_info)?,
+                revert_reason: call_infos.revert_reason.map(String::into_bytes),
+            })
+        }
+
+        /// Append a transaction and its receipt to the block being built.
+        fn append_transaction(transaction: Transaction, receipt: TransactionReceiptWrapper) -> Result<(), Error<T>> {
+            BlockReceipts::<T>::try_append(receipt.clone()).map_err(|_| Error::<T>::TooManyPendingTransactions)?;
+            Pending::<T>::try_append((transaction, receipt)).map_err(|_| Error::<T>::TooManyPendingTransactions)
+        }
+
         /// Store a Starknet block in the blockchain.
         ///
         /// # Arguments
@@ -750,7 +1252,11 @@ pub mod pallet {
             let parent_block_hash = Self::parent_block_hash(&block_number);
             let pending = Self::pending();
 
-            let global_state_root = U256::zero();
+            // TODO: compute the contracts tree root
+            let contracts_root = H256::zero();
+            let global_state_root = U256::from_big_endian(
+                commitment::calculate_state_root(contracts_root, Self::class_commitment()).as_bytes(),
+            );
             // TODO: use the real sequencer address (our own address)
             // FIXME #243
             let sequencer_address = SEQUENCER_ADDRESS;
@@ -831,7 +1337,32 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Emit events from the call info.
+        /// Emit the events of a transaction.
+        ///
+        /// The events emitted by each phase of the execution are emitted in execution order, the
+        /// validation first, then the execution and the fee transfer.
+        ///
+        /// # Arguments
+        ///
+        /// * `call_infos` - The calls made by each phase of the execution of the transaction.
+        ///
+        /// # Returns
+        ///
+        /// The events emitted by the transaction.
+        fn emit_events(call_infos: &TransactionCallInfos) -> Result<Vec<StarknetEventType>, EventError> {
+            let mut events = Vec::new();
+            for call_info in
+                [&call_infos.validate_call_info, &call_infos.execute_call_info, &call_infos.fee_transfer_call_info]
+                    .into_iter()
+                    .flatten()
+            {
+                events.extend(Self::emit_call_events(call_info)?);
+            }
+
+            Ok(events)
+        }
+
+        /// Emit the events of a call and all of its inner calls, in the order they were emitted.
         ///
         /// # Arguments
         ///
@@ -839,26 +1370,59 @@ pub mod pallet {
         ///
         /// # Returns
         ///
-        /// The result of the operation.
-        #[inline(always)]
-        fn emit_events(call_info: &mut CallInfo) -> Result<Vec<StarknetEventType>, EventError> {
+        /// The events emitted by the call.
+        fn emit_call_events(call_info: &CallInfo) -> Result<Vec<StarknetEventType>, EventError> {
+            fn collect<'a>(call_info: &'a CallInfo, events: &mut Vec<(usize, ContractAddress, &'a EventContent)>) {
+                events.extend(
+                    call_info.execution.events.iter().map(|ordered_event| {
+                        (ordered_event.order, call_info.call.storage_address, &ordered_event.event)
+                    }),
+                );
+                call_info.inner_calls.iter().for_each(|inner_call| collect(inner_call, events));
+            }
+
+            // Orders are shared by all the calls of an execution.
             let mut events = Vec::new();
+            collect(call_info, &mut events);
+            events.sort_by_key(|(order, _, _)| *order);
 
-            call_info.execution.events.sort_by_key(|ordered_event| ordered_event.order);
-            for ordered_event in &call_info.execution.events {
-                let event_type = Self::emit_event(&ordered_event.event, call_info.call.storage_address)?;
-                events.push(event_type);
+            events.into_iter().map(|(_, from_address, event)| Self::emit_event(event, from_address)).collect()
+        }
+
+        /// Send the messages to L1 of a transaction.
+        ///
+        /// The messages sent by each phase of the execution are collected in execution order, and
+        /// deposited along with their hash, so that the messages sent in a block can be retrieved.
+        ///
+        /// # Arguments
+        ///
+        /// * `call_infos` - The calls made by each phase of the execution of the transaction.
+        ///
+        /// # Returns
+        ///
+        /// The messages sent to L1 by the transaction.
+        fn send_messages(
+            call_infos: &TransactionCallInfos,
+        ) -> Result<BoundedVec<MessageToL1Wrapper, MaxArraySize>, MessageError> {
+            let mut messages = Vec::new();
+            for call_info in
+                [&call_infos.validate_call_info, &call_infos.execute_call_info, &call_infos.fee_transfer_call_info]
+                    .into_iter()
+                    .flatten()
+            {
+                messages.extend(messages_sent(call_info)?);
             }
 
-            for inner_call in &mut call_info.inner_calls {
-                inner_call.execution.events.sort_by_key(|ordered_event| ordered_event.order);
-                for ordered_event in &inner_call.execution.events {
-                    let event_type = Self::emit_event(&ordered_event.event, inner_call.call.storage_address)?;
-                    events.push(event_type);
-                }
+            let extrinsic_index = frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default();
+            for message in &messages {
+                log!(debug, "Transaction message to L1: {:?}", message);
+                let message_hash = message.hash();
+                BlockMessagesToL1::<T>::try_append((extrinsic_index, message_hash, message.clone()))
+                    .map_err(|_| MessageError::TooManyMessages)?;
+                Self::deposit_event(Event::MessageSentToL1 { message_hash, message: message.clone() });
             }
 
-            Ok(events)
+            BoundedVec::try_from(messages).map_err(|_| MessageError::TooManyMessages)
         }
 
         /// Emit an event from the call info in substrate.
@@ -881,6 +1445,8 @@ pub mod pallet {
             Self::deposit_event(Event::StarknetEvent(sn_event.clone()));
 
             PendingEvents::<T>::try_append(sn_event.clone()).map_err(|_| EventError::TooManyEvents)?;
+            let extrinsic_index = frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default();
+            BlockEvents::<T>::try_append((extrinsic_index, sn_event.clone())).map_err(|_| EventError::TooManyEvents)?;
             Ok(sn_event)
         }
 
@@ -895,8 +1461,7 @@ pub mod pallet {
         /// Returns an error if it fails to apply the state diff of newly deployed contracts.
         pub fn apply_state_diffs(state: &CachedState<DictStateReader>) -> Result<(), StateDiffError> {
             // Get all the state diffs
-            let StateDiff { deployed_contracts, storage_diffs, declared_classes: _declared_classes, nonces, .. } =
-                state.to_state_diff();
+            let StateDiff { deployed_contracts, storage_diffs, declared_classes, nonces, .. } = state.to_state_diff();
             // Store the newly deployed contracts in substrate storage.
             deployed_contracts.iter().try_for_each(|(address, class_hash)| {
                 Self::set_class_hash_at(address.0.0.0, class_hash.0.0).map_err(|_| {
@@ -910,13 +1475,46 @@ pub mod pallet {
                 })
             })?;
             // Store the modifications of storage vars.
-            storage_diffs.iter().for_each(|(address, diffs)| {
-                diffs.iter().for_each(|(key, value)| {
-                    StorageView::<T>::insert((address.0.0.0, H256::from_slice(&key.0.0.0)), U256::from(value.0))
+            storage_diffs.iter().try_for_each(|(address, diffs)| {
+                diffs.iter().try_for_each(|(key, value)| {
+                    let (address, key, value) = (address.0.0.0, H256::from_slice(&key.0.0.0), U256::from(value.0));
+                    StorageView::<T>::insert((address, key), value);
+                    BlockStorageDiffs::<T>::try_append((address, key, value))
+                        .map_err(|_| StateDiffError::StorageDiffError)
                 })
-            });
+            })?;
             // Store the new nonces.
             nonces.iter().for_each(|(address, nonce)| Nonces::<T>::insert(address.0.0.0, U256::from(nonce.0.0)));
+            // Store the newly declared classes, and commit to their compiled class hashes in the state
+            // root.
+            declared_classes.iter().try_for_each(|(class_hash, (compiled_class_hash, sierra_class))| {
+                let class_hash = class_hash.0.0;
+                let compiled_class_hash = compiled_class_hash.0.0;
+                if CompiledClassHashes::<T>::contains_key(class_hash) {
+                    log!(error, "Class already declared with class hash: {:?}", class_hash);
+                    return Err(StateDiffError::DeclaredClassError);
+                }
+                let class_commitment = commitment::update_class_commitment(
+                    &mut ClassTrieStorage::<T>::default(),
+                    Self::class_commitment(),
+                    H256(class_hash),
+                    H256(compiled_class_hash),
+                )
+                .map_err(|err| {
+                    log!(error, "Failed to update the class commitment: {:?}", err);
+                    StateDiffError::DeclaredClassError
+                })?;
+                BlockDeclaredClasses::<T>::try_append((class_hash, Some(compiled_class_hash)))
+                    .map_err(|_| StateDiffError::DeclaredClassError)?;
+                ClassCommitment::<T>::put(class_commitment);
+                CompiledClassHashes::<T>::insert(class_hash, compiled_class_hash);
+                SierraClasses::<T>::insert(class_hash, SierraClassWrapper::from(sierra_class.clone()));
+                Self::deposit_event(Event::ClassDeclared {
+                    class_hash,
+                    compiled_class_hash: Some(compiled_class_hash),
+                });
+                Ok(())
+            })?;
             Ok(())
         }
 
@@ -977,6 +1575,323 @@ pub mod pallet {
             }))
         }
 
+        /// Get the Starknet transaction wrapped in an unsigned extrinsic.
+        ///
+        /// # Arguments
+        ///
+        /// * `source` - The source of the extrinsic.
+        /// * `call` - The call of the extrinsic.
+        ///
+        /// # Error
+        ///
+        /// Returns an error if the call is not a Starknet transaction, or if it is an L1 message
+        /// not submitted by the node itself.
+        fn unsigned_transaction(
+            source: TransactionSource,
+            call: &Call<T>,
+        ) -> Result<Transaction, TransactionValidityError> {
+            match call {
+                Call::invoke { transaction } => Ok(transaction.clone().into()),
+                Call::declare { transaction } => Ok(transaction.clone().into()),
+                Call::deploy_account { transaction } => Ok(transaction.clone().into()),
+                Call::consume_l1_message { transaction }
+                    if matches!(source, TransactionSource::Local | TransactionSource::InBlock) =>
+                {
+                    Ok(transaction.clone().into())
+                }
+                _ => Err(InvalidTransaction::Call.into()),
+            }
+        }
+
+        /// Check a Starknet transaction submitted as an unsigned extrinsic.
+        ///
+        /// The version of the transaction and the deployment of its sender are checked, then the
+        /// nonce and the validation entrypoint of account transactions. Nonces lower than the one
+        /// of the sender are stale, higher ones are accepted, and so are version 0 invoke
+        /// transactions already included. Transactions whose fee exceeds their max fee are
+        /// rejected.
+        ///
+        /// # Arguments
+        ///
+        /// * `transaction` - The transaction to check.
+        ///
+        /// # Error
+        ///
+        /// Returns an error if the transaction can't be included in the next block.
+        fn validate_unsigned_transaction(transaction: &Transaction) -> Result<(), TransactionValidityError> {
+            transaction.verify_tx_version().map_err(|_| InvalidTransaction::Call)?;
+
+            let tx_type = transaction.tx_type();
+            let sender_address = transaction.sender_address();
+            let sender_deployed = ContractClassHashes::<T>::contains_key(sender_address);
+            if tx_type == TxType::DeployAccountTx {
+                ensure!(!sender_deployed, InvalidTransaction::BadSigner);
+            } else {
+                ensure!(sender_deployed, InvalidTransaction::BadSigner);
+            }
+
+            // The nonce of L1 handler transactions is the nonce of the L1 message, and version 0
+            // invoke transactions have none, so they must not have been included already.
+            if transaction.uses_account_nonce() {
+                ensure!(transaction.nonce() >= Self::nonce(sender_address), InvalidTransaction::Stale);
+            } else if let Some(legacy_hash) = Self::legacy_invoke_hash(transaction)? {
+                ensure!(!LegacyInvokeHashes::<T>::contains_key(legacy_hash), InvalidTransaction::Stale);
+            }
+
+            let block = Self::current_block();
+            let fee_token_address = Self::fee_token_address();
+            let state = &mut Self::create_state_reader().map_err(|_| UnknownTransaction::CannotLookup)?;
+
+            // Deploy account transactions can only be validated once the account is deployed.
+            if tx_type == TxType::DeployAccountTx {
+                transaction
+                    .execute(state, block.clone(), None, fee_token_address, &mut ExecutionResources::default())
+                    .map_err(|e| {
+                        log!(error, "Transaction execution failed during validation: {:?}", e);
+                        InvalidTransaction::BadProof
+                    })?;
+            }
+
+            transaction.validate(state, block, fee_token_address, &mut ExecutionResources::default()).map_err(|e| {
+                log!(error, "Transaction validation failed: {:?}", e);
+                InvalidTransaction::BadProof
+            })?;
+
+            // The fee of the transaction must be covered by its max fee. There is no fee to cover
+            // without a gas price, and L1 handler transactions are paid on L1.
+            if T::L1GasPrice::get() != 0 && tx_type != TxType::L1HandlerTx {
+                let contract_class =
+                    Self::declared_contract_class(transaction).map_err(|_| InvalidTransaction::Call)?;
+                let state = &mut Self::create_state_reader().map_err(|_| UnknownTransaction::CannotLookup)?;
+                let estimate =
+                    Self::execute_transaction(state, transaction, contract_class, false, false).map_err(|e| {
+                        log!(error, "Transaction fee estimation failed during validation: {:?}", e);
+                        InvalidTransaction::Call
+                    })?;
+                ensure!(U256::from(estimate.actual_fee) <= transaction.max_fee(), Payment);
+            }
+
+            Ok(())
+        }
+
+        /// Returns the hash of a version 0 invoke transaction, or `None` for other transactions.
+        ///
+        /// The hash is computed rather than taken from the transaction, as it is what prevents the
+        /// transaction from being replayed.
+        fn legacy_invoke_hash(transaction: &Transaction) -> Result<Option<H256>, TransactionValidityError> {
+            if transaction.tx_type() != TxType::InvokeTx || transaction.uses_account_nonce() {
+                return Ok(None);
+            }
+            let hash = transaction.compute_hash(mp_starknet::block::CHAIN_ID).map_err(|_| InvalidTransaction::Call)?;
+            Ok(Some(hash))
+        }
+
+        /// Increment the nonce of a contract, once one of its transactions has been executed.
+        ///
+        /// # Arguments
+        ///
+        /// * `contract_address` - The address of the contract.
+        fn increment_nonce(contract_address: ContractAddressWrapper) {
+            Nonces::<T>::mutate(contract_address, |nonce| *nonce += U256::one());
+        }
+
+        /// Execute a transaction on the given state, phase by phase, without applying it to the
+        /// storage.
+        ///
+        /// The account validates the transaction, which is then executed, and its fee is
+        /// transferred to the sequencer. Dispatched transactions skip the validation phase, as it
+        /// already ran in `pre_dispatch`: a transaction rejected by its account is never included.
+        ///
+        /// An invoke transaction whose execution fails is reverted rather than rejected: the state
+        /// changes of its execution are rolled back, but its fee is still charged, up to the
+        /// balance of its sender.
+        ///
+        /// The charged fee is capped at the max fee of the transaction. The fee returned when it is
+        /// not charged is the fee of the execution, so that it can be compared to the max fee.
+        ///
+        /// # Arguments
+        ///
+        /// * `state` - The state to execute the transaction on.
+        /// * `transaction` - The transaction to execute.
+        /// * `contract_class` - The contract class declared by the transaction, if any.
+        /// * `validate` - Whether to run the validation phase.
+        /// * `charge_fee` - Whether to transfer the fee to the sequencer. It is computed either
+        ///   way.
+        ///
+        /// # Returns
+        ///
+        /// The calls made by each phase of the execution, the fee charged, and the revert reason of
+        /// a reverted transaction.
+        fn execute_transaction<S: StateReader>(
+            state: &mut CachedState<S>,
+            transaction: &Transaction,
+            contract_class: Option<ContractClass>,
+            validate: bool,
+            charge_fee: bool,
+        ) -> Result<TransactionCallInfos, Error<T>> {
+            let tx_type = transaction.tx_type();
+            let block = Self::current_block();
+            let fee_token_address = Self::fee_token_address();
+            let sequencer_address = block.header().sequencer_address;
+            let validate_resources = &mut ExecutionResources::default();
+            let execute_resources = &mut ExecutionResources::default();
+
+            let mut run_validation = |state: &mut CachedState<S>| {
+                if !validate {
+                    return Ok(None);
+                }
+                transaction.validate(state, block.clone(), fee_token_address, validate_resources).map_err(|e| {
+                    log!(error, "Transaction validation failed: {:?}", e);
+                    Error::<T>::TransactionValidationFailed
+                })
+            };
+
+            // Deploy account transactions can only be validated once the account is deployed.
+            let mut validate_call_info = None;
+            if tx_type != TxType::DeployAccountTx {
+                validate_call_info = run_validation(state)?;
+            }
+
+            // Transactions with an unsupported version are rejected, as their account cannot
+            // handle them.
+            transaction.verify_tx_version().map_err(|e| {
+                log!(error, "Transaction execution failed: {:?}", e);
+                Error::<T>::TransactionExecutionFailed
+            })?;
+
+            let mut revert_reason = None;
+            let execute_call_info = if tx_type == TxType::InvokeTx {
+                // Execute on top of the state, so that the changes of a reverted execution can be
+                // dropped. The resources used until the execution failed are still charged.
+                let mut execution_state = CachedState::new(ParentState(state));
+                let execution = transaction.execute(
+                    &mut execution_state,
+                    block.clone(),
+                    None,
+                    fee_token_address,
+                    execute_resources,
+                );
+                let state_diff = execution_state.to_state_diff();
+                match execution {
+                    Ok(call_info) => {
+                        commit_state_diff(state, state_diff).map_err(|_| Error::<T>::StateDiffError)?;
+                        call_info
+                    }
+                    Err(TransactionExecutionErrorWrapper::TransactionExecution(e)) => {
+                        log!(debug, "Transaction execution reverted: {:?}", e);
+                        revert_reason = Some(format!("{:?}", e));
+                        None
+                    }
+                    Err(e) => {
+                        log!(error, "Transaction execution failed: {:?}", e);
+                        return Err(Error::<T>::TransactionExecutionFailed);
+                    }
+                }
+            } else {
+                transaction
+                    .execute(state, block.clone(), contract_class, fee_token_address, execute_resources)
+                    .map_err(|e| {
+                        log!(error, "Transaction execution failed: {:?}", e);
+                        Error::<T>::TransactionExecutionFailed
+                    })?
+            };
+
+            if tx_type == TxType::DeployAccountTx {
+                validate_call_info = run_validation(state)?;
+            }
+
+            // L1 handler transactions are paid on L1, and version 0 invoke transactions are sent by
+            // the called contract rather than by an account able to pay for them.
+            let resources = [&*validate_resources, &*execute_resources];
+            let (gas_consumed, mut actual_fee) = match tx_type {
+                TxType::L1HandlerTx => (0, 0),
+                TxType::InvokeTx if !transaction.uses_account_nonce() => (fees::calculate_l1_gas_usage(&resources), 0),
+                _ => {
+                    (fees::calculate_l1_gas_usage(&resources), fees::calculate_tx_fee(&resources, T::L1GasPrice::get()))
+                }
+            };
+
+            // There is nothing to transfer without a fee.
+            let fee_transfer_call_info = if !charge_fee || actual_fee == 0 {
+                None
+            } else {
+                // The sender never pays more than the max fee of its transaction.
+                let max_fee = u128::try_from(transaction.max_fee()).unwrap_or(u128::MAX);
+                actual_fee = actual_fee.min(max_fee);
+                let sender_address = transaction.sender_address();
+                match Self::pay_fee(state, sender_address, sequencer_address, actual_fee) {
+                    Ok(call_info) => Some(call_info),
+                    // A reverted transaction is still included, charged with what its sender can pay.
+                    Err(_) if revert_reason.is_some() => {
+                        actual_fee = actual_fee.min(Self::fee_token_balance(state, sender_address)?);
+                        let call_info = if actual_fee == 0 {
+                            None
+                        } else {
+                            Self::pay_fee(state, sender_address, sequencer_address, actual_fee).ok()
+                        };
+                        if call_info.is_none() {
+                            actual_fee = 0;
+                        }
+                        call_info
+                    }
+                    Err(_) => return Err(Error::<T>::FeeTransferFailed),
+                }
+            };
+
+            Ok(TransactionCallInfos {
+                validate_call_info,
+                execute_call_info,
+                fee_transfer_call_info,
+                gas_consumed,
+                actual_fee,
+                revert_reason,
+            })
+        }
+
+        /// Transfers a fee from an account to the sequencer, on top of the given state.
+        ///
+        /// The state is left untouched if the transfer fails.
+        fn pay_fee<S: StateReader>(
+            state: &mut CachedState<S>,
+            from: ContractAddressWrapper,
+            to: ContractAddressWrapper,
+            amount: u128,
+        ) -> Result<CallInfo, Error<T>> {
+            let mut transfer_state = CachedState::new(ParentState(state));
+            let call_info = Self::execute_fee_transfer(&mut transfer_state, from, to, amount)
+                .map_err(|_| Error::<T>::FeeTransferFailed)?;
+            let state_diff = transfer_state.to_state_diff();
+            commit_state_diff(state, state_diff).map_err(|_| Error::<T>::StateDiffError)?;
+            Ok(call_info)
+        }
+
+        /// Returns the balance of an account in the fee token contract, saturated to `u128`.
+        fn fee_token_balance<S: StateReader>(
+            state: &mut CachedState<S>,
+            account: ContractAddressWrapper,
+        ) -> Result<u128, Error<T>> {
+            let fee_token_address = StarkFelt::new(Self::fee_token_address())
+                .ok()
+                .and_then(|address| ContractAddress::try_from(address).ok())
+                .ok_or(Error::<T>::InvalidStorageKey)?;
+            let (low_key, high_key) =
+                Self::fee_token_balance_keys(account).map_err(|_| Error::<T>::InvalidStorageKey)?;
+            let mut read_balance = |key: H256| {
+                let key = StarkFelt::new(key.0)
+                    .ok()
+                    .and_then(|key| StorageKey::try_from(key).ok())
+                    .ok_or(Error::<T>::InvalidStorageKey)?;
+                state.get_storage_at(fee_token_address, key).map_err(|_| Error::<T>::FeeTransferFailed)
+            };
+
+            if read_balance(high_key)? != StarkFelt::default() {
+                return Ok(u128::MAX);
+            }
+            let low = read_balance(low_key)?;
+            let low: [u8; 16] = low.bytes()[16..].try_into().map_err(|_| Error::<T>::FeeTransferFailed)?;
+            Ok(u128::from_be_bytes(low))
+        }
+
         /// Returns Ethereum RPC URL from Storage
         fn get_eth_rpc_url() -> Result<String, OffchainWorkerError> {
             let eth_execution_rpc_url = StorageValueRef::persistent(ETHEREUM_EXECUTION_RPC)
@@ -1066,6 +1981,56 @@ pub mod pallet {
                 log!(error, "Couldn't create the cached state");
                 TransactionValidityError::Unknown(Custom(3_u8))
             })?;
+            let call_info = Self::execute_fee_transfer(state, from, to, amount)?;
+            Self::emit_call_events(&call_info).map_err(|_| TransactionValidityError::Unknown(Custom(4_u8)))?;
+            Pallet::<T>::apply_state_diffs(state).map_err(|_| {
+                log!(error, "Couldn't apply the state diffs");
+                TransactionValidityError::Unknown(Custom(3_u8))
+            })?;
+            Ok(())
+        }
+
+        /// Returns the storage keys of the low and high parts of an account balance in the fee
+        /// token contract.
+        ///
+        /// # Arguments
+        ///
+        /// * `account` - The address of the account.
+        ///
+        /// # Error
+        ///
+        /// Returns an error if the account is not a valid field element.
+        pub fn fee_token_balance_keys(account: ContractAddressWrapper) -> Result<(H256, H256), DispatchError> {
+            let account = StarkFelt::new(account).map_err(|_| Error::<T>::InvalidStorageKey)?;
+            let low_key = abi_utils::get_storage_var_address(FEE_TOKEN_BALANCES_STORAGE_VAR, &[account])
+                .map_err(|_| Error::<T>::InvalidStorageKey)?;
+            let low_key = U256::from_big_endian(low_key.0.key().bytes());
+
+            let mut low_key_bytes = [0_u8; 32];
+            let mut high_key_bytes = [0_u8; 32];
+            low_key.to_big_endian(&mut low_key_bytes);
+            (low_key + 1).to_big_endian(&mut high_key_bytes);
+            Ok((H256(low_key_bytes), H256(high_key_bytes)))
+        }
+
+        /// Execute a fee token transfer on the given state, without applying it to the storage.
+        ///
+        /// # Arguments
+        ///
+        /// * `state` - the state to execute the transfer on
+        /// * `from` - the sender of the tokens
+        /// * `to` - recipient of the tokens
+        /// * `amount` - amount of the tokens
+        ///
+        /// # Error
+        ///
+        /// Returns an error if a step of the transfer fails
+        fn execute_fee_transfer<S: StateReader>(
+            state: &mut CachedState<S>,
+            from: [u8; 32],
+            to: [u8; 32],
+            amount: <StarknetFee as OnChargeTransaction<T>>::Balance,
+        ) -> Result<CallInfo, TransactionValidityError> {
             // Get current block.
             let block = Pallet::<T>::current_block();
             let fee_token_address =
@@ -1114,7 +2079,7 @@ pub mod pallet {
             let account_ctx = AccountTransactionContext::default(); // TODO: check if it needs a real value.
             // FIXME #256
             let block_ctx = BlockContext {
-                chain_id: ChainId("SN_GOERLI".to_string()), // TODO: Make it configurable ?
+                chain_id: ChainId(mp_starknet::block::CHAIN_ID.to_string()),
                 block_number: BlockNumber(block.header().block_number.as_u64()),
                 block_timestamp: BlockTimestamp(block.header().block_timestamp),
                 sequencer_address: ContractAddress::try_from(
@@ -1140,20 +2105,15 @@ pub mod pallet {
                 &block_ctx,
                 &account_ctx,
             ) {
-                Ok(mut v) => {
+                Ok(v) => {
                     log!(trace, "Fees executed successfully: {:?}", v.execution.events);
-                    Self::emit_events(&mut v).map_err(|_| TransactionValidityError::Unknown(Custom(4_u8)))?;
+                    Ok(v)
                 }
                 Err(e) => {
                     log!(error, "Fees execution failed: {:?}", e);
-                    return Err(TransactionValidityError::Invalid(Payment));
+                    Err(TransactionValidityError::Invalid(Payment))
                 }
             }
-            Pallet::<T>::apply_state_diffs(state).map_err(|_| {
-                log!(error, "Couldn't apply the state diffs");
-                TransactionValidityError::Unknown(Custom(3_u8))
-            })?;
-            Ok(())
         }
     }
     pub struct StarknetFee;
@@ -1,7 +1,6 @@
 use frame_support::BoundedVec;
 use hex::FromHex;
-use mp_starknet::execution::{CallEntryPointWrapper, ContractAddressWrapper, EntryPointTypeWrapper};
-use mp_starknet::transaction::types::Transaction;
+use mp_starknet::transaction::types::L1HandlerTransaction;
 use sp_core::{H256, U256};
 
 use crate::pallet::alloc::format;
@@ -36,7 +35,7 @@ pub fn get_messages_events(from_block: u64, to_block: u64) -> String {
 
 impl Message {
     /// Converts a `Message` into a transaction object.
-    pub fn try_into_transaction(&self) -> Result<Transaction, OffchainWorkerError> {
+    pub fn try_into_transaction(&self) -> Result<L1HandlerTransaction, OffchainWorkerError> {
         // Data at least contains a nonce and at some point the fees.
         if self.data.is_empty() {
             return Err(OffchainWorkerError::EmptyData);
@@ -66,23 +65,20 @@ impl Message {
             ))
         }
         let calldata = BoundedVec::try_from(calldata).map_err(|_| OffchainWorkerError::ToTransactionError)?;
-        let call_entrypoint = CallEntryPointWrapper {
-            class_hash: None,
-            entrypoint_type: EntryPointTypeWrapper::L1Handler,
-            entrypoint_selector: Some(selector),
+        Ok(L1HandlerTransaction {
+            nonce,
+            contract_address: sender_address,
+            entry_point_selector: selector,
             calldata,
-            storage_address: sender_address,
-            caller_address: ContractAddressWrapper::default(),
-        };
-        Ok(Transaction { sender_address, nonce, call_entrypoint, ..Transaction::default() })
+            ..L1HandlerTransaction::default()
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
     use frame_support::bounded_vec;
-    use mp_starknet::execution::{CallEntryPointWrapper, ContractAddressWrapper, EntryPointTypeWrapper};
-    use mp_starknet::transaction::types::Transaction;
+    use mp_starknet::transaction::types::L1HandlerTransaction;
     use pretty_assertions;
     use sp_core::{H256, U256};
 
@@ -94,18 +90,12 @@ mod test {
         let hex = "0x0000000000000000000000000000000000000000000000000000000000000001".to_owned();
         let test_message: Message =
             Message { topics: vec![hex.clone(), hex.clone(), hex.clone(), hex.clone()], data: hex };
-        let expected_tx = Transaction {
-            sender_address,
+        let expected_tx = L1HandlerTransaction {
             nonce: U256::from(1),
-            call_entrypoint: CallEntryPointWrapper {
-                class_hash: None,
-                entrypoint_type: EntryPointTypeWrapper::L1Handler,
-                entrypoint_selector: Some(H256::from_low_u64_be(1)),
-                calldata: bounded_vec![U256::from(1), U256::from(1)],
-                storage_address: H256::from_low_u64_be(1).to_fixed_bytes(),
-                caller_address: ContractAddressWrapper::default(),
-            },
-            ..Transaction::default()
+            contract_address: sender_address,
+            entry_point_selector: H256::from_low_u64_be(1),
+            calldata: bounded_vec![U256::from(1), U256::from(1)],
+            ..L1HandlerTransaction::default()
         };
         pretty_assertions::assert_eq!(test_message.try_into_transaction().unwrap(), expected_tx);
     }
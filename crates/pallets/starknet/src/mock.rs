@@ -81,9 +81,14 @@ impl pallet_starknet::Config for Test {
     type StateRoot = pallet_starknet::state_root::IntermediateStateRoot<Self>;
     type SystemHash = mp_starknet::crypto::hash::pedersen::PedersenHasher;
     type TimestampProvider = Timestamp;
+    type TransactionLongevity = TransactionLongevity;
+    type L1GasPrice = L1GasPrice;
 }
 parameter_types! {
     pub FeeMultiplier: Multiplier = Multiplier::one();
+    pub const TransactionLongevity: u64 = 64;
+    /// Free transactions unless a test sets a price.
+    pub static L1GasPrice: u128 = 0;
 }
 // Provides the logic needed to handle transaction fees
 impl pallet_transaction_payment::Config for Test {
@@ -181,6 +186,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
                 U256::from(u128::MAX),
             ),
         ],
+        dev_calls_enabled: true,
         ..Default::default()
     }
     .assimilate_storage(&mut t)
@@ -198,6 +204,7 @@ pub(crate) fn run_to_block(n: u64) {
     let deployer_origin = RuntimeOrigin::signed(deployer_account);
     for b in System::block_number()..=n {
         System::set_block_number(b);
+        Starknet::on_initialize(b);
         Timestamp::set_timestamp(System::block_number() * 6_000);
         Starknet::ping(deployer_origin.clone()).unwrap();
         Starknet::on_finalize(b);
@@ -1,8 +1,13 @@
-use mp_starknet::execution::ContractAddressWrapper;
+use mp_starknet::execution::{ClassHashWrapper, ContractAddressWrapper, ContractClassWrapper};
+use mp_starknet::transaction::types::{
+    EventWrapper, MessageToL1Wrapper, SimulatedTransactionWrapper, SimulationFlags, Transaction,
+    TransactionReceiptWrapper, TransactionTraceWrapper,
+};
 use sp_core::{H256, U256};
 pub extern crate alloc;
 use alloc::vec::Vec;
 
+use sp_runtime::traits::Block as BlockT;
 use sp_runtime::DispatchError;
 
 use crate::types::StarkFeltWrapper;
@@ -13,7 +18,27 @@ sp_api::decl_runtime_apis! {
         fn current_block_hash() -> H256;
         /// Returns the current block.
         fn current_block() -> mp_starknet::block::Block;
-        /// Returns a `Call` response.
-        fn call(address: ContractAddressWrapper, function_selector: H256, calldata: Vec<U256>) -> Result<Vec<StarkFeltWrapper>, DispatchError>;
+        /// Returns a `Call` response, on top of the given pending transactions.
+        fn call(pending_transactions: Vec<Transaction>, address: ContractAddressWrapper, function_selector: H256, calldata: Vec<U256>) -> Result<Vec<StarkFeltWrapper>, DispatchError>;
+        /// Returns the Starknet events emitted in the current block, along with the index of the extrinsic which emitted them.
+        fn events() -> Vec<(u32, EventWrapper)>;
+        /// Returns the messages sent to L1 in the current block, along with their hash and the index of the extrinsic which sent them.
+        fn messages_to_l1() -> Vec<(u32, H256, MessageToL1Wrapper)>;
+        /// Returns the receipts of the Starknet transactions of the current block.
+        fn transaction_receipts() -> Vec<TransactionReceiptWrapper>;
+        /// Returns the classes declared in the current block, along with their compiled class hash for Cairo 1 classes.
+        fn declared_classes() -> Vec<(ClassHashWrapper, Option<ClassHashWrapper>)>;
+        /// Returns the storage vars written in the current block, in the order of the writes.
+        fn storage_diffs() -> Vec<(ContractAddressWrapper, H256, U256)>;
+        /// Filters extrinsics to keep only the Starknet transactions.
+        fn extrinsic_filter(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<Transaction>;
+        /// Re-executes the given transactions and returns their execution traces, stopping after `target` if set.
+        fn trace_transactions(transactions: Vec<Transaction>, target: Option<H256>) -> Result<Vec<TransactionTraceWrapper>, DispatchError>;
+        /// Simulates the given transactions on top of the current state and the given pending transactions, and returns their traces and fee estimations.
+        fn simulate_transactions(pending_transactions: Vec<Transaction>, transactions: Vec<Transaction>, simulation_flags: SimulationFlags) -> Result<Vec<SimulatedTransactionWrapper>, DispatchError>;
+        /// Returns the contract class of a given class hash, if it has been declared.
+        fn contract_class_by_class_hash(class_hash: ClassHashWrapper) -> Option<ContractClassWrapper>;
+        /// Wraps a Starknet transaction into an unsigned extrinsic.
+        fn convert_transaction(transaction: Transaction) -> <Block as BlockT>::Extrinsic;
     }
 }
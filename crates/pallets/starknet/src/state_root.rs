@@ -2,10 +2,11 @@
 
 use core::marker::PhantomData;
 
-use sp_core::{Get, U256};
+use mp_starknet::crypto::merkle_patricia_tree::stored_tree::{NodeStorage, StoredNode};
+use sp_core::{Get, H256, U256};
 use starknet_crypto::FieldElement;
 
-use crate::Config;
+use crate::{ClassTrieNodes, Config};
 
 pub struct IntermediateStateRoot<T>(PhantomData<T>);
 impl<T: Config> Get<U256> for IntermediateStateRoot<T> {
@@ -18,3 +19,22 @@ impl<T: Config> Get<U256> for IntermediateStateRoot<T> {
         U256::from_big_endian(FieldElement::ONE.to_bytes_be().as_slice())
     }
 }
+
+/// The nodes of the class commitment tree, in the pallet storage.
+pub struct ClassTrieStorage<T>(PhantomData<T>);
+
+impl<T> Default for ClassTrieStorage<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Config> NodeStorage for ClassTrieStorage<T> {
+    fn get(&self, hash: &H256) -> Option<StoredNode> {
+        ClassTrieNodes::<T>::get(hash)
+    }
+
+    fn insert(&mut self, hash: H256, node: StoredNode) {
+        ClassTrieNodes::<T>::insert(hash, node);
+    }
+}
@@ -1,7 +1,9 @@
 use core::str::FromStr;
 
 use blockifier::execution::contract_class::ContractClass;
-use blockifier::test_utils::{get_contract_class, ACCOUNT_CONTRACT_PATH, ERC20_CONTRACT_PATH};
+use blockifier::test_utils::{get_contract_class, ERC20_CONTRACT_PATH};
+use frame_support::traits::Hooks;
+use frame_support::unsigned::ValidateUnsigned;
 use frame_support::{assert_err, assert_ok, bounded_vec, debug, BoundedVec};
 use hex::FromHex;
 use hexlit::hex;
@@ -9,19 +11,21 @@ use lazy_static::lazy_static;
 use mp_starknet::block::Header as StarknetHeader;
 use mp_starknet::crypto::commitment;
 use mp_starknet::crypto::hash::pedersen::PedersenHasher;
-use mp_starknet::execution::{
-    CallEntryPointWrapper, ContractAddressWrapper, ContractClassWrapper, EntryPointTypeWrapper,
-};
+use mp_starknet::execution::{ContractAddressWrapper, ContractClassWrapper};
 use mp_starknet::starknet_serde::transaction_from_json;
-use mp_starknet::transaction::types::{EventWrapper, Transaction, TxType};
+use mp_starknet::transaction::types::{
+    DeclareTransaction, DeployAccountTransaction, EventWrapper, InvokeTransaction, L1HandlerTransaction,
+    SimulationFlags, Transaction, TransactionExecutionStatus, TxType,
+};
 use sp_core::{H256, U256};
 use sp_runtime::transaction_validity::InvalidTransaction::Payment;
 use sp_runtime::transaction_validity::TransactionValidityError::Invalid;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
 use sp_runtime::DispatchError;
 
 use crate::mock::*;
 use crate::types::Message;
-use crate::{Error, Event, SEQUENCER_ADDRESS};
+use crate::{Call, DevCallsEnabled, Error, Event, SEQUENCER_ADDRESS};
 
 #[test]
 fn should_calculate_contract_addr_correct() {
@@ -87,7 +91,7 @@ fn given_hardcoded_contract_run_invoke_tx_fails_sender_not_deployed() {
         let contract_address_bytes = <[u8; 32]>::from_hex(contract_address_str).unwrap();
 
         let transaction =
-            Transaction { version: 1_u8, sender_address: contract_address_bytes, ..Transaction::default() };
+            InvokeTransaction { version: 1_u8, sender_address: contract_address_bytes, ..InvokeTransaction::default() };
 
         assert_err!(Starknet::invoke(none_origin, transaction), Error::<Test>::AccountNotDeployed);
     })
@@ -102,7 +106,7 @@ fn given_hardcoded_contract_run_invoke_tx_fails_invalid_tx_version() {
         let none_origin = RuntimeOrigin::none();
 
         let json_content: &str = include_str!("../../../../resources/transactions/invoke_invalid_version.json");
-        let transaction = transaction_from_json(json_content, &[]).expect("Failed to create Transaction from JSON");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
 
         assert_err!(Starknet::invoke(none_origin, transaction), Error::<Test>::TransactionExecutionFailed);
     });
@@ -117,7 +121,7 @@ fn given_hardcoded_contract_run_invoke_tx_then_it_works() {
         let none_origin = RuntimeOrigin::none();
 
         let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
-        let transaction = transaction_from_json(json_content, &[]).expect("Failed to create Transaction from JSON");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
 
         let tx = Message {
             topics: vec![
@@ -137,11 +141,145 @@ fn given_hardcoded_contract_run_invoke_tx_then_it_works() {
         let pending = Starknet::pending();
         pretty_assertions::assert_eq!(pending.len(), 2);
 
-        let receipt = &pending.get(0).unwrap().1;
+        let (pending_transaction, receipt) = pending.get(0).unwrap();
+        pretty_assertions::assert_eq!(pending_transaction.tx_type(), TxType::InvokeTx);
         pretty_assertions::assert_eq!(receipt.actual_fee, U256::from(0));
         pretty_assertions::assert_eq!(receipt.events.len(), 0);
         pretty_assertions::assert_eq!(receipt.transaction_hash, transaction.hash);
-        pretty_assertions::assert_eq!(receipt.tx_type, TxType::InvokeTx);
+
+        // The receipts of the block are kept once the block is finalized.
+        Starknet::on_finalize(2);
+        pretty_assertions::assert_eq!(
+            Starknet::block_receipts().into_inner(),
+            pending.into_iter().map(|(_, receipt)| receipt).collect::<Vec<_>>()
+        );
+    });
+}
+
+#[test]
+fn given_invoke_tx_calling_unknown_entrypoint_then_it_is_reverted() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        // Call an entrypoint which does not exist in the target contract.
+        transaction.calldata[1] = U256::from(1);
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+
+        let pending = Starknet::pending();
+        pretty_assertions::assert_eq!(pending.len(), 1);
+
+        let receipt = &pending.get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.transaction_hash, transaction.hash);
+        pretty_assertions::assert_eq!(receipt.execution_status, TransactionExecutionStatus::Reverted);
+        pretty_assertions::assert_eq!(receipt.events.len(), 0);
+        let revert_reason = receipt.revert_reason.clone().unwrap();
+        assert!(!revert_reason.is_empty());
+        System::assert_last_event(
+            Event::TransactionReverted { transaction_hash: transaction.hash, revert_reason }.into(),
+        );
+
+        // The nonce of the sender is still consumed.
+        pretty_assertions::assert_eq!(Starknet::nonce(transaction.sender_address), U256::one());
+    });
+}
+
+#[test]
+fn given_invoke_tx_calling_unknown_entrypoint_then_its_fee_is_charged() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(1);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        // Call an entrypoint which does not exist in the target contract.
+        transaction.calldata[1] = U256::from(1);
+        transaction.max_fee = U256::from(1_000_000);
+
+        let fee_token_address = Starknet::fee_token_address();
+        let (low_key, high_key) = Starknet::fee_token_balance_keys(transaction.sender_address).unwrap();
+        let balance = U256::from(1_000_000);
+        assert_ok!(Starknet::mint_fee_token(RuntimeOrigin::root(), transaction.sender_address, balance));
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.execution_status, TransactionExecutionStatus::Reverted);
+        assert!(receipt.actual_fee > U256::zero());
+        // The validation and fee transfer still happened, and their events are kept.
+        assert!(!receipt.events.is_empty());
+
+        // The fee is deducted from the balance of the sender.
+        pretty_assertions::assert_eq!(Starknet::storage((fee_token_address, low_key)), balance - receipt.actual_fee);
+        pretty_assertions::assert_eq!(Starknet::storage((fee_token_address, high_key)), U256::zero());
+    });
+}
+
+#[test]
+fn given_invoke_tx_with_low_max_fee_when_invoke_then_charged_fee_is_capped() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(1);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        transaction.calldata[1] = U256::from(1);
+        transaction.max_fee = U256::one();
+        assert_ok!(Starknet::mint_fee_token(RuntimeOrigin::root(), transaction.sender_address, U256::from(1_000_000)));
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.actual_fee, U256::one());
+    });
+}
+
+#[test]
+fn given_reverted_invoke_tx_when_sender_cannot_pay_then_it_is_included_with_its_balance_as_fee() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(1_000);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        transaction.calldata[1] = U256::from(1);
+        transaction.max_fee = U256::from(u128::MAX);
+
+        let fee_token_address = Starknet::fee_token_address();
+        let (low_key, high_key) = Starknet::fee_token_balance_keys(transaction.sender_address).unwrap();
+        assert_ok!(Starknet::set_storage(RuntimeOrigin::root(), fee_token_address, low_key, U256::from(3)));
+        assert_ok!(Starknet::set_storage(RuntimeOrigin::root(), fee_token_address, high_key, U256::zero()));
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.execution_status, TransactionExecutionStatus::Reverted);
+        pretty_assertions::assert_eq!(receipt.actual_fee, U256::from(3));
+        pretty_assertions::assert_eq!(Starknet::storage((fee_token_address, low_key)), U256::zero());
+    });
+}
+
+#[test]
+fn given_invoke_tx_with_fee_above_max_fee_when_validate_unsigned_then_it_is_rejected() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(1);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        pretty_assertions::assert_eq!(transaction.max_fee, U256::zero());
+
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::External, &Call::invoke { transaction }),
+            Invalid(Payment)
+        );
     });
 }
 
@@ -154,7 +292,7 @@ fn given_hardcoded_contract_run_invoke_tx_then_event_is_emitted() {
         let none_origin = RuntimeOrigin::none();
 
         let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
-        let transaction = transaction_from_json(json_content, &[]).expect("Failed to create Transaction from JSON");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
 
         assert_ok!(Starknet::invoke(none_origin, transaction.clone()));
 
@@ -184,12 +322,436 @@ fn given_hardcoded_contract_run_invoke_tx_then_event_is_emitted() {
 
         pretty_assertions::assert_eq!(pending.len(), 1);
 
-        let receipt = &pending.get(0).unwrap().1;
+        let (pending_transaction, receipt) = pending.get(0).unwrap();
+        pretty_assertions::assert_eq!(pending_transaction.tx_type(), TxType::InvokeTx);
         pretty_assertions::assert_eq!(receipt.actual_fee, U256::from(0));
         pretty_assertions::assert_eq!(receipt.events.len(), 1);
         pretty_assertions::assert_eq!(receipt.events.get(0).unwrap(), &emitted_event);
         pretty_assertions::assert_eq!(receipt.transaction_hash, transaction.hash);
-        pretty_assertions::assert_eq!(receipt.tx_type, TxType::InvokeTx);
+
+        // The events of the block can still be read once it is finalized.
+        Starknet::on_finalize(2);
+        pretty_assertions::assert_eq!(Starknet::block_events().into_inner(), vec![(0, emitted_event)]);
+    });
+}
+
+#[test]
+fn given_nested_calls_run_invoke_tx_then_inner_event_is_emitted() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        // The account calls `test_call_contract`, which in turn calls `emit_event` on the same contract.
+        let test_contract_address = transaction.calldata[0];
+        let emit_event_selector = transaction.calldata[1];
+        let test_call_contract_selector =
+            U256::from_str("027c3334165536f239cfd400ed956eabff55fc60de4fb56728b6a4f6b87db01c").unwrap();
+        transaction.calldata = bounded_vec![
+            test_contract_address,
+            test_call_contract_selector,
+            U256::from(3),
+            test_contract_address,
+            emit_event_selector,
+            U256::zero()
+        ];
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+
+        let emitted_event = EventWrapper {
+            keys: bounded_vec![
+                H256::from_str("0x02d4fbe4956fedf49b5892807e00e7e9eea4680becba55f9187684a69e9424fa").unwrap()
+            ],
+            data: bounded_vec!(
+                H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap()
+            ),
+            from_address: H256::from_str("0x024d1e355f6b9d27a5a420c8f4b50cea9154a8e34ad30fc39d7c98d3c177d0d7")
+                .unwrap()
+                .to_fixed_bytes(),
+        };
+
+        System::assert_last_event(Event::StarknetEvent(emitted_event.clone()).into());
+        pretty_assertions::assert_eq!(Starknet::pending_events().len(), 1);
+
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.events.len(), 1);
+        pretty_assertions::assert_eq!(receipt.events.get(0).unwrap(), &emitted_event);
+    });
+}
+
+#[test]
+fn given_legacy_invoke_tx_then_contract_is_called_directly() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
+        let account_transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        // The transaction calls `emit_event` on the test contract, without going through the account.
+        let test_contract_address = H256::from_uint(&account_transaction.calldata[0]).to_fixed_bytes();
+        let emit_event_selector = H256::from_uint(&account_transaction.calldata[1]);
+        let transaction = InvokeTransaction {
+            version: 0_u8,
+            sender_address: test_contract_address,
+            entry_point_selector: Some(emit_event_selector),
+            calldata: bounded_vec![],
+            ..account_transaction
+        };
+
+        let call = Call::invoke { transaction: transaction.clone() };
+        assert_ok!(Starknet::validate_unsigned(TransactionSource::InBlock, &call));
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+
+        let emitted_event = EventWrapper {
+            keys: bounded_vec![
+                H256::from_str("0x02d4fbe4956fedf49b5892807e00e7e9eea4680becba55f9187684a69e9424fa").unwrap()
+            ],
+            data: bounded_vec!(
+                H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap()
+            ),
+            from_address: test_contract_address,
+        };
+        System::assert_last_event(Event::StarknetEvent(emitted_event.clone()).into());
+
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.events.get(0).unwrap(), &emitted_event);
+        // Version 0 transactions don't use the nonce of their sender.
+        pretty_assertions::assert_eq!(Starknet::nonce(test_contract_address), U256::zero());
+    });
+}
+
+#[test]
+fn given_included_legacy_invoke_tx_when_validate_unsigned_then_it_is_stale_and_was_not_charged() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(1);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
+        let account_transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let transaction = InvokeTransaction {
+            version: 0_u8,
+            sender_address: H256::from_uint(&account_transaction.calldata[0]).to_fixed_bytes(),
+            entry_point_selector: Some(H256::from_uint(&account_transaction.calldata[1])),
+            calldata: bounded_vec![],
+            ..account_transaction
+        };
+        let call = Call::invoke { transaction: transaction.clone() };
+        assert_ok!(Starknet::validate_unsigned(TransactionSource::External, &call));
+
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+
+        // The called contract is not an account, so it is not charged.
+        let receipt = &Starknet::pending().get(0).unwrap().1;
+        pretty_assertions::assert_eq!(receipt.actual_fee, U256::zero());
+
+        // The transaction cannot be replayed, even under another claimed hash.
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::External, &call),
+            Invalid(InvalidTransaction::Stale)
+        );
+        let replay = InvokeTransaction { hash: H256::from_low_u64_be(42), ..transaction };
+        assert_err!(Starknet::pre_dispatch(&Call::invoke { transaction: replay }), Invalid(InvalidTransaction::Stale));
+    });
+}
+
+#[test]
+fn given_legacy_invoke_tx_without_entry_point_selector_then_it_is_rejected() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
+        let account_transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let transaction = InvokeTransaction {
+            version: 0_u8,
+            sender_address: H256::from_uint(&account_transaction.calldata[0]).to_fixed_bytes(),
+            entry_point_selector: None,
+            calldata: bounded_vec![],
+            ..account_transaction
+        };
+
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::InBlock, &Call::invoke { transaction }),
+            Invalid(InvalidTransaction::BadProof)
+        );
+    });
+}
+
+#[test]
+fn given_invoke_tx_when_trace_transactions_then_inner_call_and_event_are_traced() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke_emit_event.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+
+        // Both transactions are replayed on the same state, the second one sees the first one.
+        let traces =
+            Starknet::trace_transactions(vec![transaction.clone().into(), transaction.clone().into()], None).unwrap();
+        pretty_assertions::assert_eq!(traces.len(), 2);
+
+        let trace = traces.get(0).unwrap();
+        pretty_assertions::assert_eq!(trace.transaction_hash, transaction.hash);
+        pretty_assertions::assert_eq!(trace.signature, transaction.signature.to_vec());
+        // The account validates the transaction, and there is no fee to transfer yet.
+        assert!(trace.validate_invocation.is_some());
+        assert!(trace.fee_transfer_invocation.is_none());
+
+        let invocation = trace.function_invocation.as_ref().unwrap();
+        pretty_assertions::assert_eq!(invocation.contract_address, transaction.sender_address);
+        pretty_assertions::assert_eq!(invocation.calls.len(), 1);
+        pretty_assertions::assert_eq!(invocation.calls.get(0).unwrap().events.len(), 1);
+
+        // Tracing must not touch the pending block nor the storage.
+        pretty_assertions::assert_eq!(Starknet::pending().len(), 0);
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+    });
+}
+
+#[test]
+fn given_invoke_tx_when_simulate_transactions_then_state_is_not_modified() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let simulation_flags = SimulationFlags { skip_validate: false, skip_fee_charge: true };
+
+        let simulations = Starknet::simulate_transactions(
+            vec![],
+            vec![transaction.clone().into(), transaction.clone().into()],
+            simulation_flags,
+        )
+        .unwrap();
+        pretty_assertions::assert_eq!(simulations.len(), 2);
+
+        let trace = &simulations.get(0).unwrap().transaction_trace;
+        pretty_assertions::assert_eq!(trace.transaction_hash, transaction.hash);
+        assert!(trace.validate_invocation.is_some());
+        assert!(trace.function_invocation.is_some());
+        assert!(trace.fee_transfer_invocation.is_none());
+
+        // Simulations must not touch the pending block nor the storage.
+        pretty_assertions::assert_eq!(Starknet::pending().len(), 0);
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction));
+    });
+}
+
+#[test]
+fn given_skip_validate_when_simulate_transactions_then_validation_is_not_traced() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let simulation_flags = SimulationFlags { skip_validate: true, skip_fee_charge: true };
+
+        let simulations = Starknet::simulate_transactions(vec![], vec![transaction.into()], simulation_flags).unwrap();
+
+        let trace = &simulations.get(0).unwrap().transaction_trace;
+        assert!(trace.validate_invocation.is_none());
+        assert!(trace.function_invocation.is_some());
+    });
+}
+
+#[test]
+fn given_gas_price_when_simulate_transactions_then_fee_is_estimated() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        L1GasPrice::set(10);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let simulation_flags = SimulationFlags { skip_validate: false, skip_fee_charge: true };
+
+        let simulations = Starknet::simulate_transactions(vec![], vec![transaction.into()], simulation_flags).unwrap();
+
+        let fee_estimation = &simulations.get(0).unwrap().fee_estimation;
+        assert!(fee_estimation.gas_consumed > U256::zero());
+        pretty_assertions::assert_eq!(fee_estimation.gas_price, U256::from(10));
+        pretty_assertions::assert_eq!(fee_estimation.overall_fee, fee_estimation.gas_consumed * 10);
+    });
+}
+
+#[test]
+fn given_invoke_tx_calling_unknown_entrypoint_when_simulate_transactions_then_it_is_reverted() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let mut transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        // Call an entrypoint which does not exist in the target contract.
+        transaction.calldata[1] = U256::from(1);
+        let simulation_flags = SimulationFlags { skip_validate: false, skip_fee_charge: true };
+
+        let simulations = Starknet::simulate_transactions(vec![], vec![transaction.into()], simulation_flags).unwrap();
+
+        let trace = &simulations.get(0).unwrap().transaction_trace;
+        assert!(trace.validate_invocation.is_some());
+        assert!(trace.function_invocation.is_none());
+        assert!(!trace.revert_reason.clone().unwrap().is_empty());
+    });
+}
+
+#[test]
+fn given_pending_erc20_transfer_when_call_contract_then_pending_balance_is_returned() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(1);
+
+        let (sender_account, _, _) = account_helper(TEST_ACCOUNT_SALT);
+        let declare_transaction = DeclareTransaction {
+            sender_address: sender_account,
+            class_hash: ERC20_CLASS_HASH,
+            contract_class: ERC20_CONTRACT_CLASS.clone(),
+            ..DeclareTransaction::default()
+        };
+        let deploy_transaction =
+            transaction_from_json(include_str!("../../../../resources/transactions/deploy_erc20.json")).unwrap();
+        let transfer_transaction =
+            transaction_from_json(include_str!("../../../../resources/transactions/invoke_erc20_transfer.json"))
+                .unwrap();
+
+        let erc20_address = H256::from_str("0x0074c41dd9ba722396796cba415f8a742d671eb872371c96ce1ce6016fd0f2bb")
+            .unwrap()
+            .to_fixed_bytes();
+        let balance_of_selector =
+            H256::from_str("0x02e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e").unwrap();
+        let recipient = U256::from_str("0x01176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8").unwrap();
+
+        // The token only exists in the pending block.
+        assert_err!(
+            Starknet::call_contract(vec![], erc20_address, balance_of_selector, vec![recipient]),
+            Error::<Test>::ContractNotFound
+        );
+
+        let pending_transactions: Vec<Transaction> =
+            vec![declare_transaction.into(), deploy_transaction.into(), transfer_transaction.into()];
+        let balance =
+            Starknet::call_contract(pending_transactions, erc20_address, balance_of_selector, vec![recipient]).unwrap();
+        pretty_assertions::assert_eq!(balance, vec![U256::from(2), U256::zero()]);
+
+        // The pending transactions are not applied to the storage.
+        pretty_assertions::assert_eq!(Starknet::contract_class_hash_by_address(erc20_address), [0; 32]);
+        pretty_assertions::assert_eq!(Starknet::pending().len(), 0);
+    });
+}
+
+#[test]
+fn given_invoke_tx_when_validate_unsigned_then_it_provides_sender_nonce() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+
+        let validity = Starknet::validate_unsigned(TransactionSource::External, &Call::invoke { transaction }).unwrap();
+        pretty_assertions::assert_eq!(validity.provides.len(), 1);
+        assert!(validity.requires.is_empty());
+        pretty_assertions::assert_eq!(validity.longevity, TransactionLongevity::get());
+        assert!(validity.propagate);
+    });
+}
+
+#[test]
+fn given_executed_invoke_tx_when_validate_unsigned_then_nonce_is_checked() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        assert_ok!(Starknet::invoke(RuntimeOrigin::none(), transaction.clone()));
+        pretty_assertions::assert_eq!(Starknet::nonce(transaction.sender_address), U256::one());
+
+        let call = Call::invoke { transaction: transaction.clone() };
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::External, &call),
+            Invalid(InvalidTransaction::Stale)
+        );
+
+        // Future nonces wait in the pool for the previous nonce, but can't be executed yet
+        let call = Call::invoke { transaction: InvokeTransaction { nonce: U256::from(2), ..transaction.clone() } };
+        let validity = Starknet::validate_unsigned(TransactionSource::External, &call).unwrap();
+        pretty_assertions::assert_eq!(validity.requires.len(), 1);
+        assert_err!(Starknet::pre_dispatch(&call), Invalid(InvalidTransaction::Future));
+
+        let call = Call::invoke { transaction: InvokeTransaction { nonce: U256::one(), ..transaction } };
+        let validity = Starknet::validate_unsigned(TransactionSource::External, &call).unwrap();
+        assert!(validity.requires.is_empty());
+        assert_ok!(Starknet::pre_dispatch(&call));
+    });
+}
+
+#[test]
+fn given_consecutive_nonces_when_validate_unsigned_then_tags_chain_them() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+        let first = Call::invoke { transaction: transaction.clone() };
+        let second = Call::invoke { transaction: InvokeTransaction { nonce: U256::one(), ..transaction } };
+
+        let first = Starknet::validate_unsigned(TransactionSource::External, &first).unwrap();
+        let second = Starknet::validate_unsigned(TransactionSource::External, &second).unwrap();
+        pretty_assertions::assert_eq!(second.requires, first.provides);
+        assert_ne!(second.provides, first.provides);
+    });
+}
+
+#[test]
+fn given_undeployed_sender_when_validate_unsigned_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let contract_address_str = "03e437FB56Bb213f5708Fcd6966502070e276c093ec271aA33433b89E21fd31f";
+        let contract_address_bytes = <[u8; 32]>::from_hex(contract_address_str).unwrap();
+        let transaction =
+            InvokeTransaction { version: 1_u8, sender_address: contract_address_bytes, ..InvokeTransaction::default() };
+
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::External, &Call::invoke { transaction }),
+            Invalid(InvalidTransaction::BadSigner)
+        );
+    });
+}
+
+#[test]
+fn given_external_l1_message_when_validate_unsigned_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let transaction = L1HandlerTransaction::default();
+
+        assert_err!(
+            Starknet::validate_unsigned(TransactionSource::External, &Call::consume_l1_message { transaction }),
+            Invalid(InvalidTransaction::Call)
+        );
+    });
+}
+
+#[test]
+fn given_signed_origin_when_invoke_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let json_content: &str = include_str!("../../../../resources/transactions/invoke.json");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
+
+        assert_err!(Starknet::invoke(RuntimeOrigin::signed(1), transaction), DispatchError::BadOrigin);
     });
 }
 
@@ -202,8 +764,7 @@ fn given_hardcoded_contract_run_storage_read_and_write_it_works() {
         let none_origin = RuntimeOrigin::none();
 
         let json_content: &str = include_str!("../../../../resources/transactions/storage_read_write.json");
-        let transaction =
-            transaction_from_json(json_content, ACCOUNT_CONTRACT_PATH).expect("Failed to create Transaction from JSON");
+        let transaction = transaction_from_json(json_content).expect("Failed to create Transaction from JSON");
 
         let target_contract_address =
             U256::from_str("024d1e355f6b9d27a5a420c8f4b50cea9154a8e34ad30fc39d7c98d3c177d0d7").unwrap();
@@ -219,6 +780,11 @@ fn given_hardcoded_contract_run_storage_read_and_write_it_works() {
             Starknet::storage((contract_address_bytes, H256::from_slice(&storage_var_selector_bytes))),
             U256::one()
         );
+        assert!(Starknet::block_storage_diffs().contains(&(
+            contract_address_bytes,
+            H256::from_slice(&storage_var_selector_bytes),
+            U256::one()
+        )));
     });
 }
 
@@ -234,18 +800,15 @@ fn given_contract_run_deploy_account_tx_works() {
         let salt = "0x03b37cbe4e9eac89d54c5f7cc6329a63a63e8c8db2bf936f981041e086752463";
         let (test_addr, account_class_hash, calldata) = account_helper(salt);
 
-        let transaction = Transaction {
-            sender_address: test_addr,
-            call_entrypoint: CallEntryPointWrapper::new(
-                Some(account_class_hash),
-                EntryPointTypeWrapper::External,
-                None,
-                BoundedVec::try_from(calldata.clone().into_iter().map(U256::from).collect::<Vec<U256>>()).unwrap(),
-                test_addr,
-                test_addr,
-            ),
-            contract_address_salt: Some(H256::from_str(salt).unwrap()),
-            ..Transaction::default()
+        let transaction = DeployAccountTransaction {
+            contract_address: test_addr,
+            class_hash: account_class_hash,
+            contract_address_salt: H256::from_str(salt).unwrap(),
+            constructor_calldata: BoundedVec::try_from(
+                calldata.clone().into_iter().map(U256::from).collect::<Vec<U256>>(),
+            )
+            .unwrap(),
+            ..DeployAccountTransaction::default()
         };
 
         assert_ok!(Starknet::deploy_account(none_origin, transaction));
@@ -265,18 +828,15 @@ fn given_contract_run_deploy_account_tx_twice_fails() {
 
         // TEST ACCOUNT CONTRACT
         // - ref testnet tx(0x0751b4b5b95652ad71b1721845882c3852af17e2ed0c8d93554b5b292abb9810)
-        let transaction = Transaction {
-            sender_address: test_addr,
-            call_entrypoint: CallEntryPointWrapper::new(
-                Some(account_class_hash),
-                EntryPointTypeWrapper::External,
-                None,
-                BoundedVec::try_from(calldata.clone().into_iter().map(U256::from).collect::<Vec<U256>>()).unwrap(),
-                test_addr,
-                test_addr,
-            ),
-            contract_address_salt: Some(H256::from_str(salt).unwrap()),
-            ..Transaction::default()
+        let transaction = DeployAccountTransaction {
+            contract_address: test_addr,
+            class_hash: account_class_hash,
+            contract_address_salt: H256::from_str(salt).unwrap(),
+            constructor_calldata: BoundedVec::try_from(
+                calldata.clone().into_iter().map(U256::from).collect::<Vec<U256>>(),
+            )
+            .unwrap(),
+            ..DeployAccountTransaction::default()
         };
 
         assert_ok!(Starknet::deploy_account(none_origin.clone(), transaction.clone()));
@@ -298,17 +858,10 @@ fn given_contract_run_deploy_account_tx_undeclared_then_it_fails() {
         let undeclared_class_hash =
             <[u8; 32]>::from_hex("00000000000000000000000000000000000000000000000000000000BEEFDEAD").unwrap();
 
-        let transaction = Transaction {
-            sender_address: rand_address,
-            call_entrypoint: CallEntryPointWrapper::new(
-                Some(undeclared_class_hash),
-                EntryPointTypeWrapper::External,
-                None,
-                bounded_vec![],
-                rand_address,
-                rand_address,
-            ),
-            ..Transaction::default()
+        let transaction = DeployAccountTransaction {
+            contract_address: rand_address,
+            class_hash: undeclared_class_hash,
+            ..DeployAccountTransaction::default()
         };
 
         assert_err!(Starknet::deploy_account(none_origin, transaction), Error::<Test>::TransactionExecutionFailed);
@@ -328,27 +881,21 @@ fn given_contract_declare_tx_works_once_not_twice() {
         let erc20_class_hash =
             <[u8; 32]>::from_hex("057eca87f4b19852cfd4551cf4706ababc6251a8781733a0a11cf8e94211da95").unwrap();
 
-        let mut transaction = Transaction {
+        let transaction = DeclareTransaction {
             sender_address: account_addr,
-            call_entrypoint: CallEntryPointWrapper::new(
-                Some(erc20_class_hash),
-                EntryPointTypeWrapper::External,
-                None,
-                bounded_vec![],
-                account_addr,
-                account_addr,
-            ),
-            ..Transaction::default()
+            class_hash: erc20_class_hash,
+            contract_class: erc20_class,
+            ..DeclareTransaction::default()
         };
-        // Cannot declare a class with None
-        assert_err!(
-            Starknet::declare(none_origin.clone(), transaction.clone()),
-            Error::<Test>::ContractClassMustBeSpecified
-        );
-
-        transaction.contract_class = Some(erc20_class);
 
         assert_ok!(Starknet::declare(none_origin.clone(), transaction.clone()));
+        System::assert_last_event(
+            Event::ClassDeclared { class_hash: erc20_class_hash, compiled_class_hash: None }.into(),
+        );
+        // Cairo 0 classes are not committed to in the state root.
+        assert_eq!(Starknet::compiled_class_hash(erc20_class_hash), None);
+        assert_eq!(Starknet::class_commitment(), H256::zero());
+        assert_eq!(Starknet::block_declared_classes().into_inner(), vec![(erc20_class_hash, None)]);
         // TODO: Uncomment once we have ABI support
         // assert_eq!(Starknet::contract_class_by_class_hash(erc20_class_hash), erc20_class);
         assert_err!(Starknet::declare(none_origin, transaction), Error::<Test>::ClassHashAlreadyDeclared);
@@ -488,6 +1035,115 @@ fn given_non_root_when_set_fee_token_address_then_it_fails() {
     })
 }
 
+#[test]
+fn given_root_when_set_storage_and_nonce_then_state_is_updated() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let contract_address =
+            <[u8; 32]>::from_hex("0000000000000000000000000000000000000000000000000000000000001111").unwrap();
+        let key = H256::from_low_u64_be(42);
+
+        assert_ok!(Starknet::set_storage(RuntimeOrigin::root(), contract_address, key, U256::from(7)));
+        assert_ok!(Starknet::set_nonce(RuntimeOrigin::root(), contract_address, U256::from(3)));
+        assert_eq!(Starknet::storage((contract_address, key)), U256::from(7));
+        assert_eq!(Starknet::nonce(contract_address), U256::from(3));
+    })
+}
+
+#[test]
+fn given_non_root_when_set_storage_or_nonce_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let contract_address =
+            <[u8; 32]>::from_hex("0000000000000000000000000000000000000000000000000000000000001111").unwrap();
+        assert_err!(
+            Starknet::set_storage(RuntimeOrigin::signed(1), contract_address, H256::zero(), U256::one()),
+            DispatchError::BadOrigin
+        );
+        assert_err!(
+            Starknet::set_nonce(RuntimeOrigin::signed(1), contract_address, U256::one()),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
+#[test]
+fn given_root_when_mint_fee_token_then_balance_is_increased() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let fee_token_address = Starknet::fee_token_address();
+        let recipient =
+            <[u8; 32]>::from_hex("000000000000000000000000000000000000000000000000000000000000000F").unwrap();
+        let (low_key, high_key) = Starknet::fee_token_balance_keys(recipient).unwrap();
+        // The keys of the genesis balance of 0x0F
+        assert_eq!(
+            low_key,
+            H256::from_str("0x078e4fa4db2b6f3c7a9ece31571d47ac0e853975f90059f7c9df88df974d9093").unwrap()
+        );
+        assert_eq!(
+            high_key,
+            H256::from_str("0x078e4fa4db2b6f3c7a9ece31571d47ac0e853975f90059f7c9df88df974d9094").unwrap()
+        );
+
+        // The balance of 0x0F is the maximum Uint256, so minting overflows
+        assert_err!(
+            Starknet::mint_fee_token(RuntimeOrigin::root(), recipient, U256::one()),
+            Error::<Test>::FeeTokenBalanceOverflow
+        );
+
+        // Minting carries from the low to the high part of the balance
+        assert_ok!(Starknet::set_storage(RuntimeOrigin::root(), fee_token_address, high_key, U256::zero()));
+        assert_ok!(Starknet::mint_fee_token(RuntimeOrigin::root(), recipient, U256::one()));
+        assert_eq!(Starknet::storage((fee_token_address, low_key)), U256::zero());
+        assert_eq!(Starknet::storage((fee_token_address, high_key)), U256::one());
+    })
+}
+
+#[test]
+fn given_non_root_when_mint_fee_token_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+
+        let recipient =
+            <[u8; 32]>::from_hex("000000000000000000000000000000000000000000000000000000000000000F").unwrap();
+        assert_err!(
+            Starknet::mint_fee_token(RuntimeOrigin::signed(1), recipient, U256::one()),
+            DispatchError::BadOrigin
+        );
+    })
+}
+
+#[test]
+fn given_dev_calls_disabled_when_set_storage_nonce_or_mint_then_it_fails() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(0);
+        run_to_block(2);
+        DevCallsEnabled::<Test>::put(false);
+
+        let contract_address =
+            <[u8; 32]>::from_hex("0000000000000000000000000000000000000000000000000000000000001111").unwrap();
+        assert_err!(
+            Starknet::set_storage(RuntimeOrigin::root(), contract_address, H256::zero(), U256::one()),
+            Error::<Test>::DevCallsDisabled
+        );
+        assert_err!(
+            Starknet::set_nonce(RuntimeOrigin::root(), contract_address, U256::one()),
+            Error::<Test>::DevCallsDisabled
+        );
+        assert_err!(
+            Starknet::mint_fee_token(RuntimeOrigin::root(), contract_address, U256::one()),
+            Error::<Test>::DevCallsDisabled
+        );
+    })
+}
+
 #[test]
 fn given_erc20_transfer_when_invoke_then_it_works() {
     new_test_ext().execute_with(|| {
@@ -527,18 +1183,11 @@ fn given_erc20_transfer_when_invoke_then_it_works() {
 /// * `origin` - The origin of the transaction.
 /// * `sender_account` - The address of the sender account.
 fn declare_erc20(origin: RuntimeOrigin, sender_account: ContractAddressWrapper) {
-    let declare_transaction = Transaction {
+    let declare_transaction = DeclareTransaction {
         sender_address: sender_account,
-        call_entrypoint: CallEntryPointWrapper::new(
-            Some(ERC20_CLASS_HASH),
-            EntryPointTypeWrapper::External,
-            None,
-            bounded_vec![],
-            sender_account,
-            sender_account,
-        ),
-        contract_class: Some(ERC20_CONTRACT_CLASS.clone()),
-        ..Transaction::default()
+        class_hash: ERC20_CLASS_HASH,
+        contract_class: ERC20_CONTRACT_CLASS.clone(),
+        ..DeclareTransaction::default()
     };
     assert_ok!(Starknet::declare(origin, declare_transaction));
 }
@@ -548,11 +1197,8 @@ fn declare_erc20(origin: RuntimeOrigin, sender_account: ContractAddressWrapper)
 /// * `origin` - The origin of the transaction.
 /// * `sender_account` - The address of the sender account.
 fn deploy_erc20(origin: RuntimeOrigin, _sender_account: ContractAddressWrapper) {
-    let deploy_transaction = transaction_from_json(
-        include_str!("../../../../resources/transactions/deploy_erc20.json"),
-        include_bytes!("../../../../resources/account/account.json"),
-    )
-    .unwrap();
+    let deploy_transaction =
+        transaction_from_json(include_str!("../../../../resources/transactions/deploy_erc20.json")).unwrap();
     assert_ok!(Starknet::invoke(origin, deploy_transaction));
 }
 
@@ -562,7 +1208,7 @@ fn deploy_erc20(origin: RuntimeOrigin, _sender_account: ContractAddressWrapper)
 /// * `sender_account` - The address of the sender account.
 fn invoke_transfer_erc20(origin: RuntimeOrigin, _sender_account: ContractAddressWrapper) {
     let erc20_mint_tx_json: &str = include_str!("../../../../resources/transactions/invoke_erc20_transfer.json");
-    let erc20_mint_tx = transaction_from_json(erc20_mint_tx_json, &[]).expect("Failed to create Transaction from JSON");
+    let erc20_mint_tx = transaction_from_json(erc20_mint_tx_json).expect("Failed to create Transaction from JSON");
     assert_ok!(Starknet::invoke(origin, erc20_mint_tx));
 }
 
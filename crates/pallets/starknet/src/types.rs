@@ -14,6 +14,7 @@ use alloc::vec::Vec;
 use core::str::Utf8Error;
 
 use blockifier::execution::contract_class::ContractClass;
+use blockifier::execution::entry_point::CallInfo;
 use starknet_api::api_core::ClassHash;
 use starknet_api::stdlib::collections::HashMap;
 
@@ -33,6 +34,23 @@ pub type MaxTransactionsPendingBlock = ConstU32<1073741824>;
 
 pub type ContractClassMapping = HashMap<ClassHash, ContractClass>;
 
+/// Calls made by the phases of a Starknet transaction execution.
+#[derive(Debug, Default)]
+pub struct TransactionCallInfos {
+    /// Validation by the account, `None` for L1 handler transactions.
+    pub validate_call_info: Option<CallInfo>,
+    /// Execution of the transaction, `None` when it was reverted.
+    pub execute_call_info: Option<CallInfo>,
+    /// Transfer of the fee to the sequencer, `None` when there is no fee to transfer.
+    pub fee_transfer_call_info: Option<CallInfo>,
+    /// L1 gas consumed by the transaction.
+    pub gas_consumed: u128,
+    /// Fee charged for the transaction, in the fee token.
+    pub actual_fee: u128,
+    /// Reason of the revert, when the execution failed and its state changes were rolled back.
+    pub revert_reason: Option<String>,
+}
+
 /// Representation of the origin of a Starknet transaction.
 /// For now, we still don't know how to represent the origin of a Starknet transaction,
 /// given that Starknet has native account abstraction.
@@ -23,6 +23,7 @@ use mp_starknet::block::Block as StarknetBlock;
 use scale_codec::{Decode, Encode};
 use sp_core::H256;
 use sp_runtime::generic::{Digest, OpaqueDigestItemId};
+use sp_runtime::sp_std::vec::Vec;
 use sp_runtime::ConsensusEngineId;
 
 pub const MADARA_ENGINE_ID: ConsensusEngineId = [b'm', b'a', b'd', b'a'];
@@ -43,11 +44,13 @@ pub enum PostLog {
 pub struct Hashes {
     /// Starknet block hash.
     pub block_hash: H256,
+    /// Starknet transaction hashes.
+    pub transaction_hashes: Vec<H256>,
 }
 
 impl Hashes {
     pub fn from_block(block: StarknetBlock) -> Self {
-        Hashes { block_hash: block.header().hash() }
+        Hashes { block_hash: block.header().hash(), transaction_hashes: block.transactions_hashes() }
     }
 }
 
@@ -12,6 +12,10 @@ use crate::transaction::types::Transaction;
 /// Serializer
 pub mod serialize;
 
+/// Id of the chain executing the blocks, committed to by the transaction hashes.
+// TODO: make it configurable
+pub const CHAIN_ID: &str = "SN_GOERLI";
+
 /// Block transactions max size
 // TODO: add real value (#250)
 pub type MaxTransactions = ConstU32<4294967295>;
@@ -85,7 +89,7 @@ impl Block {
     /// Return a reference to all transaction hashes
     pub fn transactions_hashes(&self) -> Vec<H256> {
         match &self.transactions {
-            BlockTransactions::Full(transactions) => transactions.into_iter().map(|tx| tx.hash).collect(),
+            BlockTransactions::Full(transactions) => transactions.into_iter().map(|tx| tx.hash()).collect(),
 
             BlockTransactions::Hashes(hashes) => hashes.to_vec(),
         }
@@ -6,6 +6,7 @@ use starknet_api::stdlib::collections::HashMap;
 
 use crate::alloc::string::ToString;
 use crate::block::header::Header;
+use crate::block::CHAIN_ID;
 use crate::execution::ContractAddressWrapper;
 
 /// Trait for serializing objects into a `BlockContext`.
@@ -58,7 +59,7 @@ impl SerializeBlockContext for BlockContext {
         .map_err(|_| BlockSerializationError::FeeTokenAddressError)?;
 
         Ok(BlockContext {
-            chain_id: ChainId("SN_GOERLI".to_string()),
+            chain_id: ChainId(CHAIN_ID.to_string()),
             block_number: BlockNumber(block_header.block_number.as_u64()),
             block_timestamp: BlockTimestamp(block_header.block_timestamp),
             sequencer_address,
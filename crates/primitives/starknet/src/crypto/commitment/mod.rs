@@ -1,11 +1,15 @@
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 
+use bitvec::prelude::Msb0;
 use bitvec::vec::BitVec;
 use sp_core::hexdisplay::AsBytesRef;
 use sp_core::H256;
 use starknet_crypto::FieldElement;
 
+use super::hash::poseidon::PoseidonHasher;
 use super::merkle_patricia_tree::merkle_tree::MerkleTree;
+use super::merkle_patricia_tree::stored_tree::{self, NodeStorage, StoredTreeError};
 use crate::traits::hash::CryptoHasher;
 use crate::transaction::types::{EventWrapper, Transaction};
 
@@ -104,6 +108,84 @@ pub fn calculate_event_commitment<T: CryptoHasher>(events: &[EventWrapper]) -> H
     H256::from_slice(&tree.commit().to_bytes_be())
 }
 
+/// Add a class to the class commitment.
+///
+/// The class commitment is the root of the Patricia Merkle tree with height 251 constructed by
+/// adding, for each Cairo 1 class, the class hash as key and the Poseidon hash of its compiled
+/// class hash as value (see https://docs.starknet.io/documentation/architecture_and_concepts/State/starknet-state/#classes_tree).
+/// The tree is hashed with Poseidon, and only the nodes along the path of the class are updated.
+///
+/// # Arguments
+///
+/// * `storage` - The storage of the nodes of the classes tree.
+/// * `class_commitment` - The current class commitment, zero if no class has been added yet.
+/// * `class_hash` - The hash of the class.
+/// * `compiled_class_hash` - The compiled class hash of the class.
+///
+/// # Returns
+///
+/// The new class commitment.
+pub fn update_class_commitment<S: NodeStorage>(
+    storage: &mut S,
+    class_commitment: H256,
+    class_hash: H256,
+    compiled_class_hash: H256,
+) -> Result<H256, StoredTreeError> {
+    let key = BitVec::<Msb0, u8>::from(class_hash.as_bytes().to_vec());
+    let leaf = <PoseidonHasher as CryptoHasher>::hash(
+        FieldElement::from_byte_slice_be(b"CONTRACT_CLASS_LEAF_V0").unwrap(),
+        FieldElement::from_byte_slice_be(compiled_class_hash.as_bytes()).unwrap(),
+    );
+    // Class hashes are 251 bits felts.
+    stored_tree::set::<PoseidonHasher, S>(storage, class_commitment, &key[5..], leaf)
+}
+
+/// Calculate the class commitment of a set of classes.
+///
+/// See [`update_class_commitment`].
+///
+/// # Arguments
+///
+/// * `classes` - The class hashes of the declared classes, along with their compiled class hash.
+///
+/// # Returns
+///
+/// The merkle root of the merkle tree built from the classes, zero if there are none.
+pub fn calculate_class_commitment(classes: &[(H256, H256)]) -> H256 {
+    let mut storage = BTreeMap::new();
+    classes.iter().fold(H256::zero(), |class_commitment, (class_hash, compiled_class_hash)| {
+        // Nodes are only missing from a storage they were never written to.
+        update_class_commitment(&mut storage, class_commitment, *class_hash, *compiled_class_hash).unwrap()
+    })
+}
+
+/// Calculate the global state root.
+///
+/// The state root is the Poseidon hash of both the contracts and the classes trees roots, unless no
+/// Cairo 1 class has been declared yet, in which case it is the contracts tree root.
+///
+/// # Arguments
+///
+/// * `contracts_root` - The root of the contracts tree.
+/// * `class_commitment` - The root of the classes tree.
+///
+/// # Returns
+///
+/// The global state root.
+pub fn calculate_state_root(contracts_root: H256, class_commitment: H256) -> H256 {
+    if class_commitment.is_zero() {
+        return contracts_root;
+    }
+
+    let state_version = FieldElement::from_byte_slice_be(b"STARKNET_STATE_V0").unwrap();
+    let state_root = PoseidonHasher::compute_hash_on_elements(&[
+        state_version,
+        FieldElement::from_byte_slice_be(contracts_root.as_bytes()).unwrap(),
+        FieldElement::from_byte_slice_be(class_commitment.as_bytes()).unwrap(),
+    ]);
+    H256::from_slice(&state_root.to_bytes_be())
+}
+
 /// Compute the combined hash of the transaction hash and the signature.
 ///
 /// Since the transaction hash doesn't take the signature values as its input
@@ -122,12 +204,12 @@ where
     T: CryptoHasher,
 {
     let signature_hash = <T as CryptoHasher>::compute_hash_on_elements(
-        &tx.signature
+        &tx.signature()
             .iter()
             .map(|elt| FieldElement::from_byte_slice_be(elt.as_bytes()).unwrap())
             .collect::<Vec<FieldElement>>(),
     );
-    <T as CryptoHasher>::hash(FieldElement::from_byte_slice_be(tx.hash.as_bytes()).unwrap(), signature_hash)
+    <T as CryptoHasher>::hash(FieldElement::from_byte_slice_be(tx.hash().as_bytes()).unwrap(), signature_hash)
 }
 
 /// Calculate the hash of an event.
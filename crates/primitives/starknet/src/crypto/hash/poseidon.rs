@@ -6,6 +6,11 @@ use starknet_crypto::FieldElement;
 
 use crate::traits::hash::CryptoHasher;
 
+/// Number of full rounds of the Hades permutation, half of them before the partial rounds.
+const FULL_ROUNDS: usize = 8;
+/// Number of partial rounds of the Hades permutation.
+const PARTIAL_ROUNDS: usize = 83;
+
 /// The poseidon hasher.
 #[derive(Default)]
 pub struct PoseidonHasher;
@@ -18,12 +23,513 @@ pub fn hash(_data: &[u8]) -> [u8; 32] {
 }
 
 /// The poseidon CryptoHasher implementation.
+///
+/// This is the Poseidon hash of Starknet, based on the Hades permutation over a state of 3
+/// elements, see the [reference implementation](https://github.com/starkware-industries/poseidon).
 impl CryptoHasher for PoseidonHasher {
+    /// Hashes 2 elements, by permuting the state `[a, b, 2]`.
     fn hash(a: FieldElement, b: FieldElement) -> FieldElement {
-        let input = felts_from_u8s::<GF>(&[a.to_bytes_be(), b.to_bytes_be()].concat());
-        FieldElement::from_byte_slice_be(&u8s_from_felts(&poseidon_hash::hash_sw8(&input))).unwrap()
+        let mut state = [a, b, FieldElement::TWO];
+        permute(&mut state);
+        state[0]
     }
-    fn compute_hash_on_elements(_elements: &[FieldElement]) -> FieldElement {
-        todo!()
+
+    /// Hashes an array, by absorbing its elements 2 by 2, once padded with a 1.
+    fn compute_hash_on_elements(elements: &[FieldElement]) -> FieldElement {
+        let mut state = [FieldElement::ZERO; 3];
+        let mut chunks = elements.chunks_exact(2);
+        for chunk in chunks.by_ref() {
+            state[0] += chunk[0];
+            state[1] += chunk[1];
+            permute(&mut state);
+        }
+
+        let remainder = chunks.remainder();
+        if let [last] = remainder {
+            state[0] += *last;
+        }
+        state[remainder.len()] += FieldElement::ONE;
+        permute(&mut state);
+        state[0]
     }
 }
+
+/// The Hades permutation.
+fn permute(state: &mut [FieldElement; 3]) {
+    for (round, round_keys) in ROUND_KEYS.iter().enumerate() {
+        state.iter_mut().zip(round_keys).for_each(|(element, round_key)| *element += *round_key);
+
+        let full_round = round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + PARTIAL_ROUNDS;
+        if full_round {
+            state.iter_mut().for_each(|element| *element = *element * *element * *element);
+        } else {
+            state[2] = state[2] * state[2] * state[2];
+        }
+
+        // Multiply by the MDS matrix ((3, 1, 1), (1, -1, 1), (1, 1, -2)).
+        let sum = state[0] + state[1] + state[2];
+        state[0] = sum + state[0] + state[0];
+        state[1] = sum - state[1] - state[1];
+        state[2] = sum - state[2] - state[2] - state[2];
+    }
+}
+
+/// Round keys of the Hades permutation, in Montgomery form.
+/// Taken from the [reference parameters](https://github.com/starkware-industries/poseidon/blob/5403dff9ff4eadb07deb5c0a43e88bedb011deb8/poseidon3.txt).
+const ROUND_KEYS: [[FieldElement; 3]; FULL_ROUNDS + PARTIAL_ROUNDS] = [
+    [
+        FieldElement::from_mont([9243643933561577962, 18087611126302680282, 7098098609281275249, 289757450055368158]),
+        FieldElement::from_mont([7853535095351734343, 10850711010770646915, 12570458558381957509, 325478872510666341]),
+        FieldElement::from_mont([14487852855283887204, 5186719240138179095, 8414109922749085282, 400708606768273790]),
+    ],
+    [
+        FieldElement::from_mont([3280395077118373336, 17795901222489795490, 2641423859640672268, 77297546474262716]),
+        FieldElement::from_mont([8106789448329946608, 5622576456418619364, 917099606739132255, 531983052745549445]),
+        FieldElement::from_mont([595196979161159364, 13104152156623721627, 11543764507410437138, 296030771607252997]),
+    ],
+    [
+        FieldElement::from_mont([7816701695378368721, 3357934764826603292, 15403608342128707689, 450788015873872442]),
+        FieldElement::from_mont([636799349004279181, 6138586124645004146, 9427309254075631876, 241320328808721095]),
+        FieldElement::from_mont([17291774529514094747, 5039440405858006990, 9298428421172181112, 506951390701988816]),
+    ],
+    [
+        FieldElement::from_mont([11806696651894481589, 1092083221520939347, 14414850501582138681, 221101813663738384]),
+        FieldElement::from_mont([13829429898061010046, 2611422711440939737, 15408727842397286736, 507284792967663799]),
+        FieldElement::from_mont([13083162048600272883, 8232965923506882345, 10342036084285918913, 4246917344608281]),
+    ],
+    [
+        FieldElement::from_mont([12470629514406685798, 11302495035548825343, 9276430095037402877, 289513206250152026]),
+        FieldElement::from_mont([16166443317206696620, 2092411839389224988, 10545029823922571239, 201269616786125957]),
+        FieldElement::from_mont([14120941658239655251, 2642188447246846335, 6420128711794491484, 387738584202674747]),
+    ],
+    [
+        FieldElement::from_mont([18142151589019894558, 18197850390380265386, 4500773026039479439, 23213895613443163]),
+        FieldElement::from_mont([1838259232375511617, 13437586145592764213, 3386373123436376988, 31300941339470903]),
+        FieldElement::from_mont([12813930444938617062, 17977170971205650778, 6646575222215583790, 445490330467511246]),
+    ],
+    [
+        FieldElement::from_mont([5418881224361403044, 16174271179784024375, 12913838151736640996, 457118832410542089]),
+        FieldElement::from_mont([17078278631468097978, 5613598547500774665, 253967247173275360, 205056122424254725]),
+        FieldElement::from_mont([5814218018525477564, 7042976932945610936, 6129097243416103635, 153409631076214267]),
+    ],
+    [
+        FieldElement::from_mont([3621258840658417782, 12082996430020286330, 8786110107754261088, 90013825019418590]),
+        FieldElement::from_mont([11767630116619037246, 5457091587212503719, 13061643383105893757, 207441147871509021]),
+        FieldElement::from_mont([3487618752862434321, 4393828912560793107, 16800785065617987148, 347818059470414293]),
+    ],
+    [
+        FieldElement::from_mont([10036894774740968568, 14447752012337165757, 16694991251528956953, 2323412363062540]),
+        FieldElement::from_mont([5960826520244257928, 43312879824374923, 20038618335817578, 335079323593385570]),
+        FieldElement::from_mont([13622430211924427312, 14945543747766687960, 5856453032098082253, 236906538664583066]),
+    ],
+    [
+        FieldElement::from_mont([16625649048788753279, 2130521830221044752, 1661930858801910911, 56969323984845557]),
+        FieldElement::from_mont([15505038582876040395, 17639260342879331015, 2183401479553931772, 385840018231776758]),
+        FieldElement::from_mont([3740795237722334731, 11315915102616095896, 8229341393425289001, 357339243166699343]),
+    ],
+    [
+        FieldElement::from_mont([5924510277243062862, 6138973384706390680, 9645268283643827904, 178807732439751035]),
+        FieldElement::from_mont([9858101512657070363, 8490712007598103186, 6358034055188805020, 86588609536002708]),
+        FieldElement::from_mont([12627573865611286226, 10584824910125248359, 5695002620475979027, 553734518116473322]),
+    ],
+    [
+        FieldElement::from_mont([2931475291088863755, 4950661838749710683, 17685151681051487615, 80672772213984379]),
+        FieldElement::from_mont([10034130411897787747, 5775510134867581262, 4888583252419498077, 384095793253830152]),
+        FieldElement::from_mont([12061382206834670118, 1812997140763906163, 1358975308838102853, 325008645899233349]),
+    ],
+    [
+        FieldElement::from_mont([98131090428155973, 12371598924866373366, 6856771589523868900, 437037944810509358]),
+        FieldElement::from_mont([2910886327317613519, 2885335970393840241, 12759548110436230790, 433980369154792567]),
+        FieldElement::from_mont([382459515727850142, 18215759780714320276, 10457230626188534960, 232745075428709288]),
+    ],
+    [
+        FieldElement::from_mont([11661884834875533326, 7733517268986148244, 3054230322673354266, 252329460231842324]),
+        FieldElement::from_mont([17071763598481838848, 15762111567114567007, 8812714459082605378, 85407612200477901]),
+        FieldElement::from_mont([16641766085230847646, 5966673110423622783, 4947646614868958216, 68912480733322045]),
+    ],
+    [
+        FieldElement::from_mont([9966794478050154826, 6236502114006974258, 15744706332152407321, 171885842566541973]),
+        FieldElement::from_mont([16783334901488089568, 1418142957718768917, 18144651096854070636, 136382714675630519]),
+        FieldElement::from_mont([2280479837291324924, 6142787706721617013, 6741100840340932495, 7730124893487296]),
+    ],
+    [
+        FieldElement::from_mont([5662564296992189264, 12323910786559522843, 11330907770403920137, 472706269507190623]),
+        FieldElement::from_mont([10348370643984481549, 10441892284960847054, 13828857006943257227, 464708198236044197]),
+        FieldElement::from_mont([7828020300912839125, 4627556079715571566, 8137227470341311645, 561022225697749167]),
+    ],
+    [
+        FieldElement::from_mont([6450769989309954531, 10735664482809146967, 2759699212699883002, 184475822321797588]),
+        FieldElement::from_mont([10151963221694466762, 11737811890036264082, 12928732865256414922, 122673335563350098]),
+        FieldElement::from_mont([11649812253943218272, 2756983657005049784, 15032843187652642399, 16259394022458264]),
+    ],
+    [
+        FieldElement::from_mont([5917398918060587122, 7232317890528840477, 16575042009257628756, 369791269318663127]),
+        FieldElement::from_mont([1130599927973592653, 3675560495460478617, 1321408996081962945, 346337827445743303]),
+        FieldElement::from_mont([10573723033409266305, 17652340351714110151, 587113430180876444, 318982746210690295]),
+    ],
+    [
+        FieldElement::from_mont([694567565438575311, 4848923220894621729, 106681442511651112, 372687875689683268]),
+        FieldElement::from_mont([15443419490163522690, 10897567445004415472, 212606457026625429, 443848836501452446]),
+        FieldElement::from_mont([9255279707050345472, 3810202389724143321, 15562975321214633849, 281543309394282327]),
+    ],
+    [
+        FieldElement::from_mont([14241066555641534463, 14360274740802034914, 16909619606238886487, 222963640283837]),
+        FieldElement::from_mont([9027017268112047813, 15675552638768809223, 15785367114775570304, 445483856924749789]),
+        FieldElement::from_mont([1753606436615532053, 5118032848943075336, 2930777517237367780, 323451535495983210]),
+    ],
+    [
+        FieldElement::from_mont([1220507945438388727, 16121125489114934099, 12880548528372619893, 91647501707847021]),
+        FieldElement::from_mont([4210021861854799973, 12237573586525281162, 2407798956592841668, 535059045235169005]),
+        FieldElement::from_mont([3199209452152258109, 9166837679814583546, 10567257519629507456, 456436008654128175]),
+    ],
+    [
+        FieldElement::from_mont([11389661447543156587, 1249480464945703428, 16640286025029032595, 511672477256422920]),
+        FieldElement::from_mont([16451742664310166053, 10936132758902482032, 14381808837727847026, 372963390788028590]),
+        FieldElement::from_mont([15879660055855356867, 13121887367167835179, 13506406504010265091, 301623074795931116]),
+    ],
+    [
+        FieldElement::from_mont([15257233821555234430, 11539066278057066276, 28444465594858214, 238004290357816453]),
+        FieldElement::from_mont([12712154365081127549, 11123252554362246264, 2152062210323144334, 262899638470815492]),
+        FieldElement::from_mont([3952761873624637487, 1271740740409975614, 12432427856260670785, 367366616976454370]),
+    ],
+    [
+        FieldElement::from_mont([14219477457998157870, 15021557515349718131, 507195473179523133, 129411977989615478]),
+        FieldElement::from_mont([17280902353146833858, 2999914325484157345, 14943039664497463419, 405364884182781659]),
+        FieldElement::from_mont([17220497941480924621, 11522374472694419035, 17224579165090884215, 230162403690722123]),
+    ],
+    [
+        FieldElement::from_mont([13511260381888202429, 3985985692287721053, 4524267081568873300, 117407263344555941]),
+        FieldElement::from_mont([10441599497804339171, 5172385405230510145, 2415596470878253773, 138194416453200068]),
+        FieldElement::from_mont([13735968927903656169, 18126254709673743820, 10959129518992871218, 184162338357233379]),
+    ],
+    [
+        FieldElement::from_mont([12916309517476252882, 1696933074240553835, 4526758642305133773, 127104158529090720]),
+        FieldElement::from_mont([2239320242760746631, 121452074127639725, 895574447040759232, 264691874619716963]),
+        FieldElement::from_mont([3475623289312658257, 1096960833728598856, 8804274941892932346, 203882308486234950]),
+    ],
+    [
+        FieldElement::from_mont([5763708159717203935, 10107279324344686038, 3520571075465617912, 75474209487736059]),
+        FieldElement::from_mont([13437865057549340872, 16772602888863074470, 17969072883014248134, 333621891659790290]),
+        FieldElement::from_mont([9976135943560925106, 10343160667427265135, 5350226558525887514, 175633075986136369]),
+    ],
+    [
+        FieldElement::from_mont([16065313423854703598, 8922863354427797605, 10736741700834557591, 212280808865651711]),
+        FieldElement::from_mont([9628484483978363287, 14756970792815856621, 7492767253232214005, 105682057175198623]),
+        FieldElement::from_mont([1283565399751327009, 2317831505664921827, 14224565248054070754, 15711294832303844]),
+    ],
+    [
+        FieldElement::from_mont([6774690227711805782, 4593136531984929973, 15304232766842294799, 259976148485310661]),
+        FieldElement::from_mont([9431558283045504015, 5059464296395726729, 11686525508255427513, 507712217032793948]),
+        FieldElement::from_mont([5450710947610868578, 16985198694762833600, 16982681478526288690, 346602563147307904]),
+    ],
+    [
+        FieldElement::from_mont([8180586643620490953, 3732141722651764749, 8562982858084334634, 574067357162520131]),
+        FieldElement::from_mont([4768814850475014387, 12583391929613551795, 17603909456381554875, 262897150362712591]),
+        FieldElement::from_mont([15652157044699110783, 540500330140458895, 8155577718315815332, 522291900318331222]),
+    ],
+    [
+        FieldElement::from_mont([9505101400683879614, 8418460852130542255, 11492440793196557199, 42767569179570522]),
+        FieldElement::from_mont([15796017403586904935, 12320009402381537209, 3147269646682430256, 47484736814779013]),
+        FieldElement::from_mont([10625387152017010272, 6698829187263523257, 8270508076728751323, 293613612664818985]),
+    ],
+    [
+        FieldElement::from_mont([7467526848810760068, 6790954598471937195, 5851178566612248036, 466459380716648300]),
+        FieldElement::from_mont([16344200684843737577, 10025317819882421423, 6589635439432960520, 121822969737023836]),
+        FieldElement::from_mont([194359357646108738, 14360574475951991257, 2492473172614854715, 574882744316070429]),
+    ],
+    [
+        FieldElement::from_mont([5913414406641203280, 7739954172363375664, 13098067249364461163, 412209670247779044]),
+        FieldElement::from_mont([10675962413298071111, 12998465291495583170, 1793691249262985271, 41906165726725363]),
+        FieldElement::from_mont([9413193215135054093, 3621060248857304144, 11104845081589815582, 518269812996658296]),
+    ],
+    [
+        FieldElement::from_mont([14682393657045544346, 3206668679389744673, 18189756197391249352, 282955699473847050]),
+        FieldElement::from_mont([5673579451045958235, 14323147522548278724, 13329320490312383330, 368646373015362336]),
+        FieldElement::from_mont([14658573910080958313, 5155714625767958575, 3832940678215502312, 140761262101177673]),
+    ],
+    [
+        FieldElement::from_mont([671214552037953183, 1071989251289953873, 1302191527752233698, 368924673323318250]),
+        FieldElement::from_mont([17108856855014376567, 10804254597185352189, 4518109278284719242, 443920566784156010]),
+        FieldElement::from_mont([16666002652026897300, 5223131328531940320, 6054471777761264703, 109889304199357811]),
+    ],
+    [
+        FieldElement::from_mont([13688957432298760435, 3999083250985800745, 8252992961778748251, 235053900766664463]),
+        FieldElement::from_mont([365880965722516970, 15081421774124621973, 16343121997975980854, 29955342927431686]),
+        FieldElement::from_mont([16899435230639161101, 3158868278582774715, 5244510053518578676, 95972606678094074]),
+    ],
+    [
+        FieldElement::from_mont([1967959428825266385, 8703098212433075932, 6235701767168408553, 411796525193161576]),
+        FieldElement::from_mont([6729950135918348341, 17690291226187128917, 13281868309179723625, 167294440537751562]),
+        FieldElement::from_mont([18022027562018974640, 12993057213284741284, 9923185695053586245, 73563351826920892]),
+    ],
+    [
+        FieldElement::from_mont([3771451762216844820, 6147094128115469240, 17844190274102200058, 157325986213079243]),
+        FieldElement::from_mont([14635632501677776704, 12126948033841760440, 18087167829058278469, 191109737576041988]),
+        FieldElement::from_mont([14698366544192221048, 1398779694081492049, 14235651390956821459, 516519255958440552]),
+    ],
+    [
+        FieldElement::from_mont([13674108626446892464, 15599900192497890810, 4283960126347276737, 36942721919908996]),
+        FieldElement::from_mont([5136185149403403029, 10051453089041974488, 7536467473250370783, 118464172430875737]),
+        FieldElement::from_mont([14824585924995933131, 1025130670205119278, 15885293834181295602, 174017042473350164]),
+    ],
+    [
+        FieldElement::from_mont([4549473014611156816, 7051987822128093496, 3621193096527481221, 52146944185993804]),
+        FieldElement::from_mont([9615119457124684041, 1766286969535144833, 3861638681467201202, 144160810350363980]),
+        FieldElement::from_mont([16296565919815573742, 7266390308734580450, 6498516124696965, 466272861621407415]),
+    ],
+    [
+        FieldElement::from_mont([14795661418972635403, 3867722184688057781, 1593175760582589758, 155537957209349658]),
+        FieldElement::from_mont([17374926327279318676, 8450762771533580189, 11647881084062829008, 359075112160431896]),
+        FieldElement::from_mont([3952267634943387511, 18273219049487806599, 2837001023435642234, 79315718098556040]),
+    ],
+    [
+        FieldElement::from_mont([12785114459240408914, 14524324261061498375, 12352729326873903595, 533835425125943035]),
+        FieldElement::from_mont([9399238559015968456, 16077415979374375964, 4936075205519443722, 330882821000208554]),
+        FieldElement::from_mont([5922534844053544509, 11377829663447829309, 10708861064549580725, 127189537926441095]),
+    ],
+    [
+        FieldElement::from_mont([1542468872688505845, 12998376756653479204, 8268922752673285206, 229769434068950680]),
+        FieldElement::from_mont([13697510688748722492, 4357505265339292183, 17775049423638537181, 351632502172644028]),
+        FieldElement::from_mont([17859923845810670260, 4761272911086364078, 14144345341329407734, 13685178109011985]),
+    ],
+    [
+        FieldElement::from_mont([2016387714589987168, 16865770661414275939, 5760227484228619972, 441677710671385240]),
+        FieldElement::from_mont([7381654481617977639, 2767372695669885599, 9365073120152143298, 193749212209155719]),
+        FieldElement::from_mont([483834899071509658, 6753969812386528377, 6477736014779319739, 135594929862308026]),
+    ],
+    [
+        FieldElement::from_mont([12282289573789368024, 1650686597669311624, 11688428260336834728, 156292665806798687]),
+        FieldElement::from_mont([10830195181116156236, 368491741730793517, 11350707945797197889, 22146187393010388]),
+        FieldElement::from_mont([18282324130413285481, 5088950285177334624, 16365222141034539649, 259474693797608614]),
+    ],
+    [
+        FieldElement::from_mont([8827674482481052492, 8336920033462100582, 10249994794842643667, 277690658111897282]),
+        FieldElement::from_mont([16356299955034569089, 1675657038258772929, 5902389612924319538, 43392401955387236]),
+        FieldElement::from_mont([15845601752249440971, 3780941445184113386, 10813740608149537107, 519347000717593935]),
+    ],
+    [
+        FieldElement::from_mont([2174547237618214440, 3888610038497888648, 12058613223199738737, 46026655772355901]),
+        FieldElement::from_mont([7701527517587774029, 18277737638841850362, 10084160593139748624, 309301010712522764]),
+        FieldElement::from_mont([6869776444395985097, 5651176936010286604, 15413446924009862812, 148038614359358627]),
+    ],
+    [
+        FieldElement::from_mont([16908456970982198626, 12176179683804089147, 16383444957614582931, 523922989624504394]),
+        FieldElement::from_mont([3290972596255324553, 17832004154531288848, 11974506494394393594, 343556461443007504]),
+        FieldElement::from_mont([7239759138190695875, 17847573380537910419, 4217345650781339721, 384767538133486964]),
+    ],
+    [
+        FieldElement::from_mont([11363081758793359037, 1538202371766688453, 12356663841790996780, 34683732629995374]),
+        FieldElement::from_mont([602541009637641467, 12028186305001330096, 15649414753494369721, 534218602856218987]),
+        FieldElement::from_mont([12725708872687997712, 12062638025074172161, 11245701981179406640, 169131909501989601]),
+    ],
+    [
+        FieldElement::from_mont([10129367354009920302, 5798730398129943257, 4423843782231757368, 365392174823474876]),
+        FieldElement::from_mont([1982882245354490693, 11084652031010980503, 10525109925140014954, 520218928750522715]),
+        FieldElement::from_mont([10017828728568554790, 16329225100909853372, 15090547860639171115, 177536262428550502]),
+    ],
+    [
+        FieldElement::from_mont([9109221190405550030, 4049568458506600243, 2280175761588753271, 435271072040449778]),
+        FieldElement::from_mont([2755671182507801172, 4883595981796246933, 6952589421966203571, 262295952787963414]),
+        FieldElement::from_mont([3596076898041113913, 5385785661919193143, 13405783579242341568, 547603442501273702]),
+    ],
+    [
+        FieldElement::from_mont([7287641131240009777, 9390092283827195665, 5641648717251136304, 257003162210699897]),
+        FieldElement::from_mont([17313988825139269443, 9017656332904672146, 14768799241978249779, 144804555361059993]),
+        FieldElement::from_mont([13984803796500321616, 17295086621630434529, 6961386698856047567, 533506485143227926]),
+    ],
+    [
+        FieldElement::from_mont([166130831926558944, 8390472735035131144, 1404764097597084215, 341310501741874702]),
+        FieldElement::from_mont([4807461537976364316, 12676686855922562112, 3867534794508858420, 1449027545774226]),
+        FieldElement::from_mont([15318032989650434154, 3764978904578152459, 4156313804118421767, 482040114912200975]),
+    ],
+    [
+        FieldElement::from_mont([16114547424758744941, 11552162914699739008, 13705988916932600735, 405660060333774211]),
+        FieldElement::from_mont([15595869952999912302, 1579788066055795645, 17884812916678818026, 234099766686034307]),
+        FieldElement::from_mont([3393341145889905335, 10355991408151799968, 2869939604265971084, 173489605006510207]),
+    ],
+    [
+        FieldElement::from_mont([11849695269868512352, 9636639390910709969, 8019312937199110287, 501531952459342655]),
+        FieldElement::from_mont([678246973693536444, 7054781922312147680, 15199462449284392407, 460663492765147686]),
+        FieldElement::from_mont([5624945513829384805, 4434583665866524014, 16729459642231630975, 296631600580250135]),
+    ],
+    [
+        FieldElement::from_mont([8672004448492933583, 1058310446984674133, 264570260947964559, 242359660064357356]),
+        FieldElement::from_mont([10382204353365036621, 2827655840675476862, 6730710717146338320, 150157130408939961]),
+        FieldElement::from_mont([11983031424045460845, 9592919725278497428, 9142944038187373072, 256234660666117289]),
+    ],
+    [
+        FieldElement::from_mont([14968349090973164094, 5419666962283422864, 18055192223787940595, 153390748618123536]),
+        FieldElement::from_mont([9231753623233178344, 10622340418603097152, 754736792351814033, 232372827146332453]),
+        FieldElement::from_mont([2249736877624875174, 4090971167464076917, 13093982244263237726, 69167824949536347]),
+    ],
+    [
+        FieldElement::from_mont([4378964301990265144, 632783657867059159, 11904902468850752065, 285757917745474584]),
+        FieldElement::from_mont([17168748738802681396, 11927980996573092371, 9497312389774801468, 321483309107307737]),
+        FieldElement::from_mont([652409577381086658, 17641412120439311749, 8500521706192993490, 486907246913793993]),
+    ],
+    [
+        FieldElement::from_mont([3446130578466826431, 7613754890610093701, 6403413440524208172, 569592423507950792]),
+        FieldElement::from_mont([3044081565734964564, 3865275935881176942, 2332417978277374561, 74890784739250502]),
+        FieldElement::from_mont([10458137626319960156, 5929838947546650877, 1481646457429938810, 15169389376246756]),
+    ],
+    [
+        FieldElement::from_mont([7259662225174182181, 16615611613695658747, 1220771128020305316, 510755175561474471]),
+        FieldElement::from_mont([10109433424197684523, 13649968104218876508, 3350476798240242641, 436176335421703831]),
+        FieldElement::from_mont([1821236595970751010, 3380021379563395171, 719990051814839644, 299985982692985656]),
+    ],
+    [
+        FieldElement::from_mont([6270591434209406749, 1182050722270607898, 1521578171716836786, 555008862924182924]),
+        FieldElement::from_mont([11515158015829746991, 17161082289259031143, 1967222090847465487, 37154906459316789]),
+        FieldElement::from_mont([18037350206112904103, 17273261944010844240, 8326393842640711968, 54818938745380866]),
+    ],
+    [
+        FieldElement::from_mont([229562820392749086, 7439361039024169775, 14545205477667761110, 358245446506814198]),
+        FieldElement::from_mont([5920647420963798645, 17098611964640994155, 18373751783678512664, 240965556087994163]),
+        FieldElement::from_mont([15533473020582886837, 10172861913527423843, 10557624096066929857, 390124072337327997]),
+    ],
+    [
+        FieldElement::from_mont([12748801730702061417, 13502184008775686336, 3359684115222490618, 232754272961168280]),
+        FieldElement::from_mont([7537867748682235809, 7327874451836783011, 3875857186699108158, 345559852794881702]),
+        FieldElement::from_mont([16668965774095451611, 11032469429287231438, 8044278319585677306, 95226641905438839]),
+    ],
+    [
+        FieldElement::from_mont([8295508544839266292, 6995211328678714527, 12834875627263724097, 490674406961459214]),
+        FieldElement::from_mont([17988053398470112910, 937499345423342005, 5616389451571495600, 237654629898209945]),
+        FieldElement::from_mont([1287023791300845810, 5305104781084393738, 12196169755825310779, 34869973311696677]),
+    ],
+    [
+        FieldElement::from_mont([11559906404265275151, 3282226148749756179, 514847877834119377, 83754859751757636]),
+        FieldElement::from_mont([7056293227217971677, 11123141461050210891, 4579732554730649822, 24925216422677013]),
+        FieldElement::from_mont([13703727340874451106, 14812265667793742134, 12923134548423753900, 450201055219742108]),
+    ],
+    [
+        FieldElement::from_mont([11712248759041614411, 18399014759094382415, 3830819649839110399, 535917848965742150]),
+        FieldElement::from_mont([14006347300274563213, 6268277384359491340, 10689843199064458940, 313796147833295757]),
+        FieldElement::from_mont([16428607906545714863, 12088953414208729315, 7984330819553914809, 136826825300813107]),
+    ],
+    [
+        FieldElement::from_mont([6636097187655146281, 10152535093158357636, 7277228304987477017, 113595476284179471]),
+        FieldElement::from_mont([5232985009820433478, 5774321331312362915, 5423121295435421627, 160915241946687079]),
+        FieldElement::from_mont([4496959753982009434, 15500193298935012020, 10120221729864538276, 354288653465438081]),
+    ],
+    [
+        FieldElement::from_mont([14130444755329568960, 4340724018212173175, 13011083046695550138, 499857056360322205]),
+        FieldElement::from_mont([12854995939248460318, 17605962662771524090, 3172105057940070877, 296063064506052201]),
+        FieldElement::from_mont([13772579382122603298, 1255952031497517795, 9236680924634861611, 45806006878932358]),
+    ],
+    [
+        FieldElement::from_mont([4686671200252243902, 8542877423138505171, 12197302579342482602, 557777498653903166]),
+        FieldElement::from_mont([7277709090087877637, 14880244296641419947, 4352283273105313283, 394090286800675927]),
+        FieldElement::from_mont([17004157737639364375, 664010727200509874, 3160293584174281108, 355193570835157020]),
+    ],
+    [
+        FieldElement::from_mont([16935418345650648126, 7613167816327614478, 8567685140361592023, 192374366945504411]),
+        FieldElement::from_mont([12010033705867698193, 9911931307120365776, 2840746018765845351, 155793935178962665]),
+        FieldElement::from_mont([4675525881554209326, 43955020525153538, 879390751628762040, 30050049649298410]),
+    ],
+    [
+        FieldElement::from_mont([14763861015356018947, 11138049896738905620, 7434359509084898001, 306707311234692650]),
+        FieldElement::from_mont([3652730524671619961, 15507906158506232036, 4378490555475949567, 147015519435233783]),
+        FieldElement::from_mont([9565388440459623416, 11824165362213114222, 1157647017610980705, 515762187079244097]),
+    ],
+    [
+        FieldElement::from_mont([5108170949950554224, 17585036634678630688, 5150179446237673988, 338920772529659903]),
+        FieldElement::from_mont([5152186350648890968, 3922757248870090445, 7461050806730497397, 493611869339498435]),
+        FieldElement::from_mont([6218803144184342379, 16183463675627846150, 12278938470191944973, 399271978177320508]),
+    ],
+    [
+        FieldElement::from_mont([11005377099851853450, 2831926306444287813, 4059558588458403468, 300800574280693080]),
+        FieldElement::from_mont([7445409638723095563, 265802036493141624, 10558145401858148334, 288297773436989845]),
+        FieldElement::from_mont([16919190997079720172, 16776070026357537291, 7912560300847447864, 181774342880683613]),
+    ],
+    [
+        FieldElement::from_mont([11195540055256250799, 16819487910159083746, 9975109723100256395, 202788216477290940]),
+        FieldElement::from_mont([8466713286045287725, 4607944321748390537, 9300117664978237270, 450062282204341714]),
+        FieldElement::from_mont([18443275231826871851, 9678273456565718972, 14528552031275983357, 513689494047832231]),
+    ],
+    [
+        FieldElement::from_mont([5162148167719785113, 11966168159704074900, 16490919999727373101, 202592981963712373]),
+        FieldElement::from_mont([12752001372995037554, 17925161161452029786, 17886757071446121676, 572735978096291197]),
+        FieldElement::from_mont([1381443859226626795, 7908596123384766303, 12064497781588284550, 402210157903437256]),
+    ],
+    [
+        FieldElement::from_mont([14145153517970630206, 4854438977203639714, 6841483682199428043, 568626501235827564]),
+        FieldElement::from_mont([415538704391262515, 9831116672973431367, 8773780710868024839, 465020318139580990]),
+        FieldElement::from_mont([8741245710184839066, 12287529218991458543, 6512331354034335542, 568819884731599714]),
+    ],
+    [
+        FieldElement::from_mont([6576257762347310175, 14407027214273489669, 631972080005108585, 103824094409213189]),
+        FieldElement::from_mont([9739264977475747449, 11452901692634999211, 1285658319211162052, 37865852095218957]),
+        FieldElement::from_mont([12708577751926938347, 9838420218064308734, 4645560983073197788, 117300033201533093]),
+    ],
+    [
+        FieldElement::from_mont([10340605832584996457, 10327258726419813791, 4585195392450604564, 456334602465038816]),
+        FieldElement::from_mont([5685179536281940376, 11613171744980913999, 18206405740345773345, 162692247713644543]),
+        FieldElement::from_mont([18187295423331185406, 4268538121951370198, 9546077166122716375, 10985307615063265]),
+    ],
+    [
+        FieldElement::from_mont([11434482562158854411, 10626175221057332717, 16052333187117450564, 474726979904301378]),
+        FieldElement::from_mont([5873271278051649912, 12272204777195619373, 14785522015871603562, 71852268263214099]),
+        FieldElement::from_mont([8587556261918152146, 13716785079272395888, 14033754587811462641, 52850404976022002]),
+    ],
+    [
+        FieldElement::from_mont([14442139634836722923, 11699440148720761671, 12758699270674665425, 121230769999599694]),
+        FieldElement::from_mont([3273049629148369887, 10901881891612794226, 10528702443911439852, 10640284910742920]),
+        FieldElement::from_mont([9096395035438932405, 3887644788828845061, 15183247642509092762, 371586460226400242]),
+    ],
+    [
+        FieldElement::from_mont([16766874731960759497, 13552940411795194818, 18113732853902850544, 195397983206643637]),
+        FieldElement::from_mont([10307483231053604630, 17101548271890006464, 5660959860684552279, 101529132856042224]),
+        FieldElement::from_mont([9247582159232463012, 327759987507192653, 13488341924391373166, 188939756384540188]),
+    ],
+    [
+        FieldElement::from_mont([17654738950397886656, 15780578624859879199, 12622206575174474832, 259885944020951664]),
+        FieldElement::from_mont([8463981009991105359, 346359320216776298, 3316269322285653257, 543189611777386368]),
+        FieldElement::from_mont([15479539033684846828, 13615341394808247006, 12770455103933415367, 169982362594377731]),
+    ],
+    [
+        FieldElement::from_mont([4345661495913797744, 5345050480307289169, 6180664092263428286, 72257896509454227]),
+        FieldElement::from_mont([11749020169028114495, 13713129446909281065, 11072280278324747054, 227226543105719209]),
+        FieldElement::from_mont([9100848398382641903, 10127654860236492454, 2503435397901446107, 549328416637092229]),
+    ],
+    [
+        FieldElement::from_mont([17441055936827863455, 4390027723363715021, 13806048402067814361, 426641262622090767]),
+        FieldElement::from_mont([11151324947632018940, 13387831322309949400, 17877371599956786911, 222635500580825722]),
+        FieldElement::from_mont([4308792446042047252, 9814011755535927973, 7132741627881624595, 189458537203649383]),
+    ],
+    [
+        FieldElement::from_mont([7285832850349650494, 17804127068443869575, 4690148183129027249, 535768662934488649]),
+        FieldElement::from_mont([15355946349758297994, 9836493964199559350, 13196017000144281063, 276739602829330508]),
+        FieldElement::from_mont([4211935417666041638, 9484362610313964292, 10667447865584902753, 361454388986103785]),
+    ],
+    [
+        FieldElement::from_mont([5851795502418302370, 8675138985472200793, 5109604408299034185, 410982946566679184]),
+        FieldElement::from_mont([11403959178017110248, 214941014243668206, 3580375334991975872, 57076293394900549]),
+        FieldElement::from_mont([10232643185482799853, 11153125143374298128, 17107203032413522341, 120585785311247620]),
+    ],
+    [
+        FieldElement::from_mont([9289807536571775026, 7432122173429756764, 13322207248175842579, 375562273343761931]),
+        FieldElement::from_mont([10482726699966188244, 5915326032957608029, 17160665227327244351, 305821474868596732]),
+        FieldElement::from_mont([12147404814752058322, 6145338516208157252, 10939614838687866847, 244625522755757258]),
+    ],
+    [
+        FieldElement::from_mont([9210974568445330512, 14855398402534343929, 3003473643367441926, 312266549893448920]),
+        FieldElement::from_mont([2186160703848841441, 749453658643478731, 5040371420017443706, 92922707235184234]),
+        FieldElement::from_mont([8137097877804572510, 4194147702398564292, 10044512728407437189, 191677938447119138]),
+    ],
+    [
+        FieldElement::from_mont([15754679985287562033, 17144367048005459911, 8637536145542499282, 178770311623751184]),
+        FieldElement::from_mont([298970159691700591, 12014891315505021636, 5642896886612387367, 320523276021081874]),
+        FieldElement::from_mont([2161827243834369197, 6529000939326479156, 3360240309062894342, 423278556197684001]),
+    ],
+    [
+        FieldElement::from_mont([17922407737483631270, 10846304006776507478, 4814020835957029095, 558105788237772190]),
+        FieldElement::from_mont([14309821559636775821, 5191281667707819629, 7991629750293746597, 55044774064780458]),
+        FieldElement::from_mont([4475858853850722769, 4126326734531744171, 14548036495158561611, 569399000276545969]),
+    ],
+    [
+        FieldElement::from_mont([17137171742635080032, 13734979251366108230, 733658667004231380, 393850665517739074]),
+        FieldElement::from_mont([18207273213375707676, 9125832381882861274, 13182372482997690796, 374441752858986998]),
+        FieldElement::from_mont([11725146126629035967, 2224508228469132237, 1606247714594998930, 162358740541808928]),
+    ],
+];
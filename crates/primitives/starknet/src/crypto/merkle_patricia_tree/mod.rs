@@ -1,2 +1,3 @@
 pub mod merkle_node;
 pub mod merkle_tree;
+pub mod stored_tree;
@@ -0,0 +1,195 @@
+//! A Starknet binary Merkle-Patricia tree whose nodes are persisted one by one, by hash.
+//!
+//! Unlike [`MerkleTree`](super::merkle_tree::MerkleTree), which holds the whole tree in memory,
+//! inserting a leaf only reads and writes the nodes along its path, so that a tree which grows
+//! across blocks does not have to be rebuilt from all its leaves. Nodes are never deleted: the
+//! nodes of the previous roots stay in storage.
+use alloc::collections::BTreeMap;
+
+use bitvec::prelude::{BitSlice, BitVec, Msb0};
+use sp_core::H256;
+use starknet_crypto::FieldElement;
+
+use crate::traits::hash::CryptoHasher;
+
+/// A node of the tree, as persisted. Leaves are not persisted, as their hash is their value.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+pub enum StoredNode {
+    /// A branch node with exactly two children.
+    Binary {
+        /// Hash of the left child.
+        left: H256,
+        /// Hash of the right child.
+        right: H256,
+    },
+    /// A path connecting a node to its only child.
+    Edge {
+        /// Hash of the child.
+        child: H256,
+        /// The path, as a big-endian integer.
+        path: H256,
+        /// The length of the path, in bits.
+        length: u8,
+    },
+}
+
+/// Errors of the stored tree.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StoredTreeError {
+    /// A node of the path of the key is missing from the storage.
+    MissingNode(H256),
+}
+
+/// Storage of the nodes of a tree, by hash.
+pub trait NodeStorage {
+    /// Returns the node with the given hash, if any.
+    fn get(&self, hash: &H256) -> Option<StoredNode>;
+    /// Persists a node with its hash.
+    fn insert(&mut self, hash: H256, node: StoredNode);
+}
+
+/// In-memory storage, for transient trees.
+impl NodeStorage for BTreeMap<H256, StoredNode> {
+    fn get(&self, hash: &H256) -> Option<StoredNode> {
+        BTreeMap::get(self, hash).cloned()
+    }
+
+    fn insert(&mut self, hash: H256, node: StoredNode) {
+        BTreeMap::insert(self, hash, node);
+    }
+}
+
+/// Sets the value of a key in the tree.
+///
+/// # Arguments
+///
+/// * `storage` - The storage of the nodes of the tree.
+/// * `root` - The root of the tree, zero if it is empty.
+/// * `key` - The key to set, whose length is the height of the tree.
+/// * `value` - The value to set, which must not be zero.
+///
+/// # Returns
+///
+/// The new root of the tree.
+pub fn set<H: CryptoHasher, S: NodeStorage>(
+    storage: &mut S,
+    root: H256,
+    key: &BitSlice<Msb0, u8>,
+    value: FieldElement,
+) -> Result<H256, StoredTreeError> {
+    let root = if root.is_zero() { None } else { Some(root) };
+    let root = set_at::<H, S>(storage, root, 0, key, value)?;
+    Ok(to_h256(root))
+}
+
+/// Sets the value of a key in the subtree of a node, and returns the hash of the new subtree.
+fn set_at<H: CryptoHasher, S: NodeStorage>(
+    storage: &mut S,
+    node: Option<H256>,
+    height: usize,
+    key: &BitSlice<Msb0, u8>,
+    value: FieldElement,
+) -> Result<FieldElement, StoredTreeError> {
+    let node = match node {
+        Some(node) => node,
+        // The subtree is empty.
+        None => return Ok(edge_to_leaf::<H, S>(storage, &key[height..], value)),
+    };
+    // The node is the leaf of the key.
+    if height == key.len() {
+        return Ok(value);
+    }
+
+    match storage.get(&node).ok_or(StoredTreeError::MissingNode(node))? {
+        StoredNode::Binary { left, right } => {
+            let (left, right) = if key[height] {
+                (from_h256(left), set_at::<H, S>(storage, Some(right), height + 1, key, value)?)
+            } else {
+                (set_at::<H, S>(storage, Some(left), height + 1, key, value)?, from_h256(right))
+            };
+            Ok(store_binary::<H, S>(storage, left, right))
+        }
+        StoredNode::Edge { child, path, length } => {
+            let path_bits = BitVec::<Msb0, u8>::from(path.as_bytes().to_vec());
+            let path = &path_bits[256 - length as usize..];
+            let common = path.iter().zip(key[height..].iter()).take_while(|(a, b)| a == b).count();
+
+            // The key goes through the edge.
+            if common == path.len() {
+                let child = set_at::<H, S>(storage, Some(child), height + path.len(), key, value)?;
+                return Ok(store_edge::<H, S>(storage, child, path));
+            }
+
+            // The key leaves the edge, which is split by a binary node.
+            let branch_height = height + common;
+            let old_child = if common + 1 == path.len() {
+                from_h256(child)
+            } else {
+                store_edge::<H, S>(storage, from_h256(child), &path[common + 1..])
+            };
+            let new_child = edge_to_leaf::<H, S>(storage, &key[branch_height + 1..], value);
+            let (left, right) = if key[branch_height] { (old_child, new_child) } else { (new_child, old_child) };
+            let branch = store_binary::<H, S>(storage, left, right);
+
+            if common == 0 { Ok(branch) } else { Ok(store_edge::<H, S>(storage, branch, &path[..common])) }
+        }
+    }
+}
+
+/// Persists the edge from a node to a new leaf, if the node is not the leaf itself.
+fn edge_to_leaf<H: CryptoHasher, S: NodeStorage>(
+    storage: &mut S,
+    path: &BitSlice<Msb0, u8>,
+    value: FieldElement,
+) -> FieldElement {
+    if path.is_empty() { value } else { store_edge::<H, S>(storage, value, path) }
+}
+
+/// Persists a binary node and returns its hash.
+fn store_binary<H: CryptoHasher, S: NodeStorage>(
+    storage: &mut S,
+    left: FieldElement,
+    right: FieldElement,
+) -> FieldElement {
+    let hash = H::hash(left, right);
+    storage.insert(to_h256(hash), StoredNode::Binary { left: to_h256(left), right: to_h256(right) });
+    hash
+}
+
+/// Persists an edge node and returns its hash, `H(child, path) + length`.
+fn store_edge<H: CryptoHasher, S: NodeStorage>(
+    storage: &mut S,
+    child: FieldElement,
+    path: &BitSlice<Msb0, u8>,
+) -> FieldElement {
+    // Right align the path in 256 bits, to read it as an integer.
+    let mut path_bits = BitVec::<Msb0, u8>::repeat(false, 256 - path.len());
+    path_bits.extend_from_slice(path);
+    let path_bytes = path_bits.into_vec();
+
+    // Safe as paths are at most 251 bits long.
+    let length = path.len() as u8;
+    let hash = H::hash(child, FieldElement::from_byte_slice_be(&path_bytes).unwrap())
+        + FieldElement::from_byte_slice_be(&[length]).unwrap();
+    storage
+        .insert(to_h256(hash), StoredNode::Edge { child: to_h256(child), path: H256::from_slice(&path_bytes), length });
+    hash
+}
+
+fn to_h256(felt: FieldElement) -> H256 {
+    H256::from(felt.to_bytes_be())
+}
+
+fn from_h256(hash: H256) -> FieldElement {
+    // Hashes are read from the storage, where only field elements are written.
+    FieldElement::from_bytes_be(&hash.to_fixed_bytes()).unwrap()
+}
@@ -15,11 +15,15 @@ use blockifier::transaction::objects::AccountTransactionContext;
 use frame_support::BoundedVec;
 use serde_json::{from_slice, to_string};
 use sp_core::{ConstU32, H256, U256};
-use starknet_api::api_core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::api_core::{
+    calculate_contract_address as _calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector,
+};
 use starknet_api::deprecated_contract_class::{EntryPoint, EntryPointOffset, EntryPointType, Program};
 use starknet_api::hash::StarkFelt;
+use starknet_api::state::ContractClass as SierraClass;
 use starknet_api::stdlib::collections::HashMap;
-use starknet_api::transaction::Calldata;
+use starknet_api::transaction::{Calldata, ContractAddressSalt};
+use starknet_api::StarknetApiError;
 
 use self::types::{EntryPointExecutionErrorWrapper, EntryPointExecutionResultWrapper};
 use crate::block::serialize::SerializeBlockContext;
@@ -38,6 +42,31 @@ type MaxEntryPoints = ConstU32<4294967295>;
 /// Wrapper type for class hash field.
 pub type ClassHashWrapper = [u8; 32];
 
+/// Computes the address of a contract deployed without a deployer, as done by deploy account
+/// transactions.
+///
+/// # Arguments
+///
+/// * `class_hash` - The hash of the contract class.
+/// * `salt` - The salt of the contract.
+/// * `constructor_calldata` - The calldata of the constructor.
+pub fn calculate_contract_address(
+    class_hash: ClassHashWrapper,
+    salt: H256,
+    constructor_calldata: &[U256],
+) -> Result<ContractAddressWrapper, StarknetApiError> {
+    let calldata =
+        constructor_calldata.iter().map(|x| StarkFelt::new((*x).into())).collect::<Result<vec::Vec<StarkFelt>, _>>()?;
+    let address = _calculate_contract_address(
+        ContractAddressSalt(StarkFelt::new(salt.0)?),
+        ClassHash(StarkFelt::new(class_hash)?),
+        &Calldata(Arc::new(calldata)),
+        ContractAddress::default(),
+    )?;
+
+    Ok(address.0.0.0)
+}
+
 /// Contract Class
 #[derive(
     Clone,
@@ -94,6 +123,37 @@ impl Default for ContractClassWrapper {
     }
 }
 
+/// Sierra class of a Cairo 1 contract, as declared.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SierraClassWrapper {
+    /// Sierra class json.
+    pub class: BoundedVec<u8, MaxProgramSize>,
+}
+
+impl SierraClassWrapper {
+    /// Convert to starknet sierra class.
+    pub fn to_starknet_sierra_class(&self) -> Result<SierraClass, serde_json::Error> {
+        from_slice::<SierraClass>(self.class.as_ref())
+    }
+}
+
+impl From<SierraClass> for SierraClassWrapper {
+    fn from(sierra_class: SierraClass) -> Self {
+        let class_string = to_string(&sierra_class).unwrap();
+        Self { class: BoundedVec::try_from(class_string.as_bytes().to_vec()).unwrap() }
+    }
+}
+
 /// Enum that represents all the entrypoints types.
 #[derive(
     Clone,
@@ -138,6 +198,34 @@ impl EntryPointTypeWrapper {
     }
 }
 
+/// Enum that represents the types of calls.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum CallTypeWrapper {
+    /// Regular call.
+    Call,
+    /// Library call, executed in the context of the caller.
+    Delegate,
+}
+
+impl From<CallType> for CallTypeWrapper {
+    fn from(call_type: CallType) -> Self {
+        match call_type {
+            CallType::Call => CallTypeWrapper::Call,
+            CallType::Delegate => CallTypeWrapper::Delegate,
+        }
+    }
+}
+
 // pub enum ContractClassAbiEntryWrapper {
 // 	/// An event abi entry.
 //     Event(EventAbiEntry),
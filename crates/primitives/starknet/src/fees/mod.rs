@@ -0,0 +1,66 @@
+//! Starknet transaction fees.
+//!
+//! The fee of a transaction is the L1 gas its execution consumes, times the L1 gas price. The
+//! consumed gas is driven by the Cairo resource the execution uses the most, relative to its cost.
+//! See the [documentation](https://docs.starknet.io/documentation/architecture_and_concepts/Fees/fee-mechanism/)
+//! for details.
+use blockifier::execution::entry_point::ExecutionResources;
+
+/// Name of the resource counting the Cairo steps of an execution.
+const N_STEPS_RESOURCE: &str = "n_steps";
+
+/// Cost of a unit of each Cairo resource, in thousandths of L1 gas.
+pub const VM_RESOURCE_FEE_COSTS: [(&str, u128); 9] = [
+    (N_STEPS_RESOURCE, 10),
+    ("pedersen_builtin", 320),
+    ("range_check_builtin", 160),
+    ("ecdsa_builtin", 20480),
+    ("bitwise_builtin", 640),
+    ("ec_op_builtin", 10240),
+    ("poseidon_builtin", 320),
+    ("keccak_builtin", 20480),
+    ("output_builtin", 0),
+];
+
+/// Computes the L1 gas consumed by the Cairo resources used by the phases of an execution.
+///
+/// # Arguments
+///
+/// * `resources` - The resources used by each phase of the execution.
+///
+/// # Returns
+///
+/// The consumed L1 gas, rounded up.
+pub fn calculate_l1_gas_usage(resources: &[&ExecutionResources]) -> u128 {
+    let usage = |name: &str| -> u128 {
+        resources
+            .iter()
+            .map(|resources| {
+                let vm_resources = &resources.vm_resources;
+                let usage = if name == N_STEPS_RESOURCE {
+                    vm_resources.n_steps
+                } else {
+                    vm_resources.builtin_instance_counter.get(name).copied().unwrap_or_default()
+                };
+                usage as u128
+            })
+            .sum()
+    };
+
+    let milligas = VM_RESOURCE_FEE_COSTS.iter().map(|(name, cost)| usage(name) * cost).max().unwrap_or_default();
+    (milligas + 999) / 1000
+}
+
+/// Computes the fee of an execution.
+///
+/// # Arguments
+///
+/// * `resources` - The resources used by each phase of the execution.
+/// * `gas_price` - The price of a unit of L1 gas, in the fee token.
+///
+/// # Returns
+///
+/// The fee, in the fee token.
+pub fn calculate_tx_fee(resources: &[&ExecutionResources], gas_price: u128) -> u128 {
+    calculate_l1_gas_usage(resources).saturating_mul(gas_price)
+}
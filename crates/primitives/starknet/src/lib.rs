@@ -38,3 +38,6 @@ pub mod traits;
 
 /// Starknet Execution related functionality.
 pub mod execution;
+
+/// Starknet transaction fees.
+pub mod fees;
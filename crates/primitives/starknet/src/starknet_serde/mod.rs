@@ -3,14 +3,13 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::{fmt, format};
 
-use blockifier::test_utils::get_contract_class;
 use frame_support::BoundedVec;
 use hex::{FromHex, FromHexError};
 use serde::{Deserialize, Serialize};
 use sp_core::{H256, U256};
 
-use crate::execution::{CallEntryPointWrapper, ContractAddressWrapper, ContractClassWrapper, EntryPointTypeWrapper};
-use crate::transaction::types::{EventWrapper, MaxArraySize, Transaction};
+use crate::execution::{CallEntryPointWrapper, ContractAddressWrapper, EntryPointTypeWrapper};
+use crate::transaction::types::{EventWrapper, InvokeTransaction, MaxArraySize};
 
 /// Removes the "0x" prefix from a given hexadecimal string
 fn remove_prefix(input: &str) -> &str {
@@ -133,7 +132,7 @@ impl fmt::Display for DeserializeEventError {
     }
 }
 
-/// Struct for deserializing Transaction from JSON
+/// Struct for deserializing an invoke transaction from JSON
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeserializeTransaction {
     /// The version of the transaction
@@ -148,6 +147,9 @@ pub struct DeserializeTransaction {
     pub sender_address: String,
     /// Nonce
     pub nonce: u64,
+    /// Max fee, zero if not specified
+    #[serde(default)]
+    pub max_fee: Option<String>,
     /// Call entrypoint
     pub call_entrypoint: DeserializeCallEntrypoint,
 }
@@ -169,6 +171,8 @@ pub enum DeserializeTransactionError {
     EventsExceedMaxSize,
     /// InvalidSenderAddress error
     InvalidSenderAddress(FromHexError),
+    /// InvalidMaxFee error
+    InvalidMaxFee(String),
     /// InvalidCallEntryPoint error
     InvalidCallEntryPoint(DeserializeCallEntrypointError),
 }
@@ -184,6 +188,7 @@ impl fmt::Display for DeserializeTransactionError {
             DeserializeTransactionError::InvalidEvents(e) => write!(f, "Invalid events format: {:?}", e),
             DeserializeTransactionError::EventsExceedMaxSize => write!(f, "Events exceed max size"),
             DeserializeTransactionError::InvalidSenderAddress(e) => write!(f, "Invalid sender address format: {:?}", e),
+            DeserializeTransactionError::InvalidMaxFee(s) => write!(f, "Invalid max fee format: {:?}", s),
             DeserializeTransactionError::InvalidCallEntryPoint(e) => {
                 write!(f, "Invalid call_entry_point format: {:?}", e)
             }
@@ -191,14 +196,14 @@ impl fmt::Display for DeserializeTransactionError {
     }
 }
 
-/// Implementation of `TryFrom<DeserializeTransaction>` for `Transaction`.
+/// Implementation of `TryFrom<DeserializeTransaction>` for `InvokeTransaction`.
 ///
-/// Converts a `DeserializeTransaction` into a `Transaction`, performing necessary validations
-/// and transformations on the input data.
-impl TryFrom<DeserializeTransaction> for Transaction {
+/// Converts a `DeserializeTransaction` into an `InvokeTransaction`, performing necessary
+/// validations and transformations on the input data.
+impl TryFrom<DeserializeTransaction> for InvokeTransaction {
     type Error = DeserializeTransactionError;
 
-    /// Converts a `DeserializeTransaction` into a `Transaction`.
+    /// Converts a `DeserializeTransaction` into an `InvokeTransaction`.
     ///
     /// Returns a `DeserializeTransactionError` variant if any field fails validation or conversion.
     fn try_from(d: DeserializeTransaction) -> Result<Self, Self::Error> {
@@ -224,12 +229,21 @@ impl TryFrom<DeserializeTransaction> for Transaction {
         // Convert nonce to U256
         let nonce = U256::from(d.nonce);
 
-        // Convert call_entrypoint to CallEntryPointWrapper
+        // Convert max_fee to U256
+        let max_fee = match d.max_fee {
+            Some(max_fee) => string_to_u256(&max_fee).map_err(DeserializeTransactionError::InvalidMaxFee)?,
+            None => U256::zero(),
+        };
+
+        // Convert call_entrypoint to CallEntryPointWrapper, whose calldata and entrypoint selector are the
+        // ones of the transaction
         let call_entrypoint = CallEntryPointWrapper::try_from(d.call_entrypoint)
             .map_err(DeserializeTransactionError::InvalidCallEntryPoint)?;
+        let calldata = call_entrypoint.calldata;
+        let entry_point_selector = call_entrypoint.entrypoint_selector;
 
-        // Create Transaction with validated and converted fields
-        Ok(Self { version, hash, signature, sender_address, nonce, call_entrypoint, ..Transaction::default() })
+        // Create InvokeTransaction with validated and converted fields
+        Ok(Self { version, hash, signature, sender_address, nonce, max_fee, calldata, entry_point_selector })
     }
 }
 
@@ -329,32 +343,15 @@ impl TryFrom<DeserializeEventWrapper> for EventWrapper {
     }
 }
 
-/// Create a `Transaction` from a JSON string and an optional contract content.
-///
-/// This function takes a JSON string (`json_str`) and a byte slice (`contract_content`) containing
-/// the contract content, if available.
-///
-/// If `contract_content` is not empty, the function will use it to set the `contract_class`
-/// field of the resulting `Transaction` object. Otherwise, the `contract_class` field will be set
-/// to `None`.
+/// Create an `InvokeTransaction` from a JSON string.
 ///
 /// Returns a `DeserializeTransactionError` if JSON deserialization fails, or if the deserialized
-/// object fails to convert into a `Transaction`.
-pub fn transaction_from_json(
-    json_str: &str,
-    contract_content: &'static [u8],
-) -> Result<Transaction, DeserializeTransactionError> {
-    // Deserialize the JSON string into a DeserializeTransaction and convert it into a Transaction
+/// object fails to convert into an `InvokeTransaction`.
+pub fn transaction_from_json(json_str: &str) -> Result<InvokeTransaction, DeserializeTransactionError> {
+    // Deserialize the JSON string into a DeserializeTransaction and convert it into an
+    // InvokeTransaction
     let deserialized_transaction: DeserializeTransaction =
         serde_json::from_str(json_str).map_err(|e| DeserializeTransactionError::FailedToParse(format!("{:?}", e)))?;
-    let mut transaction = Transaction::try_from(deserialized_transaction)?;
-
-    // Set the contract_class field based on contract_content
-    if !contract_content.is_empty() {
-        transaction.contract_class = Some(ContractClassWrapper::from(get_contract_class(contract_content)));
-    } else {
-        transaction.contract_class = None;
-    }
 
-    Ok(transaction)
+    InvokeTransaction::try_from(deserialized_transaction)
 }
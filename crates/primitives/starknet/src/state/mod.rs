@@ -1,12 +1,12 @@
 use alloc::sync::Arc;
 
 use blockifier::execution::contract_class::ContractClass;
-use blockifier::state::cached_state::ContractStorageKey;
+use blockifier::state::cached_state::{CachedState, ContractStorageKey};
 use blockifier::state::errors::StateError;
-use blockifier::state::state_api::{StateReader, StateResult};
+use blockifier::state::state_api::{State, StateReader, StateResult};
 use starknet_api::api_core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
-use starknet_api::state::StorageKey;
+use starknet_api::state::{StateDiff, StorageKey};
 use starknet_api::stdlib::collections::HashMap;
 
 type ContractClassMapping = HashMap<ClassHash, ContractClass>;
@@ -49,3 +49,56 @@ impl StateReader for DictStateReader {
         Ok(class_hash)
     }
 }
+
+/// A `StateReader` reading through another state.
+///
+/// Changes made on a `CachedState` on top of it are not visible to the underlying state, until
+/// they are committed with [`commit_state_diff`]. Dropping them rolls them back, which is how the
+/// execution of a reverted transaction is discarded.
+pub struct ParentState<'a, S: StateReader>(pub &'a mut CachedState<S>);
+
+impl<'a, S: StateReader> StateReader for ParentState<'a, S> {
+    fn get_storage_at(&mut self, contract_address: ContractAddress, key: StorageKey) -> StateResult<StarkFelt> {
+        self.0.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.0.get_nonce_at(contract_address)
+    }
+
+    fn get_contract_class(&mut self, class_hash: &ClassHash) -> StateResult<Arc<ContractClass>> {
+        self.0.get_contract_class(class_hash)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.0.get_class_hash_at(contract_address)
+    }
+}
+
+/// Applies the changes made on top of a state to it.
+///
+/// Declared classes are not part of the committed changes: they are only declared by the
+/// execution of declare transactions, which is never rolled back.
+///
+/// # Arguments
+///
+/// * `state` - The state the changes were made on top of.
+/// * `state_diff` - The changes, as returned by the `to_state_diff` of the state they were made on.
+pub fn commit_state_diff<S: StateReader>(state: &mut CachedState<S>, state_diff: StateDiff) -> StateResult<()> {
+    let StateDiff { deployed_contracts, storage_diffs, nonces, .. } = state_diff;
+    for (contract_address, class_hash) in deployed_contracts {
+        state.set_class_hash_at(contract_address, class_hash)?;
+    }
+    for (contract_address, storage_diff) in storage_diffs {
+        for (key, value) in storage_diff {
+            state.set_storage_at(contract_address, key, value);
+        }
+    }
+    // Nonces only ever increase.
+    for (contract_address, nonce) in nonces {
+        while state.get_nonce_at(contract_address)? != nonce {
+            state.increment_nonce(contract_address)?;
+        }
+    }
+    Ok(())
+}
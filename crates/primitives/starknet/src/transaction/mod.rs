@@ -2,34 +2,50 @@
 /// Types related to transactions.
 pub mod types;
 
-use alloc::vec;
+use alloc::sync::Arc;
+use alloc::{format, vec};
 
+use blockifier::abi::abi_utils::selector_from_name;
 use blockifier::block_context::BlockContext;
 use blockifier::execution::contract_class::ContractClass;
-use blockifier::execution::entry_point::{CallInfo, ExecutionResources};
+use blockifier::execution::entry_point::{
+    CallEntryPoint, CallInfo, CallType, ExecutionContext, ExecutionResources, MessageToL1,
+};
 use blockifier::state::cached_state::CachedState;
 use blockifier::state::state_api::StateReader;
+use blockifier::transaction::constants::{
+    VALIDATE_DECLARE_ENTRY_POINT_NAME, VALIDATE_DEPLOY_ENTRY_POINT_NAME, VALIDATE_ENTRY_POINT_NAME,
+};
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::AccountTransactionContext;
 use blockifier::transaction::transactions::Executable;
 use frame_support::BoundedVec;
-use sp_core::{H256, U256};
-use starknet_api::api_core::{ContractAddress as StarknetContractAddress, EntryPointSelector, Nonce};
+use sp_core::hashing::keccak_256;
+use sp_core::{H160, H256, U256};
+use starknet_api::api_core::{ClassHash, ContractAddress as StarknetContractAddress, EntryPointSelector, Nonce};
+use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::transaction::{
-    ContractAddressSalt, DeclareTransaction, DeclareTransactionV0V1, DeployAccountTransaction, EventContent, Fee,
-    InvokeTransactionV1, L1HandlerTransaction, TransactionHash, TransactionOutput, TransactionReceipt,
-    TransactionSignature, TransactionVersion,
+    Calldata, ContractAddressSalt, DeclareTransaction as StarknetDeclareTransaction, DeclareTransactionV0V1,
+    DeployAccountTransaction as StarknetDeployAccountTransaction, EventContent, Fee, InvokeTransactionV1,
+    L1HandlerTransaction as StarknetL1HandlerTransaction, MessageToL1 as StarknetMessageToL1, TransactionHash,
+    TransactionOutput, TransactionReceipt, TransactionSignature, TransactionVersion,
 };
 use starknet_api::StarknetApiError;
+use starknet_crypto::FieldElement;
+use starknet_ff::FromByteSliceError;
 
 use self::types::{
-    EventError, EventWrapper, MaxArraySize, Transaction, TransactionExecutionErrorWrapper,
-    TransactionExecutionResultWrapper, TransactionReceiptWrapper, TxType,
+    DeclareTransaction, DeployAccountTransaction, EventError, EventWrapper, ExecutionOutputError,
+    FunctionInvocationWrapper, InvokeTransaction, L1HandlerTransaction, MaxArraySize, MessageError, MessageToL1Wrapper,
+    Transaction, TransactionExecutionErrorWrapper, TransactionExecutionResultWrapper, TransactionExecutionStatus,
+    TransactionReceiptWrapper, TxType,
 };
 use crate::block::serialize::SerializeBlockContext;
 use crate::block::Block as StarknetBlock;
-use crate::execution::{CallEntryPointWrapper, ContractAddressWrapper, ContractClassWrapper};
+use crate::crypto::hash::pedersen::PedersenHasher;
+use crate::execution::{ClassHashWrapper, ContractAddressWrapper, ContractClassWrapper};
+use crate::traits::hash::CryptoHasher;
 
 impl EventWrapper {
     /// Creates a new instance of an event.
@@ -142,9 +158,96 @@ impl Default for EventWrapper {
     }
 }
 
+impl MessageToL1Wrapper {
+    /// Creates a message sent to L1 from a message sent by a call.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_address` - Contract Address which sent the message.
+    /// * `message` - Message retrieved from the `CallInfo`.
+    pub fn try_new(from_address: StarknetContractAddress, message: &MessageToL1) -> Result<Self, MessageError> {
+        let payload = message.payload.0.iter().map(|x| H256::from_slice(x.bytes())).collect::<vec::Vec<H256>>();
+
+        Ok(Self {
+            from_address: from_address.0.key().bytes().try_into().map_err(|_| MessageError::InvalidFromAddress)?,
+            to_address: H160::from_slice(message.to_address.0.as_bytes()),
+            payload: BoundedVec::try_from(payload).map_err(|_| MessageError::InvalidPayload)?,
+        })
+    }
+
+    /// Computes the hash of the message, under which the Starknet core contract records it on L1.
+    ///
+    /// See the [documentation](https://docs.starknet.io/documentation/architecture_and_concepts/L1-L2_Communication/messaging-mechanism/#l2-l1_messages)
+    /// for details.
+    pub fn hash(&self) -> H256 {
+        let mut data = vec::Vec::with_capacity(32 * (3 + self.payload.len()));
+        data.extend_from_slice(&self.from_address);
+        data.extend_from_slice(H256::from(self.to_address).as_bytes());
+        data.extend_from_slice(H256::from_low_u64_be(self.payload.len() as u64).as_bytes());
+        self.payload.iter().for_each(|x| data.extend_from_slice(x.as_bytes()));
+
+        H256(keccak_256(&data))
+    }
+}
+
+/// Collects the messages sent to L1 by a call and all of its inner calls, in the order they were
+/// sent.
+///
+/// # Arguments
+///
+/// * `call_info` - The call info of the call.
+///
+/// # Error
+///
+/// Returns an error if a message can't be converted.
+pub fn messages_sent(call_info: &CallInfo) -> Result<vec::Vec<MessageToL1Wrapper>, MessageError> {
+    fn collect<'a>(
+        call_info: &'a CallInfo,
+        messages: &mut vec::Vec<(usize, StarknetContractAddress, &'a MessageToL1)>,
+    ) {
+        messages.extend(
+            call_info.execution.l2_to_l1_messages.iter().map(|ordered_message| {
+                (ordered_message.order, call_info.call.storage_address, &ordered_message.message)
+            }),
+        );
+        call_info.inner_calls.iter().for_each(|inner_call| collect(inner_call, messages));
+    }
+
+    // Orders are shared by all the calls of an execution.
+    let mut messages = vec::Vec::new();
+    collect(call_info, &mut messages);
+    messages.sort_by_key(|(order, _, _)| *order);
+
+    messages.into_iter().map(|(_, from_address, message)| MessageToL1Wrapper::try_new(from_address, message)).collect()
+}
+
+/// Try to convert a message of a `TransactionReceipt` into a `MessageToL1Wrapper`.
+impl TryFrom<&StarknetMessageToL1> for MessageToL1Wrapper {
+    type Error = MessageError;
+
+    fn try_from(message: &StarknetMessageToL1) -> Result<Self, Self::Error> {
+        Self::try_new(
+            message.from_address,
+            &MessageToL1 { to_address: message.to_address, payload: message.payload.clone() },
+        )
+    }
+}
+
+impl From<EventError> for ExecutionOutputError {
+    fn from(error: EventError) -> Self {
+        Self::Event(error)
+    }
+}
+
+impl From<MessageError> for ExecutionOutputError {
+    fn from(error: MessageError) -> Self {
+        Self::Message(error)
+    }
+}
+
 /// Try to convert a `&TransactionReceipt` into a `TransactionReceiptWrapper`.
 impl TryInto<TransactionReceiptWrapper> for &TransactionReceipt {
-    type Error = EventError;
+    type Error = ExecutionOutputError;
 
     // TODO: add block hash and block number (#252)
     fn try_into(self) -> Result<TransactionReceiptWrapper, Self::Error> {
@@ -156,126 +259,367 @@ impl TryInto<TransactionReceiptWrapper> for &TransactionReceipt {
                 EventWrapper::builder().with_event_content(e.content.clone()).with_from_address(e.from_address).build()
             })
             .collect();
+        let messages_sent = match &self.output {
+            TransactionOutput::Declare(output) => &output.messages_sent,
+            TransactionOutput::Deploy(output) => &output.messages_sent,
+            TransactionOutput::DeployAccount(output) => &output.messages_sent,
+            TransactionOutput::Invoke(output) => &output.messages_sent,
+            TransactionOutput::L1Handler(output) => &output.messages_sent,
+        }
+        .iter()
+        .map(MessageToL1Wrapper::try_from)
+        .collect::<Result<vec::Vec<MessageToL1Wrapper>, MessageError>>()?;
 
         Ok(TransactionReceiptWrapper {
             transaction_hash: H256::from_slice(self.transaction_hash.0.bytes()),
             actual_fee: U256::from(self.output.actual_fee().0),
-            tx_type: match self.output {
-                TransactionOutput::Declare(_) => TxType::DeclareTx,
-                TransactionOutput::DeployAccount(_) => TxType::DeployAccountTx,
-                TransactionOutput::Invoke(_) => TxType::InvokeTx,
-                TransactionOutput::L1Handler(_) => TxType::L1HandlerTx,
-                _ => TxType::InvokeTx,
-            },
+            messages_sent: BoundedVec::try_from(messages_sent).map_err(|_| MessageError::TooManyMessages)?,
             events: BoundedVec::try_from(_events?).map_err(|_| EventError::TooManyEvents)?,
+            execution_status: TransactionExecutionStatus::Succeeded,
+            revert_reason: None,
         })
     }
 }
 
-/// Try to convert a `&Transaction` into a `DeployAccountTransaction`.
-impl TryInto<DeployAccountTransaction> for &Transaction {
+/// Try to convert a `&CallInfo` into a `FunctionInvocationWrapper`.
+impl TryFrom<&CallInfo> for FunctionInvocationWrapper {
+    type Error = ExecutionOutputError;
+
+    fn try_from(call_info: &CallInfo) -> Result<Self, Self::Error> {
+        let mut ordered_events = call_info.execution.events.clone();
+        ordered_events.sort_by_key(|ordered_event| ordered_event.order);
+        let events = ordered_events
+            .into_iter()
+            .map(|ordered_event| {
+                EventWrapper::builder()
+                    .with_event_content(ordered_event.event)
+                    .with_from_address(call_info.call.storage_address)
+                    .build()
+            })
+            .collect::<Result<vec::Vec<EventWrapper>, EventError>>()?;
+        let mut ordered_messages = call_info.execution.l2_to_l1_messages.iter().collect::<vec::Vec<_>>();
+        ordered_messages.sort_by_key(|ordered_message| ordered_message.order);
+        let messages = ordered_messages
+            .into_iter()
+            .map(|ordered_message| {
+                MessageToL1Wrapper::try_new(call_info.call.storage_address, &ordered_message.message)
+            })
+            .collect::<Result<vec::Vec<MessageToL1Wrapper>, MessageError>>()?;
+        let calls = call_info
+            .inner_calls
+            .iter()
+            .map(FunctionInvocationWrapper::try_from)
+            .collect::<Result<vec::Vec<FunctionInvocationWrapper>, ExecutionOutputError>>()?;
+
+        Ok(Self {
+            contract_address: call_info.call.storage_address.0.0.0,
+            entry_point_selector: H256::from_slice(call_info.call.entry_point_selector.0.bytes()),
+            calldata: call_info.call.calldata.0.iter().map(|x| U256::from(x.0)).collect(),
+            caller_address: call_info.call.caller_address.0.0.0,
+            class_hash: call_info.call.class_hash.map(|class_hash| class_hash.0.0),
+            entry_point_type: call_info.call.entry_point_type.into(),
+            call_type: call_info.call.call_type.into(),
+            result: call_info.execution.retdata.0.iter().map(|x| U256::from(x.0)).collect(),
+            calls,
+            events,
+            messages,
+        })
+    }
+}
+
+/// Converts a signature to the Starknet format.
+fn to_starknet_signature(signature: &[H256]) -> Result<TransactionSignature, StarknetApiError> {
+    Ok(TransactionSignature(signature.iter().map(|x| StarkFelt::new(x.0)).collect::<Result<vec::Vec<_>, _>>()?))
+}
+
+/// Converts a max fee to the Starknet format.
+fn to_starknet_fee(max_fee: U256) -> Result<Fee, StarknetApiError> {
+    u128::try_from(max_fee).map(Fee).map_err(|_| StarknetApiError::OutOfRange { string: format!("{max_fee:#x}") })
+}
+
+/// Converts calldata to the Starknet format.
+fn to_starknet_calldata(calldata: &[U256]) -> Result<Calldata, StarknetApiError> {
+    Ok(Calldata(Arc::new(calldata.iter().map(|x| StarkFelt::new((*x).into())).collect::<Result<vec::Vec<_>, _>>()?)))
+}
+
+/// Try to convert a `&DeployAccountTransaction` into a Starknet `DeployAccountTransaction`.
+impl TryInto<StarknetDeployAccountTransaction> for &DeployAccountTransaction {
     type Error = StarknetApiError;
 
-    fn try_into(self) -> Result<DeployAccountTransaction, Self::Error> {
-        Ok(DeployAccountTransaction {
+    fn try_into(self) -> Result<StarknetDeployAccountTransaction, Self::Error> {
+        Ok(StarknetDeployAccountTransaction {
             transaction_hash: TransactionHash(StarkFelt::new(self.hash.0)?),
-            max_fee: Fee(2),
+            max_fee: to_starknet_fee(self.max_fee)?,
             version: TransactionVersion(StarkFelt::new(U256::from(self.version).into())?),
-            signature: TransactionSignature(
-                self.signature.clone().into_inner().iter().map(|x| StarkFelt::new(x.0).unwrap()).collect(),
-            ),
+            signature: to_starknet_signature(&self.signature)?,
             nonce: Nonce(StarkFelt::new(self.nonce.into())?),
-            contract_address: StarknetContractAddress::try_from(StarkFelt::new(self.sender_address)?)?,
-            class_hash: self.call_entrypoint.to_starknet_call_entry_point().class_hash.unwrap_or_default(),
-            constructor_calldata: self.call_entrypoint.to_starknet_call_entry_point().calldata,
-            contract_address_salt: ContractAddressSalt(StarkFelt::new(
-                self.contract_address_salt.unwrap_or_default().to_fixed_bytes(),
-            )?),
+            contract_address: StarknetContractAddress::try_from(StarkFelt::new(self.contract_address)?)?,
+            class_hash: ClassHash(StarkFelt::new(self.class_hash)?),
+            constructor_calldata: to_starknet_calldata(&self.constructor_calldata)?,
+            contract_address_salt: ContractAddressSalt(StarkFelt::new(self.contract_address_salt.0)?),
         })
     }
 }
 
-/// Try to convert a `&Transaction` into a `L1HandlerTransaction`.
-impl TryInto<L1HandlerTransaction> for &Transaction {
+/// Try to convert a `&L1HandlerTransaction` into a Starknet `L1HandlerTransaction`.
+impl TryInto<StarknetL1HandlerTransaction> for &L1HandlerTransaction {
     type Error = StarknetApiError;
 
-    fn try_into(self) -> Result<L1HandlerTransaction, Self::Error> {
-        Ok(L1HandlerTransaction {
+    fn try_into(self) -> Result<StarknetL1HandlerTransaction, Self::Error> {
+        Ok(StarknetL1HandlerTransaction {
             transaction_hash: TransactionHash(StarkFelt::new(self.hash.0)?),
             version: TransactionVersion(StarkFelt::new(U256::from(self.version).into())?),
             nonce: Nonce(StarkFelt::new(self.nonce.into())?),
-            contract_address: StarknetContractAddress::try_from(StarkFelt::new(self.sender_address)?)?,
-            calldata: self.call_entrypoint.to_starknet_call_entry_point().calldata,
-            entry_point_selector: EntryPointSelector(StarkHash::new(
-                *self.call_entrypoint.entrypoint_selector.unwrap_or_default().as_fixed_bytes(),
-            )?),
+            contract_address: StarknetContractAddress::try_from(StarkFelt::new(self.contract_address)?)?,
+            calldata: to_starknet_calldata(&self.calldata)?,
+            entry_point_selector: EntryPointSelector(StarkHash::new(self.entry_point_selector.0)?),
         })
     }
 }
 
-/// Try to convert a `&Transaction` into a `InvokeTransaction`.
-impl TryInto<InvokeTransactionV1> for &Transaction {
+/// Try to convert a `&InvokeTransaction` into a Starknet `InvokeTransactionV1`.
+impl TryInto<InvokeTransactionV1> for &InvokeTransaction {
     type Error = StarknetApiError;
 
     fn try_into(self) -> Result<InvokeTransactionV1, Self::Error> {
         Ok(InvokeTransactionV1 {
             transaction_hash: TransactionHash(StarkFelt::new(self.hash.0)?),
-            max_fee: Fee(2),
-            signature: TransactionSignature(
-                self.signature.clone().into_inner().iter().map(|x| StarkFelt::new(x.0).unwrap()).collect(),
-            ),
+            max_fee: to_starknet_fee(self.max_fee)?,
+            signature: to_starknet_signature(&self.signature)?,
             nonce: Nonce(StarkFelt::new(self.nonce.into())?),
             sender_address: StarknetContractAddress::try_from(StarkFelt::new(self.sender_address)?)?,
-            calldata: self.call_entrypoint.to_starknet_call_entry_point().calldata,
+            calldata: to_starknet_calldata(&self.calldata)?,
         })
     }
 }
 
-/// Try to convert a `&Transaction` into a `DeclareTransaction`.
-impl TryInto<DeclareTransaction> for &Transaction {
+/// Try to convert a `&DeclareTransaction` into a Starknet `DeclareTransaction`.
+impl TryInto<StarknetDeclareTransaction> for &DeclareTransaction {
     type Error = StarknetApiError;
 
-    fn try_into(self) -> Result<DeclareTransaction, Self::Error> {
-        let tx = DeclareTransactionV0V1 {
-            transaction_hash: TransactionHash(StarkFelt::new(self.hash.0)?),
-            max_fee: Fee(2),
-            signature: TransactionSignature(
-                self.signature.clone().into_inner().iter().map(|x| StarkFelt::new(x.0).unwrap()).collect(),
-            ),
-            nonce: Nonce(StarkFelt::new(self.nonce.into())?),
-            sender_address: StarknetContractAddress::try_from(StarkFelt::new(self.sender_address)?)?,
-            class_hash: self.call_entrypoint.to_starknet_call_entry_point().class_hash.unwrap_or_default(),
-        };
+    fn try_into(self) -> Result<StarknetDeclareTransaction, Self::Error> {
+        let transaction_hash = TransactionHash(StarkFelt::new(self.hash.0)?);
+        let max_fee = to_starknet_fee(self.max_fee)?;
+        let signature = to_starknet_signature(&self.signature)?;
+        let nonce = Nonce(StarkFelt::new(self.nonce.into())?);
+        let sender_address = StarknetContractAddress::try_from(StarkFelt::new(self.sender_address)?)?;
+        let class_hash = ClassHash(StarkFelt::new(self.class_hash)?);
 
-        Ok(if self.version == 0_u8 {
-            DeclareTransaction::V0(tx)
-        } else if self.version == 1_u8 {
-            DeclareTransaction::V1(tx)
-        } else {
-            unimplemented!("DeclareTransactionV2 required the compiled class hash. I don't know how to get it");
-        })
+        // Declare v2 transactions of Cairo 1 classes are not supported.
+        let tx = DeclareTransactionV0V1 { transaction_hash, max_fee, signature, nonce, sender_address, class_hash };
+        match self.version {
+            0 => Ok(StarknetDeclareTransaction::V0(tx)),
+            1 => Ok(StarknetDeclareTransaction::V1(tx)),
+            version => Err(StarknetApiError::OutOfRange { string: format!("declare transaction version {version}") }),
+        }
+    }
+}
+
+impl From<InvokeTransaction> for Transaction {
+    fn from(transaction: InvokeTransaction) -> Self {
+        Self::Invoke(transaction)
+    }
+}
+
+impl From<DeclareTransaction> for Transaction {
+    fn from(transaction: DeclareTransaction) -> Self {
+        Self::Declare(transaction)
+    }
+}
+
+impl From<DeployAccountTransaction> for Transaction {
+    fn from(transaction: DeployAccountTransaction) -> Self {
+        Self::DeployAccount(transaction)
+    }
+}
+
+impl From<L1HandlerTransaction> for Transaction {
+    fn from(transaction: L1HandlerTransaction) -> Self {
+        Self::L1Handler(transaction)
     }
 }
 
 impl Transaction {
-    /// Creates a new instance of a transaction.
-    #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        version: u8,
-        hash: H256,
-        signature: BoundedVec<H256, MaxArraySize>,
-        sender_address: ContractAddressWrapper,
-        nonce: U256,
-        call_entrypoint: CallEntryPointWrapper,
-        contract_class: Option<ContractClassWrapper>,
-        contract_address_salt: Option<H256>,
-    ) -> Self {
-        Self { version, hash, signature, sender_address, nonce, call_entrypoint, contract_class, contract_address_salt }
+    /// Returns the type of the transaction.
+    pub fn tx_type(&self) -> TxType {
+        match self {
+            Self::Invoke(_) => TxType::InvokeTx,
+            Self::Declare(_) => TxType::DeclareTx,
+            Self::DeployAccount(_) => TxType::DeployAccountTx,
+            Self::L1Handler(_) => TxType::L1HandlerTx,
+        }
+    }
+
+    /// Returns the version of the transaction.
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::Invoke(tx) => tx.version,
+            Self::Declare(tx) => tx.version,
+            Self::DeployAccount(tx) => tx.version,
+            Self::L1Handler(tx) => tx.version,
+        }
+    }
+
+    /// Returns the hash of the transaction.
+    pub fn hash(&self) -> H256 {
+        match self {
+            Self::Invoke(tx) => tx.hash,
+            Self::Declare(tx) => tx.hash,
+            Self::DeployAccount(tx) => tx.hash,
+            Self::L1Handler(tx) => tx.hash,
+        }
+    }
+
+    /// Computes the hash of the transaction on the given chain.
+    ///
+    /// See the [documentation](https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/)
+    /// for the fields committed to by each type of transaction. The signature is not part of the
+    /// hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The id of the chain the transaction is sent to, e.g. `SN_GOERLI`.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the transaction, or an error if one of its fields is not a field element.
+    pub fn compute_hash(&self, chain_id: &str) -> Result<H256, FromByteSliceError> {
+        let felt = |bytes: &[u8]| FieldElement::from_byte_slice_be(bytes);
+        let u256_felt = |x: &U256| felt(&<[u8; 32]>::from(*x));
+        let hash_on_u256 = |values: &[U256]| -> Result<FieldElement, FromByteSliceError> {
+            let values = values.iter().map(u256_felt).collect::<Result<vec::Vec<_>, _>>()?;
+            Ok(PedersenHasher::compute_hash_on_elements(&values))
+        };
+        let chain_id = felt(chain_id.as_bytes())?;
+        let version = felt(&[self.version()])?;
+
+        let elements = match self {
+            Self::Invoke(tx) => match tx.entry_point_selector {
+                // Version 0 transactions commit to the called entrypoint instead of the nonce.
+                Some(entry_point_selector) => vec![
+                    felt(b"invoke")?,
+                    version,
+                    felt(&tx.sender_address)?,
+                    felt(entry_point_selector.as_bytes())?,
+                    hash_on_u256(&tx.calldata)?,
+                    u256_felt(&tx.max_fee)?,
+                    chain_id,
+                ],
+                None => vec![
+                    felt(b"invoke")?,
+                    version,
+                    felt(&tx.sender_address)?,
+                    FieldElement::ZERO,
+                    hash_on_u256(&tx.calldata)?,
+                    u256_felt(&tx.max_fee)?,
+                    chain_id,
+                    u256_felt(&tx.nonce)?,
+                ],
+            },
+            Self::Declare(tx) => {
+                let class_hash = felt(&tx.class_hash)?;
+                // Version 0 transactions commit to an empty calldata, and to the class hash instead of
+                // the nonce.
+                vec![
+                    felt(b"declare")?,
+                    version,
+                    felt(&tx.sender_address)?,
+                    FieldElement::ZERO,
+                    if tx.version == 0 {
+                        PedersenHasher::compute_hash_on_elements(&[])
+                    } else {
+                        PedersenHasher::compute_hash_on_elements(&[class_hash])
+                    },
+                    u256_felt(&tx.max_fee)?,
+                    chain_id,
+                    if tx.version == 0 { class_hash } else { u256_felt(&tx.nonce)? },
+                ]
+            }
+            Self::DeployAccount(tx) => {
+                let mut calldata = vec![felt(&tx.class_hash)?, felt(tx.contract_address_salt.as_bytes())?];
+                for value in tx.constructor_calldata.iter() {
+                    calldata.push(u256_felt(value)?);
+                }
+                vec![
+                    felt(b"deploy_account")?,
+                    version,
+                    felt(&tx.contract_address)?,
+                    FieldElement::ZERO,
+                    PedersenHasher::compute_hash_on_elements(&calldata),
+                    u256_felt(&tx.max_fee)?,
+                    chain_id,
+                    u256_felt(&tx.nonce)?,
+                ]
+            }
+            // L1 handler transactions pay no fee, so they commit to a max fee of 0.
+            Self::L1Handler(tx) => vec![
+                felt(b"l1_handler")?,
+                version,
+                felt(&tx.contract_address)?,
+                felt(tx.entry_point_selector.as_bytes())?,
+                hash_on_u256(&tx.calldata)?,
+                FieldElement::ZERO,
+                chain_id,
+                u256_felt(&tx.nonce)?,
+            ],
+        };
+
+        Ok(H256::from(PedersenHasher::compute_hash_on_elements(&elements).to_bytes_be()))
+    }
+
+    /// Returns the signature of the transaction, empty for L1 handler transactions.
+    pub fn signature(&self) -> &[H256] {
+        match self {
+            Self::Invoke(tx) => &tx.signature,
+            Self::Declare(tx) => &tx.signature,
+            Self::DeployAccount(tx) => &tx.signature,
+            Self::L1Handler(_) => &[],
+        }
     }
 
-    /// Creates a new instance of a transaction without signature.
-    pub fn from_tx_hash(hash: H256) -> Self {
-        Self { hash, ..Self::default() }
+    /// Returns the address of the account sending the transaction, or of the contract handling
+    /// the message of an L1 handler transaction.
+    pub fn sender_address(&self) -> ContractAddressWrapper {
+        match self {
+            Self::Invoke(tx) => tx.sender_address,
+            Self::Declare(tx) => tx.sender_address,
+            Self::DeployAccount(tx) => tx.contract_address,
+            Self::L1Handler(tx) => tx.contract_address,
+        }
+    }
+
+    /// Returns the nonce of the transaction, which is the nonce of the L1 message for L1 handler
+    /// transactions.
+    pub fn nonce(&self) -> U256 {
+        match self {
+            Self::Invoke(tx) => tx.nonce,
+            Self::Declare(tx) => tx.nonce,
+            Self::DeployAccount(tx) => tx.nonce,
+            Self::L1Handler(tx) => tx.nonce,
+        }
+    }
+
+    /// Returns the max fee the sender is willing to pay, zero for L1 handler transactions which
+    /// are paid on L1.
+    pub fn max_fee(&self) -> U256 {
+        match self {
+            Self::Invoke(tx) => tx.max_fee,
+            Self::Declare(tx) => tx.max_fee,
+            Self::DeployAccount(tx) => tx.max_fee,
+            Self::L1Handler(_) => U256::zero(),
+        }
+    }
+
+    /// Returns whether the transaction is ordered by the nonce of its sender account.
+    ///
+    /// L1 handler transactions carry the nonce of their L1 message, and version 0 invoke
+    /// transactions call a contract directly, without going through an account.
+    pub fn uses_account_nonce(&self) -> bool {
+        match self {
+            Self::Invoke(tx) => tx.version != 0_u8,
+            Self::Declare(_) | Self::DeployAccount(_) => true,
+            Self::L1Handler(_) => false,
+        }
     }
 
     /// Verifies if a transaction has the correct version
@@ -283,24 +627,29 @@ impl Transaction {
     /// # Arguments
     ///
     /// * `self` - The transaction to execute
-    /// * `tx_type` - The type of the transaction to execute
     ///
     /// # Returns
     ///
     /// * `TransactionExecutionResultWrapper<()>` - The result of the transaction version validation
-    pub fn verify_tx_version(&self, tx_type: &TxType) -> TransactionExecutionResultWrapper<()> {
-        let version = match StarkFelt::new(U256::from(self.version).into()) {
+    pub fn verify_tx_version(&self) -> TransactionExecutionResultWrapper<()> {
+        let version = match StarkFelt::new(U256::from(self.version()).into()) {
             Ok(felt) => TransactionVersion(felt),
             Err(err) => {
                 return Err(TransactionExecutionErrorWrapper::StarknetApi(err));
             }
         };
 
-        let allowed_versions: vec::Vec<TransactionVersion> = match tx_type {
-            TxType::DeclareTx => {
-                // Support old versions in order to allow bootstrapping of a new system.
+        let allowed_versions: vec::Vec<TransactionVersion> = match self {
+            Self::Declare(_) => {
+                // Support old versions in order to allow bootstrapping of a new system. Declare v2
+                // transactions of Cairo 1 classes are not supported, as our blockifier and
+                // `ContractClassWrapper` only know about Cairo 0 classes.
                 vec![TransactionVersion(StarkFelt::from(0)), TransactionVersion(StarkFelt::from(1))]
             }
+            // Legacy v0 invoke transactions call a contract directly, without an account. Resource
+            // bounds based v3 transactions are not supported, as our starknet_api and blockifier
+            // versions can neither represent nor execute them.
+            Self::Invoke(_) => vec![TransactionVersion(StarkFelt::from(0)), TransactionVersion(StarkFelt::from(1))],
             _ => vec![TransactionVersion(StarkFelt::from(1))],
         };
         if allowed_versions.contains(&version) {
@@ -315,14 +664,17 @@ impl Transaction {
 
     /// Executes a transaction
     ///
+    /// Only the execution phase is run: the validation by the account and the fee transfer are
+    /// separate phases, see `validate`.
+    ///
     /// # Arguments
     ///
     /// * `self` - The transaction to execute.
     /// * `state` - The state to execute the transaction on.
     /// * `block` - The block to execute the transaction on.
-    /// * `tx_type` - The type of the transaction to execute.
     /// * `contract_class` - The contract class to execute the transaction on.
     /// * `fee_token_address` - The fee token address.
+    /// * `execution_resources` - The resources used by the execution are added to these.
     ///
     /// # Returns
     ///
@@ -332,42 +684,60 @@ impl Transaction {
         &self,
         state: &mut CachedState<S>,
         block: StarknetBlock,
-        tx_type: TxType,
         contract_class: Option<ContractClass>,
         fee_token_address: ContractAddressWrapper,
+        execution_resources: &mut ExecutionResources,
     ) -> TransactionExecutionResultWrapper<Option<CallInfo>> {
         // Create the block context.
         let block_context = BlockContext::try_serialize(block.header().clone(), fee_token_address)
             .map_err(|_| TransactionExecutionErrorWrapper::BlockContextSerializationError)?;
-        // Initialize the execution resources.
-        let execution_resources = &mut ExecutionResources::default();
 
         // Verify the transaction version.
-        self.verify_tx_version(&tx_type)?;
+        self.verify_tx_version()?;
 
-        match tx_type {
-            TxType::InvokeTx => {
-                let tx: InvokeTransactionV1 = self.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+        match self {
+            Self::Invoke(tx) if tx.version == 0_u8 => {
+                let call = tx.entry_point_call()?;
+                let tx: InvokeTransactionV1 = tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+                let account_context = self.get_invoke_transaction_context(&tx);
+
+                // Version 0 transactions are not executed by an account: the entrypoint is called
+                // directly, and its failure is not reverted but rejects the transaction.
+                call.execute(
+                    state,
+                    execution_resources,
+                    &mut ExecutionContext::default(),
+                    &block_context,
+                    &account_context,
+                )
+                .map(Some)
+                .map_err(TransactionExecutionErrorWrapper::EntryPointExecution)
+            }
+            Self::Invoke(tx) => {
+                let tx: InvokeTransactionV1 = tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
                 let account_context = self.get_invoke_transaction_context(&tx);
 
                 tx.run_execute(state, execution_resources, &block_context, &account_context, contract_class)
                     .map_err(TransactionExecutionErrorWrapper::TransactionExecution)
             }
-            TxType::L1HandlerTx => {
-                let tx = self.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+            Self::L1Handler(tx) => {
+                let tx: StarknetL1HandlerTransaction =
+                    tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
                 let account_context = self.get_l1_handler_transaction_context(&tx);
                 tx.run_execute(state, execution_resources, &block_context, &account_context, contract_class)
                     .map_err(TransactionExecutionErrorWrapper::TransactionExecution)
             }
-            TxType::DeclareTx => {
-                let tx = self.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+            Self::Declare(tx) => {
+                let tx: StarknetDeclareTransaction =
+                    tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
                 let account_context = self.get_declare_transaction_context(&tx);
                 // Execute.
                 tx.run_execute(state, execution_resources, &block_context, &account_context, contract_class)
                     .map_err(TransactionExecutionErrorWrapper::TransactionExecution)
             }
-            TxType::DeployAccountTx => {
-                let tx = self.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+            Self::DeployAccount(tx) => {
+                let tx: StarknetDeployAccountTransaction =
+                    tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
                 let account_context = self.get_deploy_account_transaction_context(&tx);
 
                 // Execute.
@@ -375,11 +745,83 @@ impl Transaction {
                     .map_err(TransactionExecutionErrorWrapper::TransactionExecution)
             }
         }
+    }
+
+    /// Runs the account validation entrypoint of a transaction
+    ///
+    /// Invoke, declare and deploy account transactions are respectively validated by the
+    /// `__validate__`, `__validate_declare__` and `__validate_deploy__` entrypoints of the account,
+    /// within the `validate_max_n_steps` of the block context.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The transaction to validate.
+    /// * `state` - The state to validate the transaction on.
+    /// * `block` - The block to validate the transaction on.
+    /// * `fee_token_address` - The fee token address.
+    /// * `execution_resources` - The resources used by the validation are added to these.
+    ///
+    /// # Returns
+    ///
+    /// * `TransactionExecutionResultWrapper<Option<CallInfo>>` - The result of the validation,
+    ///   `None` for transactions which are not validated by an account
+    pub fn validate<S: StateReader>(
+        &self,
+        state: &mut CachedState<S>,
+        block: StarknetBlock,
+        fee_token_address: ContractAddressWrapper,
+        execution_resources: &mut ExecutionResources,
+    ) -> TransactionExecutionResultWrapper<Option<CallInfo>> {
+        // Create the block context.
+        let mut block_context = BlockContext::try_serialize(block.header().clone(), fee_token_address)
+            .map_err(|_| TransactionExecutionErrorWrapper::BlockContextSerializationError)?;
+        // Validation calls are bounded by `validate_max_n_steps` rather than `invoke_tx_max_n_steps`.
+        block_context.invoke_tx_max_n_steps = block_context.validate_max_n_steps;
+
+        self.verify_tx_version()?;
+
+        let (entrypoint_name, calldata, account_context) = match self {
+            // Version 0 transactions are not sent by an account.
+            Self::Invoke(tx) if tx.version == 0_u8 => return tx.entry_point_call().map(|_| None),
+            Self::Invoke(tx) => {
+                let tx: InvokeTransactionV1 = tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+                (VALIDATE_ENTRY_POINT_NAME, tx.calldata.clone(), self.get_invoke_transaction_context(&tx))
+            }
+            Self::Declare(tx) => {
+                let tx: StarknetDeclareTransaction =
+                    tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+                let calldata = Calldata(Arc::new(vec![tx.class_hash().0]));
+                (VALIDATE_DECLARE_ENTRY_POINT_NAME, calldata, self.get_declare_transaction_context(&tx))
+            }
+            Self::DeployAccount(tx) => {
+                let tx: StarknetDeployAccountTransaction =
+                    tx.try_into().map_err(TransactionExecutionErrorWrapper::StarknetApi)?;
+                let mut calldata = vec![tx.class_hash.0, tx.contract_address_salt.0];
+                calldata.extend_from_slice(&tx.constructor_calldata.0);
+                (
+                    VALIDATE_DEPLOY_ENTRY_POINT_NAME,
+                    Calldata(Arc::new(calldata)),
+                    self.get_deploy_account_transaction_context(&tx),
+                )
+            }
+            // L1 handler transactions are not sent by an account.
+            Self::L1Handler(_) => return Ok(None),
+        };
+
+        let validate_call = CallEntryPoint {
+            class_hash: None,
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: selector_from_name(entrypoint_name),
+            calldata,
+            storage_address: account_context.sender_address,
+            caller_address: StarknetContractAddress::default(),
+            call_type: CallType::Call,
+        };
 
-        // TODO: Investigate the use of tx.execute() instead of tx.run_execute()
-        // Going one lower level gives us more flexibility like not validating the tx as we could do
-        // it before the tx lands in the mempool.
-        // However it also means we need to copy/paste internal code from the tx.execute() method.
+        validate_call
+            .execute(state, execution_resources, &mut ExecutionContext::default(), &block_context, &account_context)
+            .map(Some)
+            .map_err(TransactionExecutionErrorWrapper::EntryPointExecution)
     }
 
     /// Get the transaction context for a l1 handler transaction
@@ -392,7 +834,7 @@ impl Transaction {
     /// # Returns
     ///
     /// * `AccountTransactionContext` - The context of the transaction
-    fn get_l1_handler_transaction_context(&self, tx: &L1HandlerTransaction) -> AccountTransactionContext {
+    fn get_l1_handler_transaction_context(&self, tx: &StarknetL1HandlerTransaction) -> AccountTransactionContext {
         AccountTransactionContext {
             transaction_hash: tx.transaction_hash,
             max_fee: Fee::default(),
@@ -417,7 +859,7 @@ impl Transaction {
         AccountTransactionContext {
             transaction_hash: tx.transaction_hash,
             max_fee: tx.max_fee,
-            version: TransactionVersion(StarkFelt::from(1)),
+            version: TransactionVersion(StarkFelt::from(u64::from(self.version()))),
             signature: tx.signature.clone(),
             nonce: tx.nonce,
             sender_address: tx.sender_address,
@@ -434,7 +876,10 @@ impl Transaction {
     /// # Returns
     ///
     /// * `AccountTransactionContext` - The context of the transaction
-    fn get_deploy_account_transaction_context(&self, tx: &DeployAccountTransaction) -> AccountTransactionContext {
+    fn get_deploy_account_transaction_context(
+        &self,
+        tx: &StarknetDeployAccountTransaction,
+    ) -> AccountTransactionContext {
         AccountTransactionContext {
             transaction_hash: tx.transaction_hash,
             max_fee: tx.max_fee,
@@ -455,12 +900,12 @@ impl Transaction {
     /// # Returns
     ///
     /// * `AccountTransactionContext` - The context of the transaction
-    fn get_declare_transaction_context(&self, tx: &DeclareTransaction) -> AccountTransactionContext {
+    fn get_declare_transaction_context(&self, tx: &StarknetDeclareTransaction) -> AccountTransactionContext {
         // TODO: use lib implem once this PR is merged: https://github.com/starkware-libs/starknet-api/pull/49
         let version = match tx {
-            DeclareTransaction::V0(_) => TransactionVersion(StarkFelt::from(0)),
-            DeclareTransaction::V1(_) => TransactionVersion(StarkFelt::from(1)),
-            DeclareTransaction::V2(_) => TransactionVersion(StarkFelt::from(2)),
+            StarknetDeclareTransaction::V0(_) => TransactionVersion(StarkFelt::from(0)),
+            StarknetDeclareTransaction::V1(_) => TransactionVersion(StarkFelt::from(1)),
+            StarknetDeclareTransaction::V2(_) => TransactionVersion(StarkFelt::from(2)),
         };
 
         AccountTransactionContext {
@@ -474,20 +919,98 @@ impl Transaction {
     }
 }
 
+impl InvokeTransaction {
+    /// Builds the call of the entrypoint of the sender contract made by a version 0 transaction.
+    ///
+    /// # Error
+    ///
+    /// Returns an error if the transaction has no entrypoint selector.
+    fn entry_point_call(&self) -> TransactionExecutionResultWrapper<CallEntryPoint> {
+        let entry_point_selector =
+            self.entry_point_selector.ok_or(TransactionExecutionErrorWrapper::MissingEntryPointSelector)?;
+        let to_starknet_api_error = TransactionExecutionErrorWrapper::StarknetApi;
+
+        Ok(CallEntryPoint {
+            class_hash: None,
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: EntryPointSelector(
+                StarkHash::new(entry_point_selector.0).map_err(to_starknet_api_error)?,
+            ),
+            calldata: to_starknet_calldata(&self.calldata).map_err(to_starknet_api_error)?,
+            storage_address: StarknetContractAddress::try_from(
+                StarkFelt::new(self.sender_address).map_err(to_starknet_api_error)?,
+            )
+            .map_err(to_starknet_api_error)?,
+            caller_address: StarknetContractAddress::default(),
+            call_type: CallType::Call,
+        })
+    }
+}
+
 impl Default for Transaction {
     fn default() -> Self {
-        let one = H256::from_slice(&[
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
-        ]);
+        Self::Invoke(InvokeTransaction::default())
+    }
+}
+
+impl Default for InvokeTransaction {
+    fn default() -> Self {
+        let one = H256::from_low_u64_be(1);
         Self {
             version: 1_u8,
             hash: one,
             signature: BoundedVec::try_from(vec![one, one]).unwrap(),
+            sender_address: ContractAddressWrapper::default(),
             nonce: U256::default(),
+            max_fee: U256::default(),
+            calldata: BoundedVec::default(),
+            entry_point_selector: None,
+        }
+    }
+}
+
+impl Default for DeclareTransaction {
+    fn default() -> Self {
+        let one = H256::from_low_u64_be(1);
+        Self {
+            version: 1_u8,
+            hash: one,
+            signature: BoundedVec::try_from(vec![one, one]).unwrap(),
             sender_address: ContractAddressWrapper::default(),
-            call_entrypoint: CallEntryPointWrapper::default(),
-            contract_class: None,
-            contract_address_salt: None,
+            nonce: U256::default(),
+            max_fee: U256::default(),
+            class_hash: ClassHashWrapper::default(),
+            contract_class: ContractClassWrapper::default(),
+        }
+    }
+}
+
+impl Default for DeployAccountTransaction {
+    fn default() -> Self {
+        let one = H256::from_low_u64_be(1);
+        Self {
+            version: 1_u8,
+            hash: one,
+            signature: BoundedVec::try_from(vec![one, one]).unwrap(),
+            nonce: U256::default(),
+            max_fee: U256::default(),
+            contract_address: ContractAddressWrapper::default(),
+            class_hash: ClassHashWrapper::default(),
+            contract_address_salt: H256::default(),
+            constructor_calldata: BoundedVec::default(),
+        }
+    }
+}
+
+impl Default for L1HandlerTransaction {
+    fn default() -> Self {
+        Self {
+            version: 1_u8,
+            hash: H256::from_low_u64_be(1),
+            nonce: U256::default(),
+            contract_address: ContractAddressWrapper::default(),
+            entry_point_selector: H256::default(),
+            calldata: BoundedVec::default(),
         }
     }
 }
@@ -497,8 +1020,10 @@ impl Default for TransactionReceiptWrapper {
         Self {
             transaction_hash: H256::default(),
             actual_fee: U256::default(),
-            tx_type: TxType::InvokeTx,
+            messages_sent: BoundedVec::default(),
             events: BoundedVec::try_from(vec![EventWrapper::default(), EventWrapper::default()]).unwrap(),
+            execution_status: TransactionExecutionStatus::Succeeded,
+            revert_reason: None,
         }
     }
 }
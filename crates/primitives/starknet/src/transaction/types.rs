@@ -1,9 +1,14 @@
+use alloc::vec::Vec;
+
+use blockifier::execution::errors::EntryPointExecutionError;
 use blockifier::transaction::errors::TransactionExecutionError;
 use frame_support::BoundedVec;
-use sp_core::{ConstU32, H256, U256};
+use sp_core::{ConstU32, H160, H256, U256};
 use starknet_api::StarknetApiError;
 
-use crate::execution::{CallEntryPointWrapper, ContractAddressWrapper, ContractClassWrapper};
+use crate::execution::{
+    CallTypeWrapper, ClassHashWrapper, ContractAddressWrapper, ContractClassWrapper, EntryPointTypeWrapper,
+};
 
 /// Max size of arrays.
 /// TODO: add real value (#250)
@@ -21,6 +26,10 @@ pub enum TransactionExecutionErrorWrapper {
     StarknetApi(StarknetApiError),
     /// Block context serialization error.
     BlockContextSerializationError,
+    /// Entrypoint execution error.
+    EntryPointExecution(EntryPointExecutionError),
+    /// Version 0 invoke transaction without an entrypoint selector.
+    MissingEntryPointSelector,
 }
 
 /// Different tx types.
@@ -48,6 +57,65 @@ pub enum TxType {
 }
 
 /// Representation of a Starknet transaction.
+///
+/// Each variant carries the fields of its transaction type.
+/// See `https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/` for more details.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transaction {
+    /// Regular invoke transaction.
+    Invoke(InvokeTransaction),
+    /// Declare transaction.
+    Declare(DeclareTransaction),
+    /// Deploy account transaction.
+    DeployAccount(DeployAccountTransaction),
+    /// Message sent from ethereum.
+    L1Handler(L1HandlerTransaction),
+}
+
+/// Representation of a Starknet invoke transaction.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvokeTransaction {
+    /// The version of the transaction.
+    pub version: u8,
+    /// Transaction hash.
+    pub hash: H256,
+    /// Signature.
+    pub signature: BoundedVec<H256, MaxArraySize>,
+    /// Sender Address
+    pub sender_address: ContractAddressWrapper,
+    /// Nonce
+    pub nonce: U256,
+    /// Max fee the sender is willing to pay for the transaction.
+    pub max_fee: U256,
+    /// Calldata of the `__execute__` entrypoint of the sender account, or of the called
+    /// entrypoint of a version 0 transaction.
+    pub calldata: BoundedVec<U256, MaxArraySize>,
+    /// Selector of the entrypoint called by a version 0 transaction, which calls the sender
+    /// contract directly rather than through the `__execute__` entrypoint of an account.
+    pub entry_point_selector: Option<H256>,
+}
+
+/// Representation of a Starknet declare transaction.
 #[derive(
     Clone,
     Debug,
@@ -59,7 +127,7 @@ pub enum TxType {
     scale_codec::MaxEncodedLen,
 )]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-pub struct Transaction {
+pub struct DeclareTransaction {
     /// The version of the transaction.
     pub version: u8,
     /// Transaction hash.
@@ -70,15 +138,79 @@ pub struct Transaction {
     pub sender_address: ContractAddressWrapper,
     /// Nonce
     pub nonce: U256,
-    /// Call entrypoint
-    pub call_entrypoint: CallEntryPointWrapper,
-    /// Contract Class
-    pub contract_class: Option<ContractClassWrapper>,
-    /// Contract Address Salt
-    pub contract_address_salt: Option<H256>,
+    /// Max fee the sender is willing to pay for the transaction.
+    pub max_fee: U256,
+    /// Hash of the declared class.
+    pub class_hash: ClassHashWrapper,
+    /// The declared class.
+    pub contract_class: ContractClassWrapper,
+}
+
+/// Representation of a Starknet deploy account transaction.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeployAccountTransaction {
+    /// The version of the transaction.
+    pub version: u8,
+    /// Transaction hash.
+    pub hash: H256,
+    /// Signature.
+    pub signature: BoundedVec<H256, MaxArraySize>,
+    /// Nonce
+    pub nonce: U256,
+    /// Max fee the sender is willing to pay for the transaction.
+    pub max_fee: U256,
+    /// Address of the deployed account, computed from the class hash, the salt and the
+    /// constructor calldata.
+    pub contract_address: ContractAddressWrapper,
+    /// Hash of the class of the account.
+    pub class_hash: ClassHashWrapper,
+    /// Salt of the address of the account.
+    pub contract_address_salt: H256,
+    /// Calldata of the constructor of the account.
+    pub constructor_calldata: BoundedVec<U256, MaxArraySize>,
+}
+
+/// Representation of a Starknet L1 handler transaction, which consumes a message sent from
+/// ethereum.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1HandlerTransaction {
+    /// The version of the transaction.
+    pub version: u8,
+    /// Transaction hash.
+    pub hash: H256,
+    /// Nonce of the L1 message.
+    pub nonce: U256,
+    /// Address of the contract handling the message.
+    pub contract_address: ContractAddressWrapper,
+    /// Selector of the L1 handler entrypoint.
+    pub entry_point_selector: H256,
+    /// Calldata of the L1 handler, starting with the ethereum address of the message sender.
+    pub calldata: BoundedVec<U256, MaxArraySize>,
 }
 
 /// Representation of a Starknet transaction receipt.
+///
+/// The type of the receipt is the one of the transaction it is stored along with.
 #[derive(
     Clone,
     Debug,
@@ -95,12 +227,38 @@ pub struct TransactionReceiptWrapper {
     pub transaction_hash: H256,
     /// Fee paid for the transaction.
     pub actual_fee: U256,
-    /// Transaction type
-    pub tx_type: TxType,
-    /// Messages sent in the transaction.
-    // pub messages_sent: BoundedVec<Message, MaxArraySize>, // TODO: add messages
+    /// Messages sent to L1 in the transaction.
+    pub messages_sent: BoundedVec<MessageToL1Wrapper, MaxArraySize>,
     /// Events emitted in the transaction.
     pub events: BoundedVec<EventWrapper, MaxArraySize>,
+    /// Whether the execution of the transaction succeeded or was reverted.
+    pub execution_status: TransactionExecutionStatus,
+    /// Reason of the revert, if the execution was reverted.
+    pub revert_reason: Option<BoundedVec<u8, MaxArraySize>>,
+}
+
+/// Execution status of a Starknet transaction included in a block.
+///
+/// Since Starknet v0.11, a transaction whose execution fails once validated is still included,
+/// and charged: only the state changes of its execution are rolled back.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionExecutionStatus {
+    /// The execution succeeded.
+    #[default]
+    Succeeded,
+    /// The execution failed, and its state changes were rolled back.
+    Reverted,
 }
 
 /// Representation of a Starknet event.
@@ -147,6 +305,68 @@ pub enum EventError {
     TooManyEvents,
 }
 
+/// Representation of a message sent from L2 to L1.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageToL1Wrapper {
+    /// The address of the L2 contract sending the message.
+    pub from_address: ContractAddressWrapper,
+    /// The address of the L1 contract receiving the message.
+    pub to_address: H160,
+    /// The payload of the message.
+    pub payload: BoundedVec<H256, MaxArraySize>,
+}
+
+/// Error enum wrapper for messages sent to L1.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum MessageError {
+    /// Provided payload is invalid.
+    InvalidPayload,
+    /// Provided from address is invalid.
+    InvalidFromAddress,
+    /// Too many messages
+    TooManyMessages,
+}
+
+/// Error enum wrapper for the conversion of the outputs of an execution, such as receipts and
+/// traces.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExecutionOutputError {
+    /// An event couldn't be converted.
+    Event(EventError),
+    /// A message sent to L1 couldn't be converted.
+    Message(MessageError),
+}
+
 /// Error enum wrapper for state diffs.
 #[derive(
     Clone,
@@ -164,4 +384,106 @@ pub enum StateDiffError {
     DeployedContractError,
     /// Couldn't register newly declared contracts.
     DeclaredClassError,
+    /// Couldn't register the modifications of storage vars.
+    StorageDiffError,
+}
+
+/// Representation of a function invocation, as found in a transaction trace.
+#[derive(Clone, Debug, PartialEq, Eq, scale_codec::Encode, scale_codec::Decode, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionInvocationWrapper {
+    /// The address of the invoked contract.
+    pub contract_address: ContractAddressWrapper,
+    /// The selector of the invoked entrypoint.
+    pub entry_point_selector: H256,
+    /// The calldata of the invocation.
+    pub calldata: Vec<U256>,
+    /// The address of the caller.
+    pub caller_address: ContractAddressWrapper,
+    /// The hash of the class being executed.
+    pub class_hash: Option<ClassHashWrapper>,
+    /// The type of the invoked entrypoint.
+    pub entry_point_type: EntryPointTypeWrapper,
+    /// The type of the call.
+    pub call_type: CallTypeWrapper,
+    /// The value returned by the invocation.
+    pub result: Vec<U256>,
+    /// The inner calls made by the invocation.
+    pub calls: Vec<FunctionInvocationWrapper>,
+    /// The events emitted by the invocation.
+    pub events: Vec<EventWrapper>,
+    /// The messages sent to L1 by the invocation, excluding those of its inner calls.
+    pub messages: Vec<MessageToL1Wrapper>,
+}
+
+/// Representation of the execution trace of a Starknet transaction.
+#[derive(Clone, Debug, PartialEq, Eq, scale_codec::Encode, scale_codec::Decode, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransactionTraceWrapper {
+    /// Transaction hash.
+    pub transaction_hash: H256,
+    /// Transaction type.
+    pub tx_type: TxType,
+    /// Signature.
+    pub signature: Vec<H256>,
+    /// The invocation of the account `__validate__` entrypoint, if any.
+    pub validate_invocation: Option<FunctionInvocationWrapper>,
+    /// The invocation of the transaction entrypoint, if any.
+    pub function_invocation: Option<FunctionInvocationWrapper>,
+    /// The invocation of the fee token `transfer` entrypoint, if any.
+    pub fee_transfer_invocation: Option<FunctionInvocationWrapper>,
+    /// Reason of the revert, if the execution of the transaction was reverted.
+    pub revert_reason: Option<Vec<u8>>,
+}
+
+/// Flags altering the way transactions are simulated.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationFlags {
+    /// Do not run the account validation entrypoint.
+    pub skip_validate: bool,
+    /// Do not transfer the fee from the account to the sequencer.
+    pub skip_fee_charge: bool,
+}
+
+/// Fee estimation of a Starknet transaction.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    scale_codec::Encode,
+    scale_codec::Decode,
+    scale_info::TypeInfo,
+    scale_codec::MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeEstimateWrapper {
+    /// The amount of gas consumed by the transaction.
+    pub gas_consumed: U256,
+    /// The gas price used for the estimation.
+    pub gas_price: U256,
+    /// The estimated fee, in the fee token.
+    pub overall_fee: U256,
+}
+
+/// Result of the simulation of a Starknet transaction.
+#[derive(Clone, Debug, PartialEq, Eq, scale_codec::Encode, scale_codec::Decode, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulatedTransactionWrapper {
+    /// The execution trace of the transaction.
+    pub transaction_trace: TransactionTraceWrapper,
+    /// The fee estimation of the transaction.
+    pub fee_estimation: FeeEstimateWrapper,
 }
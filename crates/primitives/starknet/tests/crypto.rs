@@ -1,38 +1,42 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use frame_support::bounded_vec;
 use mp_starknet::crypto::commitment::{
-    calculate_event_commitment, calculate_event_hash, calculate_transaction_commitment,
+    calculate_class_commitment, calculate_event_commitment, calculate_event_hash, calculate_state_root,
+    calculate_transaction_commitment, update_class_commitment,
 };
 use mp_starknet::crypto::hash::pedersen::PedersenHasher;
-use mp_starknet::execution::CallEntryPointWrapper;
-use mp_starknet::transaction::types::{EventWrapper, Transaction};
+use mp_starknet::crypto::hash::poseidon::PoseidonHasher;
+use mp_starknet::crypto::merkle_patricia_tree::stored_tree::StoredTreeError;
+use mp_starknet::traits::hash::CryptoHasher;
+use mp_starknet::transaction::types::{EventWrapper, InvokeTransaction, Transaction};
 use sp_core::{H256, U256};
 use starknet_crypto::FieldElement;
 
 #[test]
 fn test_merkle_tree() {
     let txs = vec![
-        Transaction {
+        Transaction::Invoke(InvokeTransaction {
             version: 0_u8,
             hash: H256::from_low_u64_be(6),
             signature: bounded_vec![H256::from_low_u64_be(10), H256::from_low_u64_be(20), H256::from_low_u64_be(30)],
             sender_address: [0; 32],
             nonce: U256::zero(),
-            call_entrypoint: CallEntryPointWrapper::default(),
-            contract_class: None,
-            contract_address_salt: None,
-        },
-        Transaction {
+            max_fee: U256::zero(),
+            calldata: bounded_vec![],
+            entry_point_selector: None,
+        }),
+        Transaction::Invoke(InvokeTransaction {
             version: 0_u8,
             hash: H256::from_low_u64_be(28),
             signature: bounded_vec![H256::from_low_u64_be(40)],
             sender_address: [1; 32],
             nonce: U256::zero(),
-            call_entrypoint: CallEntryPointWrapper::default(),
-            contract_class: None,
-            contract_address_salt: None,
-        },
+            max_fee: U256::zero(),
+            calldata: bounded_vec![],
+            entry_point_selector: None,
+        }),
     ];
     let tx_com = calculate_transaction_commitment::<PedersenHasher>(&txs);
     let events = vec![EventWrapper::default(), EventWrapper::default()];
@@ -61,3 +65,90 @@ fn test_event_hash() {
         FieldElement::from_str("0x754233cddfc3670a8e9c47f714397312a0319691a8762a49351fad896b37462").unwrap()
     )
 }
+
+#[test]
+fn test_poseidon_hash() {
+    // Test vectors generated with cairo-lang v0.11.0.
+    assert_eq!(
+        <PoseidonHasher as CryptoHasher>::hash(
+            FieldElement::from_hex_be("0xb662f9017fa7956fd70e26129b1833e10ad000fd37b4d9f4e0ce6884b7bbe").unwrap(),
+            FieldElement::from_hex_be("0x1fe356bf76102cdae1bfbdc173602ead228b12904c00dad9cf16e035468bea").unwrap(),
+        ),
+        FieldElement::from_hex_be("0x75540825a6ecc5dc7d7c2f5f868164182742227f1367d66c43ee51ec7937a81").unwrap()
+    );
+    assert_eq!(
+        PoseidonHasher::compute_hash_on_elements(&[
+            FieldElement::from_hex_be("0x9bf52404586087391c5fbb42538692e7ca2149bac13c145ae4230a51a6fc47").unwrap(),
+            FieldElement::from_hex_be("0x40304159ee9d2d611120fbd7c7fb8020cc8f7a599bfa108e0e085222b862c0").unwrap(),
+            FieldElement::from_hex_be("0x46286e4f3c450761d960d6a151a9c0988f9e16f8a48d4c0a85817c009f806a").unwrap(),
+        ]),
+        FieldElement::from_hex_be("0x1ec38b38dc88bac7b0ed6ff6326f975a06a59ac601b417745fd412a5d38e4f7").unwrap()
+    );
+    assert_eq!(
+        PoseidonHasher::compute_hash_on_elements(&[
+            FieldElement::from_hex_be("0xbdace8883922662601b2fd197bb660b081fcf383ede60725bd080d4b5f2fd3").unwrap(),
+            FieldElement::from_hex_be("0x1eb1daaf3fdad326b959dec70ced23649cdf8786537cee0c5758a1a4229097").unwrap(),
+            FieldElement::from_hex_be("0x869ca04071b779d6f940cdf33e62d51521e19223ab148ef571856ff3a44ff1").unwrap(),
+            FieldElement::from_hex_be("0x533e6df8d7c4b634b1f27035c8676a7439c635e1fea356484de7f0de677930").unwrap(),
+        ]),
+        FieldElement::from_hex_be("0x2520b8f910174c3e650725baacad4efafaae7623c69a0b5513d75e500f36624").unwrap()
+    );
+}
+
+#[test]
+fn test_state_root() {
+    let contracts_root = H256::from_low_u64_be(1);
+    let no_classes = calculate_class_commitment(&[]);
+    assert_eq!(no_classes, H256::zero());
+    // Without Cairo 1 classes, the state root is the contracts tree root.
+    assert_eq!(calculate_state_root(contracts_root, no_classes), contracts_root);
+
+    // A single class is an edge from the root to its leaf.
+    let classes =
+        [(H256::from_low_u64_be(2), H256::from_low_u64_be(3)), (H256::from_low_u64_be(4), H256::from_low_u64_be(5))];
+    assert_eq!(
+        calculate_class_commitment(&classes[..1]),
+        H256::from_str("0x00b6438bfe8fa5b0a01f7d1d8699fcf7ecc9bab8f7ef4db8e2ebf9383e591572").unwrap()
+    );
+
+    let class_commitment = calculate_class_commitment(&classes);
+    assert_eq!(
+        class_commitment,
+        H256::from_str("0x06c2af81098a3f9d7c520b04c5cd88d5be7150313ea392d51ba3b331844aaf66").unwrap()
+    );
+    // The commitment does not depend on the order of the classes.
+    assert_eq!(calculate_class_commitment(&[classes[1], classes[0]]), class_commitment);
+    assert_eq!(
+        calculate_state_root(contracts_root, class_commitment),
+        H256::from_str("0x01af0858415e7a0113bfbbe658d50d7acb9859954b32e73f0e8fbec7495c1e01").unwrap()
+    );
+}
+
+#[test]
+fn test_class_commitment_is_updated_incrementally() {
+    let mut storage = BTreeMap::new();
+    let class_commitment =
+        update_class_commitment(&mut storage, H256::zero(), H256::from_low_u64_be(2), H256::from_low_u64_be(3))
+            .unwrap();
+    let class_commitment =
+        update_class_commitment(&mut storage, class_commitment, H256::from_low_u64_be(4), H256::from_low_u64_be(5))
+            .unwrap();
+
+    assert_eq!(
+        class_commitment,
+        calculate_class_commitment(&[
+            (H256::from_low_u64_be(2), H256::from_low_u64_be(3)),
+            (H256::from_low_u64_be(4), H256::from_low_u64_be(5))
+        ])
+    );
+    // The nodes of the tree must be read from the storage.
+    assert_eq!(
+        update_class_commitment(
+            &mut BTreeMap::new(),
+            class_commitment,
+            H256::from_low_u64_be(6),
+            H256::from_low_u64_be(7)
+        ),
+        Err(StoredTreeError::MissingNode(class_commitment))
+    );
+}
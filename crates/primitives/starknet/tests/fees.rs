@@ -0,0 +1,40 @@
+use blockifier::execution::entry_point::ExecutionResources;
+use mp_starknet::fees::{calculate_l1_gas_usage, calculate_tx_fee};
+
+fn execution_resources(n_steps: usize, builtins: &[(&str, usize)]) -> ExecutionResources {
+    let mut resources = ExecutionResources::default();
+    resources.vm_resources.n_steps = n_steps;
+    resources
+        .vm_resources
+        .builtin_instance_counter
+        .extend(builtins.iter().map(|(name, usage)| (name.to_string(), *usage)));
+    resources
+}
+
+#[test]
+fn l1_gas_usage_is_driven_by_the_most_used_resource() {
+    // 1000 steps cost 10 gas, 20 pedersen builtins cost 6.4 gas.
+    let resources = execution_resources(1000, &[("pedersen_builtin", 20)]);
+    assert_eq!(calculate_l1_gas_usage(&[&resources]), 10);
+
+    // 40 pedersen builtins cost 12.8 gas, rounded up.
+    let resources = execution_resources(1000, &[("pedersen_builtin", 40)]);
+    assert_eq!(calculate_l1_gas_usage(&[&resources]), 13);
+}
+
+#[test]
+fn l1_gas_usage_adds_up_the_phases_of_an_execution() {
+    let validate_resources = execution_resources(600, &[]);
+    let execute_resources = execution_resources(600, &[]);
+
+    assert_eq!(calculate_l1_gas_usage(&[&validate_resources, &execute_resources]), 12);
+}
+
+#[test]
+fn tx_fee_is_the_l1_gas_usage_times_the_gas_price() {
+    let resources = execution_resources(1000, &[]);
+
+    assert_eq!(calculate_tx_fee(&[&resources], 0), 0);
+    assert_eq!(calculate_tx_fee(&[&resources], 100), 1000);
+    assert_eq!(calculate_tx_fee(&[], 100), 0);
+}
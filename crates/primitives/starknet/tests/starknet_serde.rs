@@ -17,7 +17,7 @@ fn test_missing_not_optional_field() {
       }
     }"#;
 
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::FailedToParse(_))));
 }
 
@@ -39,7 +39,7 @@ fn test_invalid_number_format() {
       }
     }"#;
 
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::FailedToParse(_))));
 }
 
@@ -61,7 +61,7 @@ fn test_invalid_format_for_h256() {
         "caller_address": "02356b628D108863BAf8644c945d97bAD70190AF5957031f4852d00D0F690a77"
       }
     }"#;
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::InvalidHash(_))));
 
     // Hash invalid hexa
@@ -80,7 +80,7 @@ fn test_invalid_format_for_h256() {
         "caller_address": "02356b628D108863BAf8644c945d97bAD70190AF5957031f4852d00D0F690a77"
       }
     }"#;
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::InvalidHash(_))));
 }
 
@@ -102,7 +102,7 @@ fn test_invalid_format_for_address() {
         "caller_address": "02356b628D108863BAf8644c945d97bAD70190AF5957031f4852d00D0F690a77"
       }
     }"#;
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::InvalidSenderAddress(_))));
 
     // No valid hexa
@@ -121,7 +121,7 @@ fn test_invalid_format_for_address() {
         "caller_address": "02356b628D108863BAf8644c945d97bAD70190AF5957031f4852d00D0F690a77"
       }
     }"#;
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(transaction, Err(DeserializeTransactionError::InvalidSenderAddress(_))));
 }
 
@@ -143,7 +143,7 @@ fn test_missing_optional_field_no_error() {
       }
     }"#;
 
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(transaction.is_ok(), "Expected no error because class_hash in call_entrypoint is optional");
 }
 
@@ -165,7 +165,7 @@ fn test_wrong_entrypoint_type() {
       }
     }"#;
 
-    let transaction = transaction_from_json(json_content, &[]);
+    let transaction = transaction_from_json(json_content);
     assert!(matches!(
         transaction,
         Err(DeserializeTransactionError::InvalidCallEntryPoint(DeserializeCallEntrypointError::InvalidEntryPointType))
@@ -0,0 +1,25 @@
+use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::{State, StateReader};
+use mp_starknet::state::{commit_state_diff, DictStateReader, ParentState};
+use starknet_api::api_core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+#[test]
+fn changes_on_top_of_a_state_are_only_visible_once_committed() {
+    let mut state = CachedState::new(DictStateReader::default());
+    let contract_address = ContractAddress::try_from(StarkFelt::from(1)).unwrap();
+    let key = StorageKey::try_from(StarkFelt::from(2)).unwrap();
+
+    let mut child_state = CachedState::new(ParentState(&mut state));
+    child_state.set_storage_at(contract_address, key, StarkFelt::from(3));
+    child_state.increment_nonce(contract_address).unwrap();
+    let state_diff = child_state.to_state_diff();
+
+    // Dropped changes are never applied.
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), StarkFelt::default());
+
+    commit_state_diff(&mut state, state_diff).unwrap();
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), StarkFelt::from(3));
+    assert_eq!(state.get_nonce_at(contract_address).unwrap().0, StarkFelt::from(1));
+}
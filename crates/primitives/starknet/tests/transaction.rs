@@ -1,41 +1,168 @@
+use std::str::FromStr;
+
 use frame_support::bounded_vec;
-use mp_starknet::transaction::types::{EventError, EventWrapper, Transaction, TransactionReceiptWrapper, TxType};
-use sp_core::{H256, U256};
-use starknet_api::api_core::{ContractAddress, PatriciaKey};
+use mp_starknet::block::CHAIN_ID;
+use mp_starknet::transaction::types::{
+    DeclareTransaction, DeployAccountTransaction, EventError, EventWrapper, ExecutionOutputError, InvokeTransaction,
+    L1HandlerTransaction, MessageToL1Wrapper, Transaction, TransactionReceiptWrapper, TxType,
+};
+use sp_core::{H160, H256, U256};
+use starknet_api::api_core::{ContractAddress, EthAddress, PatriciaKey};
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::patricia_key;
 use starknet_api::transaction::{
-    Event, EventContent, EventData, EventKey, Fee, InvokeTransactionOutput, TransactionHash, TransactionOutput,
-    TransactionReceipt,
+    DeclareTransaction as StarknetDeclareTransaction, Event, EventContent, EventData, EventKey, Fee,
+    InvokeTransactionOutput, InvokeTransactionV1 as StarknetInvokeTransactionV1, L2ToL1Payload, MessageToL1,
+    TransactionHash, TransactionOutput, TransactionReceipt,
 };
 
 #[test]
 fn verify_tx_version_passes_for_valid_version() {
-    let tx = Transaction {
+    let tx = Transaction::Invoke(InvokeTransaction {
         version: 1_u8,
         hash: H256::from_low_u64_be(6),
         signature: bounded_vec![H256::from_low_u64_be(10), H256::from_low_u64_be(20), H256::from_low_u64_be(30)],
         sender_address: [0; 32],
         nonce: U256::zero(),
-        ..Transaction::default()
-    };
+        ..InvokeTransaction::default()
+    });
 
-    assert!(tx.verify_tx_version(&TxType::InvokeTx).is_ok())
+    assert!(tx.verify_tx_version().is_ok())
 }
 
 #[test]
-fn verify_tx_version_fails_for_invalid_version() {
-    let tx = Transaction {
+fn verify_tx_version_passes_for_legacy_invoke_version() {
+    let tx = Transaction::Invoke(InvokeTransaction {
         version: 0_u8,
+        entry_point_selector: Some(H256::from_low_u64_be(1)),
+        ..InvokeTransaction::default()
+    });
+
+    assert!(tx.verify_tx_version().is_ok());
+    assert!(!tx.uses_account_nonce());
+}
+
+#[test]
+fn verify_tx_version_fails_for_invalid_version() {
+    let tx = Transaction::Invoke(InvokeTransaction {
+        version: 2_u8,
         hash: H256::from_low_u64_be(6),
         signature: bounded_vec![H256::from_low_u64_be(10), H256::from_low_u64_be(20), H256::from_low_u64_be(30)],
         sender_address: [0; 32],
         nonce: U256::zero(),
-        ..Transaction::default()
+        ..InvokeTransaction::default()
+    });
+
+    assert!(tx.verify_tx_version().is_err())
+}
+
+#[test]
+fn verify_tx_version_passes_for_legacy_declare_version() {
+    let tx = Transaction::Declare(DeclareTransaction { version: 0_u8, ..DeclareTransaction::default() });
+
+    assert_eq!(tx.tx_type(), TxType::DeclareTx);
+    assert!(tx.verify_tx_version().is_ok())
+}
+
+#[test]
+fn max_fee_is_carried_to_the_starknet_transaction() {
+    let tx = InvokeTransaction { max_fee: U256::from(1_000), ..InvokeTransaction::default() };
+    let starknet_tx: StarknetInvokeTransactionV1 = (&tx).try_into().unwrap();
+
+    assert_eq!(starknet_tx.max_fee, Fee(1_000));
+
+    let tx = InvokeTransaction { max_fee: U256::MAX, ..InvokeTransaction::default() };
+    assert!(TryInto::<StarknetInvokeTransactionV1>::try_into(&tx).is_err());
+}
+
+#[test]
+fn declare_v2_is_not_supported() {
+    let tx = DeclareTransaction { version: 2_u8, ..DeclareTransaction::default() };
+    assert!(TryInto::<StarknetDeclareTransaction>::try_into(&tx).is_err());
+
+    let tx = Transaction::Declare(tx);
+    assert!(tx.verify_tx_version().is_err());
+}
+
+#[test]
+fn message_to_l1_hash_matches_the_l1_message_hash() {
+    let message = MessageToL1Wrapper {
+        from_address: H256::from_low_u64_be(1).to_fixed_bytes(),
+        to_address: H160::from_low_u64_be(2),
+        payload: bounded_vec![H256::from_low_u64_be(3), H256::from_low_u64_be(4)],
     };
 
-    assert!(tx.verify_tx_version(&TxType::InvokeTx).is_err())
+    // keccak256(abi.encodePacked(from_address, to_address, payload.length, payload))
+    assert_eq!(
+        message.hash(),
+        H256::from_str("0x2cac3db3b1d4d30a6799a472c477b4a01a3a4bc43fd92f1e6506ce82d7d810dd").unwrap()
+    );
+}
+
+#[test]
+fn transaction_hashes_commit_to_the_fields_of_each_transaction_type() {
+    let hash = |transaction: Transaction| transaction.compute_hash(CHAIN_ID).unwrap();
+    let felt = |hex: &str| H256::from_str(&format!("{hex:0>64}")).unwrap();
+    let address = |x: u64| H256::from_low_u64_be(x).to_fixed_bytes();
+
+    let invoke = InvokeTransaction {
+        version: 1,
+        sender_address: address(1),
+        nonce: U256::from(2),
+        max_fee: U256::from(3),
+        calldata: bounded_vec![U256::from(4), U256::from(5)],
+        ..InvokeTransaction::default()
+    };
+    assert_eq!(hash(invoke.clone().into()), felt("1029a4607e3012b6aa6abd28e83ad7630a4e6754a837dc91e49f176941dc0d7"));
+    // Version 0 invoke transactions commit to the entrypoint instead of the nonce.
+    assert_eq!(
+        hash(InvokeTransaction { version: 0, entry_point_selector: Some(H256::from_low_u64_be(6)), ..invoke }.into()),
+        felt("2ddf629d06bf9c77ef8f627870dd73866fac7f4bc836f0f4b692dee67cc2830")
+    );
+
+    let declare = DeclareTransaction {
+        version: 1,
+        sender_address: address(1),
+        nonce: U256::from(2),
+        max_fee: U256::from(3),
+        class_hash: address(7),
+        ..DeclareTransaction::default()
+    };
+    assert_eq!(hash(declare.clone().into()), felt("704719d071a88a66ae8ffed09e1e4ac1b266210d19d8bcb231c5c6d5ee3d68b"));
+    assert_eq!(
+        hash(DeclareTransaction { version: 0, ..declare }.into()),
+        felt("10a6e9ce4ebc4db0e34917d9c7df458818de293c5bb513dc519eb2b881e4f4c")
+    );
+
+    let deploy_account = DeployAccountTransaction {
+        version: 1,
+        nonce: U256::from(2),
+        max_fee: U256::from(3),
+        contract_address: address(10),
+        class_hash: address(7),
+        contract_address_salt: H256::from_low_u64_be(8),
+        constructor_calldata: bounded_vec![U256::from(9)],
+        ..DeployAccountTransaction::default()
+    };
+    assert_eq!(hash(deploy_account.into()), felt("21a1f8b0bc21c6c174719876fd89abcfe868f752d0e697afaa89664d6edb6e9"));
+
+    let l1_handler = L1HandlerTransaction {
+        version: 0,
+        nonce: U256::from(2),
+        contract_address: address(10),
+        entry_point_selector: H256::from_low_u64_be(6),
+        calldata: bounded_vec![U256::from(4), U256::from(5)],
+        ..L1HandlerTransaction::default()
+    };
+    assert_eq!(hash(l1_handler.into()), felt("6cfd7029f15dc8150e10dd7011ddb9baa90041c48aabbbddf3ea94abb6a3680"));
+}
+
+#[test]
+fn transaction_hashes_reject_values_outside_of_the_field() {
+    let invoke = InvokeTransaction { version: 1, max_fee: U256::MAX, ..InvokeTransaction::default() };
+
+    assert!(Transaction::from(invoke).compute_hash(CHAIN_ID).is_err());
 }
 
 #[test]
@@ -56,12 +183,18 @@ fn test_try_into_transaction_receipt_wrapper() {
         },
     };
 
+    let message = MessageToL1 {
+        from_address: ContractAddress(patricia_key!("0x1")),
+        to_address: EthAddress::try_from(StarkFelt::from(2)).unwrap(),
+        payload: L2ToL1Payload(vec![StarkFelt::from(3), StarkFelt::from(4)]),
+    };
+
     // Create a sample TransactionReceipt
     let transaction_receipt = &TransactionReceipt {
         transaction_hash: TransactionHash(StarkFelt::new([1; 32]).unwrap()),
         output: TransactionOutput::Invoke(InvokeTransactionOutput {
             actual_fee: Fee(0),
-            messages_sent: vec![],
+            messages_sent: vec![message],
             events: vec![event1.clone(), event2.clone()],
         }),
         block_hash: BlockHash(StarkFelt::new([0; 32]).unwrap()),
@@ -69,7 +202,7 @@ fn test_try_into_transaction_receipt_wrapper() {
     };
 
     // Convert TransactionReceipt to TransactionReceiptWrapper
-    let result: Result<TransactionReceiptWrapper, EventError> = transaction_receipt.try_into();
+    let result: Result<TransactionReceiptWrapper, ExecutionOutputError> = transaction_receipt.try_into();
 
     // Check if the conversion is successful
     assert!(result.is_ok());
@@ -77,10 +210,19 @@ fn test_try_into_transaction_receipt_wrapper() {
     let transaction_receipt_wrapper = result.unwrap();
     let events = transaction_receipt_wrapper.events;
 
-    // Check if the transaction hash, actual fee, and tx type are correctly converted
+    // Check if the transaction hash and actual fee are correctly converted
     assert_eq!(transaction_receipt_wrapper.transaction_hash, H256::from_slice(&[1; 32]));
     assert_eq!(transaction_receipt_wrapper.actual_fee, U256::from(0));
-    assert_eq!(transaction_receipt_wrapper.tx_type, TxType::InvokeTx);
+
+    // Check if the messages are correctly converted
+    assert_eq!(
+        transaction_receipt_wrapper.messages_sent.into_inner(),
+        vec![MessageToL1Wrapper {
+            from_address: H256::from_low_u64_be(1).to_fixed_bytes(),
+            to_address: H160::from_low_u64_be(2),
+            payload: bounded_vec![H256::from_low_u64_be(3), H256::from_low_u64_be(4)],
+        }]
+    );
 
     // Check if the events are correctly converted
     let event_wrapper1 = EventWrapper::builder()
@@ -128,9 +270,9 @@ fn test_try_into_transaction_receipt_wrapper_with_too_many_events() {
     };
 
     // Convert TransactionReceipt to TransactionReceiptWrapper
-    let result: Result<TransactionReceiptWrapper, EventError> = transaction_receipt.try_into();
+    let result: Result<TransactionReceiptWrapper, ExecutionOutputError> = transaction_receipt.try_into();
 
     // Check if the conversion fails with the expected error
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), EventError::TooManyEvents);
+    assert_eq!(result.unwrap_err(), ExecutionOutputError::Event(EventError::TooManyEvents));
 }
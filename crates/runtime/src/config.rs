@@ -79,6 +79,10 @@ parameter_types! {
         ::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
     pub const SS58Prefix: u8 = 42;
     pub FeeMultiplier: Multiplier = Multiplier::one();
+    /// Number of blocks a validated Starknet transaction stays in the pool before it is revalidated.
+    pub const TransactionLongevity: u64 = HOURS as u64;
+    /// Price of a unit of L1 gas, in the fee token, used to compute the Starknet transaction fees.
+    pub const L1GasPrice: u128 = 100_000_000_000;
 }
 
 // This storage item will be used to check if we are in the manual sealing mode
@@ -21,12 +21,17 @@ pub use frame_support::weights::constants::{
 pub use frame_support::weights::{IdentityFee, Weight};
 pub use frame_support::{construct_runtime, parameter_types, StorageValue};
 pub use frame_system::Call as SystemCall;
-use mp_starknet::execution::ContractAddressWrapper;
+use mp_starknet::execution::{ClassHashWrapper, ContractAddressWrapper, ContractClassWrapper};
+use mp_starknet::transaction::types::{
+    EventWrapper, MessageToL1Wrapper, SimulatedTransactionWrapper, SimulationFlags, Transaction,
+    TransactionReceiptWrapper, TransactionTraceWrapper,
+};
 pub use pallet_balances::Call as BalancesCall;
 use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 /// Import the StarkNet pallet.
 pub use pallet_starknet;
 use pallet_starknet::types::StarkFeltWrapper;
+pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -272,8 +277,63 @@ impl_runtime_apis! {
             Starknet::current_block()
         }
 
-        fn call(address: ContractAddressWrapper, function_selector: H256, calldata: Vec<U256>) -> Result<Vec<StarkFeltWrapper>, DispatchError> {
-            Starknet::call_contract(address, function_selector, calldata)
+        fn call(pending_transactions: Vec<Transaction>, address: ContractAddressWrapper, function_selector: H256, calldata: Vec<U256>) -> Result<Vec<StarkFeltWrapper>, DispatchError> {
+            Starknet::call_contract(pending_transactions, address, function_selector, calldata)
+        }
+
+        fn events() -> Vec<(u32, EventWrapper)> {
+            Starknet::block_events().into_inner()
+        }
+
+        fn messages_to_l1() -> Vec<(u32, H256, MessageToL1Wrapper)> {
+            Starknet::block_messages_to_l1().into_inner()
+        }
+
+        fn transaction_receipts() -> Vec<TransactionReceiptWrapper> {
+            Starknet::block_receipts().into_inner()
+        }
+
+        fn declared_classes() -> Vec<(ClassHashWrapper, Option<ClassHashWrapper>)> {
+            Starknet::block_declared_classes().into_inner()
+        }
+
+        fn storage_diffs() -> Vec<(ContractAddressWrapper, H256, U256)> {
+            Starknet::block_storage_diffs().into_inner()
+        }
+
+        fn extrinsic_filter(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<Transaction> {
+            xts.into_iter().filter_map(|xt| match xt.function {
+                RuntimeCall::Starknet(pallet_starknet::Call::invoke { transaction }) => Some(transaction.into()),
+                RuntimeCall::Starknet(pallet_starknet::Call::declare { transaction }) => Some(transaction.into()),
+                RuntimeCall::Starknet(pallet_starknet::Call::deploy_account { transaction }) => Some(transaction.into()),
+                RuntimeCall::Starknet(pallet_starknet::Call::consume_l1_message { transaction }) => Some(transaction.into()),
+                _ => None,
+            }).collect()
+        }
+
+        fn trace_transactions(transactions: Vec<Transaction>, target: Option<H256>) -> Result<Vec<TransactionTraceWrapper>, DispatchError> {
+            Starknet::trace_transactions(transactions, target)
+        }
+
+        fn simulate_transactions(pending_transactions: Vec<Transaction>, transactions: Vec<Transaction>, simulation_flags: SimulationFlags) -> Result<Vec<SimulatedTransactionWrapper>, DispatchError> {
+            Starknet::simulate_transactions(pending_transactions, transactions, simulation_flags)
+        }
+
+        fn contract_class_by_class_hash(class_hash: ClassHashWrapper) -> Option<ContractClassWrapper> {
+            // The storage map returns an empty class for unknown class hashes.
+            let contract_class = Starknet::contract_class_by_class_hash(class_hash);
+            (contract_class != ContractClassWrapper::default()).then_some(contract_class)
+        }
+
+        fn convert_transaction(transaction: Transaction) -> <Block as BlockT>::Extrinsic {
+            let call = match transaction {
+                Transaction::Invoke(transaction) => pallet_starknet::Call::invoke { transaction },
+                Transaction::Declare(transaction) => pallet_starknet::Call::declare { transaction },
+                Transaction::DeployAccount(transaction) => pallet_starknet::Call::deploy_account { transaction },
+                Transaction::L1Handler(transaction) => pallet_starknet::Call::consume_l1_message { transaction },
+            };
+
+            UncheckedExtrinsic::new_unsigned(RuntimeCall::Starknet(call))
         }
     }
 
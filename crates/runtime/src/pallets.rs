@@ -143,6 +143,8 @@ impl pallet_starknet::Config for Runtime {
     type StateRoot = pallet_starknet::state_root::IntermediateStateRoot<Self>;
     type SystemHash = mp_starknet::crypto::hash::pedersen::PedersenHasher;
     type TimestampProvider = Timestamp;
+    type TransactionLongevity = TransactionLongevity;
+    type L1GasPrice = L1GasPrice;
 }
 
 /// A stateless module with helpers for dispatch management which does no re-authentication.